@@ -0,0 +1,331 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::ops::{Bound, Range, RangeBounds};
+
+use crate::{options::Style, writer::WriteHexdump, LineFormatter, RowBuffer};
+
+/// A labeled range of bytes. The bytes are highlighted in the dump and the
+/// label is listed to the right of the row where the range starts.
+///
+/// ```
+/// use hxd::{AsHexd, annotation::Highlight, options::{HexdOptionsBuilder, Spacing}};
+///
+/// let packet = b"\x01\x00\x05hello";
+///
+/// let dump = packet.hexd()
+///     .ungrouped(8, Spacing::Normal)
+///     .annotate(1..3, "length", Highlight::Bracket)
+///     .annotate(3.., "payload", Highlight::Underline)
+///     .dump_to::<String>();
+/// assert_eq!(dump, concat!(
+///     "00000000: 01 00 05 68 65 6C 6C 6F |...hello|  a: length, b: payload\n",
+///     "             [aaa] bbbbbbbbbbbbbb   []bbbbb\n",
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// The offsets of the annotated bytes in the input. Like
+    /// [`print_range`](crate::options::HexdOptions::print_range), these are
+    /// not affected by the [index offset](crate::options::HexdOptions::index_offset).
+    pub range: Range<usize>,
+
+    /// The text listed in the legend.
+    pub label: String,
+
+    /// How the annotated bytes are highlighted.
+    pub highlight: Highlight,
+}
+
+impl Annotation {
+    /// Construct a new annotation of the bytes in `range`.
+    pub fn new<R: RangeBounds<usize>, L: Into<String>>(
+        range: R,
+        label: L,
+        highlight: Highlight,
+    ) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(s) => *s,
+            Bound::Excluded(s) => s.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(e) => e.saturating_add(1),
+            Bound::Excluded(e) => *e,
+            Bound::Unbounded => usize::MAX,
+        };
+        Self {
+            range: start..end,
+            label: label.into(),
+            highlight,
+        }
+    }
+}
+
+/// The ways an [`Annotation`] can be highlighted.
+///
+/// Underlined and bracketed ranges are drawn on lines below each row, using
+/// the key of the annotation listed in the legend. The letters of keys after
+/// `Z` (`aa`, `ab`, …) are repeated along the range. Ranges which overlap
+/// are drawn on separate lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Highlight {
+    /// The bytes are underlined with the key of the annotation.
+    #[default]
+    Underline,
+
+    /// The bytes are underlined with the key of the annotation, between a
+    /// `[` under the first byte and a `]` under the last byte.
+    Bracket,
+
+    /// The bytes are rendered in the given style. The label is listed in
+    /// the same style instead of having a key. This style takes precedence
+    /// over the [palette](crate::options::Palette), but not over the
+    /// highlighting of changed bytes in a [diff](crate::diff::HexdDiff).
+    Color(Style),
+}
+
+/// The annotations of a dump along with their layout.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Annotations {
+    list: Vec<Annotation>,
+    /// The line below each row on which an annotation is drawn,
+    /// or `None` if it is colored.
+    lanes: Vec<Option<usize>>,
+    lane_count: usize,
+    /// The keys of the annotations which are drawn below the rows.
+    keys: Vec<Option<String>>,
+    /// Whether the label of an annotation has been listed yet.
+    listed: Vec<bool>,
+}
+
+impl Annotations {
+    pub(crate) fn new(list: Vec<Annotation>) -> Self {
+        let mut lanes = vec![None; list.len()];
+        let mut keys = vec![None; list.len()];
+
+        let mut drawn = (0..list.len())
+            .filter(|i| !matches!(list[*i].highlight, Highlight::Color(_)))
+            .collect::<Vec<_>>();
+        for (k, i) in drawn.iter().enumerate() {
+            keys[*i] = Some(key_for(k));
+        }
+
+        // assign each range to the first line which is free at its start
+        drawn.sort_by_key(|i| list[*i].range.start);
+        let mut lane_ends: Vec<usize> = Vec::new();
+        for i in drawn {
+            let range = &list[i].range;
+            match lane_ends.iter().position(|end| *end <= range.start) {
+                Some(lane) => {
+                    lane_ends[lane] = range.end;
+                    lanes[i] = Some(lane);
+                }
+                None => {
+                    lanes[i] = Some(lane_ends.len());
+                    lane_ends.push(range.end);
+                }
+            }
+        }
+
+        Self {
+            lane_count: lane_ends.len(),
+            listed: vec![false; list.len()],
+            list,
+            lanes,
+            keys,
+        }
+    }
+
+    pub(crate) fn lane_count(&self) -> usize {
+        self.lane_count
+    }
+
+    /// Return true if any annotated byte is in `row`.
+    pub(crate) fn intersects(&self, row: &RowBuffer) -> bool {
+        self.list.iter().any(|a| overlaps(&a.range, row))
+    }
+
+    /// Return the style of the last colored annotation of the byte at `pos`.
+    pub(crate) fn style_at(&self, pos: usize) -> Option<Style> {
+        self.list
+            .iter()
+            .rev()
+            .filter(|a| a.range.contains(&pos))
+            .find_map(|a| match a.highlight {
+                Highlight::Color(style) => Some(style),
+                _ => None,
+            })
+    }
+
+    /// Return the annotation drawn on `lane` which contains the byte at `pos`.
+    fn in_lane_at(&self, lane: usize, pos: usize) -> Option<(usize, &Annotation)> {
+        self.list
+            .iter()
+            .enumerate()
+            .find(|(i, a)| self.lanes[*i] == Some(lane) && a.range.contains(&pos))
+    }
+}
+
+/// Keys run from `a` to `z` and from `A` to `Z`, and then continue with
+/// longer keys (`aa`, `ab`, …) like the columns of a spreadsheet.
+fn key_for(n: usize) -> String {
+    let letter = |k: usize| match k {
+        0..=25 => (b'a' + k as u8) as char,
+        _ => (b'A' + (k - 26) as u8) as char,
+    };
+    let mut key = Vec::new();
+    let mut n = n + 1;
+    while n > 0 {
+        n -= 1;
+        key.push(letter(n % 52));
+        n /= 52;
+    }
+    key.iter().rev().collect()
+}
+
+/// Draws the key of an annotation along its range, repeating the
+/// characters of keys which are longer than one.
+struct KeyPen {
+    annotation: usize,
+    key: Vec<u8>,
+    drawn: usize,
+}
+
+impl KeyPen {
+    fn new(annotations: &Annotations, annotation: usize) -> Self {
+        let key = annotations.keys[annotation].as_deref().unwrap_or("?");
+        Self {
+            annotation,
+            key: key.as_bytes().to_vec(),
+            drawn: 0,
+        }
+    }
+
+    /// Switch to the key of `annotation`, unless it is being drawn already.
+    fn switch<'p>(
+        pen: &'p mut Option<Self>,
+        annotations: &Annotations,
+        annotation: usize,
+    ) -> &'p mut Self {
+        if pen.as_ref().is_some_and(|p| p.annotation != annotation) {
+            *pen = None;
+        }
+        pen.get_or_insert_with(|| Self::new(annotations, annotation))
+    }
+
+    fn next(&mut self) -> u8 {
+        let c = self.key[self.drawn % self.key.len()];
+        self.drawn += 1;
+        c
+    }
+}
+
+fn overlaps(range: &Range<usize>, row: &RowBuffer) -> bool {
+    range.start < row.elt_index + row.length && row.elt_index < range.end
+}
+
+impl<W: WriteHexdump> LineFormatter<W> {
+    /// Write the labels of the annotations which start in `row`, or which
+    /// started before it and have not been listed yet.
+    pub(crate) fn write_legend(&mut self, row: &RowBuffer) {
+        let mut first = true;
+        for i in 0..self.annotations.list.len() {
+            if self.annotations.listed[i] || !overlaps(&self.annotations.list[i].range, row) {
+                continue;
+            }
+            self.annotations.listed[i] = true;
+
+            self.push_slice(if first { b"  " } else { b", " });
+            first = false;
+
+            let label = self.annotations.list[i].label.clone();
+            match self.annotations.list[i].highlight {
+                Highlight::Color(style) => {
+                    self.push_style(style);
+                    self.push_slice(label.as_bytes());
+                    self.push_reset(style);
+                }
+                _ => {
+                    let key = self.annotations.keys[i].clone();
+                    self.push_slice(key.as_deref().unwrap_or("?").as_bytes());
+                    self.push_slice(b": ");
+                    self.push_slice(label.as_bytes());
+                }
+            }
+        }
+    }
+
+    /// Return true if an annotation drawn on `lane` has bytes in `row`.
+    pub(crate) fn lane_in_row(&self, row: &RowBuffer, lane: usize) -> bool {
+        let a = &self.annotations;
+        (0..a.list.len()).any(|i| a.lanes[i] == Some(lane) && overlaps(&a.list[i].range, row))
+    }
+
+    /// Write the part of `lane` which lies under `row`.
+    pub(crate) fn write_annotation_lane(&mut self, row: &RowBuffer, lane: usize) {
+        let cell_width = self.options.base.cell_width();
+        let elt_width = self.options.elt_width();
+        let pos_of = |f: &Self, i: usize| f.row_position(row, i).map(|p| row.elt_index + p);
+
+        let mut pen = None;
+        for i in 0..elt_width {
+            let pos = pos_of(self, i);
+            let found = pos.and_then(|pos| {
+                self.annotations
+                    .in_lane_at(lane, pos)
+                    .map(|(k, a)| (k, a.range.clone(), a.highlight))
+            });
+
+            let Some((k, range, highlight)) = found else {
+                for _ in 0..cell_width {
+                    self.push_slice(b" ");
+                }
+                self.write_cell_spacing(i);
+                continue;
+            };
+
+            let pos = pos.unwrap();
+            let pen = KeyPen::switch(&mut pen, &self.annotations, k);
+            let mut cell = [0u8; 8];
+            cell[..cell_width].fill_with(|| pen.next());
+            if highlight == Highlight::Bracket {
+                if pos == range.start {
+                    cell[0] = b'[';
+                }
+                if pos + 1 == range.end {
+                    cell[cell_width - 1] = b']';
+                }
+            }
+            self.push_slice(&cell[..cell_width]);
+
+            // the underline continues through the spacing between bytes
+            let continues =
+                i + 1 < elt_width && pos_of(self, i + 1).is_some_and(|next| range.contains(&next));
+            if continues {
+                self.write_cell_spacing_filled(i, || pen.next());
+            } else {
+                self.write_cell_spacing(i);
+            }
+        }
+
+        if self.options.show_ascii {
+            self.push_slice(b" ");
+            let mut pen = None;
+            for i in 0..elt_width {
+                let mark = pos_of(self, i)
+                    .and_then(|pos| {
+                        let (k, a) = self.annotations.in_lane_at(lane, pos)?;
+                        let key = KeyPen::switch(&mut pen, &self.annotations, k).next();
+                        let single = a.range.start == pos && pos + 1 == a.range.end;
+                        Some(match a.highlight {
+                            Highlight::Bracket if single => key,
+                            Highlight::Bracket if pos == a.range.start => b'[',
+                            Highlight::Bracket if pos + 1 == a.range.end => b']',
+                            _ => key,
+                        })
+                    })
+                    .unwrap_or(b' ');
+                self.push_slice(&[mark]);
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+use core::{cmp::min, convert::Infallible, future::poll_fn, ops::RangeBounds, pin::pin};
+
+use crate::{
+    annotation::{Annotation, Highlight},
+    options::{HexdOptions, HexdOptionsBuilder},
+    reader::{AsyncReadBytes, ReadBytes},
+    writer::WriteHexdump,
+    HexdumpLineWriter, ReadWriteError, MAX_BUFFER_SIZE,
+};
+
+/// Renders a hexdump of an [asynchronous byte stream](AsyncReadBytes).
+///
+/// Lines are written as soon as the bytes of their row have arrived, so a
+/// hexdump of a slow stream is written while the stream is still being read.
+/// The dump is an ordinary [`Future`](std::future::Future) which does not
+/// depend on any particular async runtime.
+///
+/// ```
+/// use hxd::{asynchronous::IntoAsyncHexd, options::{HexdOptionsBuilder, Spacing}};
+/// # use std::{future::Future, pin::pin, task::{Context, Poll, Waker}};
+/// # fn block_on<F: Future>(f: F) -> F::Output {
+/// #     let mut f = pin!(f);
+/// #     let mut cx = Context::from_waker(Waker::noop());
+/// #     loop {
+/// #         if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+/// #             return output;
+/// #         }
+/// #     }
+/// # }
+///
+/// let stream: &[u8] = b"async bytes";
+///
+/// let dump = block_on(stream.async_hexd().ungrouped(8, Spacing::Normal).dump_to::<String>());
+/// assert_eq!(dump.unwrap(), concat!(
+///     "00000000: 61 73 79 6E 63 20 62 79 |async by|\n",
+///     "00000008: 74 65 73                |tes     |\n",
+/// ));
+/// ```
+pub struct AsyncHexd<R: AsyncReadBytes> {
+    reader: R,
+    options: HexdOptions,
+    annotations: Vec<Annotation>,
+}
+
+impl<R: AsyncReadBytes> AsyncHexd<R> {
+    /// Construct a new [`AsyncHexd`] instance with the given reader and [default options](HexdOptions::default).
+    pub fn new(reader: R) -> Self {
+        Self::new_with_options(reader, HexdOptions::default())
+    }
+
+    /// Construct a new [`AsyncHexd`] instance with the given reader and options.
+    pub fn new_with_options(reader: R, options: HexdOptions) -> Self {
+        Self {
+            reader,
+            options,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Label the bytes in `range` and highlight them in the dump.
+    /// See [`Annotation`].
+    pub fn annotate<B: RangeBounds<usize>, L: Into<String>>(
+        self,
+        range: B,
+        label: L,
+        highlight: Highlight,
+    ) -> Self {
+        self.annotations([Annotation::new(range, label, highlight)])
+    }
+
+    /// Add several [annotations](Annotation) to the dump.
+    pub fn annotations<I: IntoIterator<Item = Annotation>>(mut self, annotations: I) -> Self {
+        self.annotations.extend(annotations);
+        self
+    }
+
+    /// Construct a default instance of `W` and write a hexdump to it, returning its output.
+    pub async fn dump_to<W: WriteHexdump + Default>(self) -> Result<W::Output, R::Error> {
+        self.dump_into(W::default()).await
+    }
+
+    /// Write a hexdump to an instance of `W` and return its output.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`FallibleHexd::dump_into`](crate::FallibleHexd::dump_into).
+    pub async fn dump_into<W: WriteHexdump>(self, writer: W) -> Result<W::Output, R::Error> {
+        let mut reader = pin!(self.reader);
+        let received = ReceivedBytes {
+            bytes: VecDeque::new(),
+            byte_hint: reader.total_byte_hint(),
+        };
+        let mut hlw = HexdumpLineWriter::new(received, writer, self.options.clone())
+            .annotated(self.annotations);
+        let mut chunk = [0u8; MAX_BUFFER_SIZE];
+
+        // the print range is skipped here, so that the line iterator
+        // does not have to wait for the skipped bytes
+        let skip = self.options.print_range.skip;
+        let mut skipped = 0usize;
+        while skipped < skip {
+            let len = min(skip - skipped, chunk.len());
+            let n = poll_fn(|cx| reader.as_mut().poll_next_n(cx, &mut chunk[..len])).await?;
+            if n == 0 {
+                break;
+            }
+            skipped += n;
+        }
+        let mut remaining = self
+            .options
+            .print_range
+            .limit
+            .map(|l| l.saturating_sub(skip));
+
+        let mut result = hlw.write_head();
+        let mut ended = false;
+        while result.is_ok() {
+            // a row is only read once it can be read in full, along with
+            // the bytes after it which may be needed to decode its text
+            let wanted =
+                hlw.rows.line_iterator.options.elt_width() + hlw.rows.line_iterator.context_len();
+            loop {
+                let received = &mut hlw.rows.line_iterator.reader.bytes;
+                if ended || received.len() >= wanted || remaining == Some(0) {
+                    break;
+                }
+                let len = min(wanted - received.len(), remaining.unwrap_or(usize::MAX));
+                let buf = &mut chunk[..min(len, MAX_BUFFER_SIZE)];
+                let n = poll_fn(|cx| reader.as_mut().poll_next_n(cx, buf)).await?;
+                received.extend(&buf[..n]);
+                remaining = remaining.map(|r| r - n);
+                ended = n == 0;
+            }
+
+            match hlw.write_next() {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) => result = Err(e),
+            }
+        }
+        let result = result.and_then(|_| hlw.write_tail());
+
+        Ok(WriteHexdump::consume(match result {
+            Ok(()) => Ok(hlw.formatter.writer),
+            Err(ReadWriteError::Write(e)) => Err(e),
+            Err(ReadWriteError::Read(e)) => match e {},
+        }))
+    }
+}
+
+/// [`AsyncHexd`] implements [`HexdOptionsBuilder`] to allow for fluent
+/// configuration.
+impl<R: AsyncReadBytes> HexdOptionsBuilder for AsyncHexd<R> {
+    fn map_options<F: FnOnce(HexdOptions) -> HexdOptions>(self, f: F) -> Self {
+        Self {
+            options: f(self.options),
+            ..self
+        }
+    }
+}
+
+/// Yield an owning [`AsyncHexd`] over an asynchronous byte stream.
+pub trait IntoAsyncHexd<R: AsyncReadBytes>: Sized {
+    fn into_async_hexd(self) -> AsyncHexd<R>;
+    fn async_hexd(self) -> AsyncHexd<R> {
+        self.into_async_hexd()
+    }
+}
+
+impl<R: AsyncReadBytes> IntoAsyncHexd<R> for R {
+    fn into_async_hexd(self) -> AsyncHexd<R> {
+        AsyncHexd::new(self)
+    }
+}
+
+/// The bytes received from the stream which have not been read by the
+/// line iterator yet. The stream is read ahead of the line iterator, so
+/// running out of bytes here means that the stream has ended.
+struct ReceivedBytes {
+    bytes: VecDeque<u8>,
+    byte_hint: Option<usize>,
+}
+
+impl ReadBytes for ReceivedBytes {
+    type Error = Infallible;
+
+    fn next_n<'buf>(&mut self, buf: &'buf mut [u8]) -> Result<&'buf [u8], Self::Error> {
+        let n = min(buf.len(), self.bytes.len());
+        for (b, r) in buf.iter_mut().zip(self.bytes.drain(..n)) {
+            *b = r;
+        }
+        Ok(&buf[..n])
+    }
+
+    /// The print range has already been skipped while reading the stream.
+    fn skip_n(&mut self, n: usize) -> Result<usize, Self::Error> {
+        Ok(n)
+    }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        self.byte_hint
+    }
+}
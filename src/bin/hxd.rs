@@ -0,0 +1,550 @@
+//! A command-line hexdump tool built on the `hxd` library.
+//!
+//! The flags follow `xxd` where possible, so `hxd -u -a` renders exactly what
+//! `v.hexd().dump()` prints with default options.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, ErrorKind, IsTerminal, Read, Write},
+    process::ExitCode,
+};
+
+use hxd::{
+    elf::Elf,
+    options::{
+        Base, Checksum, ElisionMarker, ElisionMode, Footer, GroupSize, Header, HexdOptions,
+        HexdOptionsBuilder, IndexBase, LeadingZeroChar, SourceFormat, SourceLanguage, Spacing,
+        TextEncoding,
+    },
+    parser::HexdParser,
+    reader::{IOReader, ReadBytes, SeekReader},
+    writer::IOWriter,
+    FallibleHexd, ReadWriteError,
+};
+
+const USAGE: &str = "\
+Usage: hxd [options] [infile [outfile]]
+
+Options:
+  -s, --skip <offset>          start at <offset> bytes into the input
+  -l, --length <len>           stop after <len> bytes
+  -c, --cols <cols>            bytes per row (default: 16)
+  -g, --group <bytes>          bytes per group: 0, 1, 2, 4, 8 or 16 (default: 2)
+      --byte-spacing <sp>      spacing between bytes in a group
+      --group-spacing <sp>     spacing between groups
+                               (<sp> is one of none, normal, wide, ultrawide)
+  -b, --binary                 binary digit dump
+  -o, --octal                  octal dump
+  -d, --decimal                decimal dump
+      --leading-zero <c>       leading zero character for octal and decimal
+                               dumps: zero, space or underscore
+  -u, --uppercase              use uppercase hex letters
+  -a, --autoskip               replace repeated rows with '*'
+      --elide <mode>           rows replaced by -a: uniform (rows of a single
+                               repeated group) or repeated (any row equal to
+                               the one before it) (default: uniform)
+      --min-elided <rows>      only replace runs of at least <rows> rows
+      --marker <marker>        line written in place of replaced rows: '*',
+                               counted, or a template in which {rows},
+                               {bytes}, {start} and {end} are replaced
+  -O, --offset <off>           add <off> to the displayed index
+  -A, --absolute-offset <off>  display the first dumped byte at index <off>
+      --header                 print a row of column offsets above the dump
+      --header-every <rows>    repeat the column offsets every <rows> rows
+      --footer                 print a summary of the dump below it
+      --checksum <sum>         include a checksum in the summary: crc32,
+                               adler32, sum8 or xor8 (implies --footer)
+      --index-base <base>      index column base: hex, decimal or octal
+                               (default: hex)
+      --index-width <digits>   write the index with exactly <digits> digits
+      --no-index               do not print the index column
+      --no-ascii               do not print the ASCII column
+      --unaligned              do not align rows when skipping
+      --encoding <enc>         text column encoding: ascii, latin1, cp437,
+                               ebcdic or utf8 (default: ascii)
+  -E, --ebcdic                 show the text column in EBCDIC
+  -R, --color <when>           color the output: always, auto or never
+                               (default: never)
+  -p, --plain                  output a continuous hex dump, 30 bytes per
+                               line (-c 0 writes a single line)
+  -i, --include                output a C include file (a C array)
+      --source <lang>          output source code in <lang>: c, rust,
+                               rust-bytes or python (default cols: 12)
+  -n, --name <name>            variable name for source output (default:
+                               derived from infile)
+      --section <name>         dump the section <name> of an ELF file,
+                               indexed by its address (may be repeated)
+      --list-sections          list the sections and segments of an ELF file
+  -r, --reverse                convert a hexdump (made with the same options)
+                               back into binary
+  -h, --help                   print this help
+  -V, --version                print the version
+
+Numbers may be given in decimal or with a 0x, 0o or 0b prefix.
+";
+
+struct Args {
+    options: HexdOptions,
+    reverse: bool,
+    sections: Vec<String>,
+    list_sections: bool,
+    infile: Option<String>,
+    outfile: Option<String>,
+}
+
+enum Command {
+    Run(Box<Args>),
+    Help,
+    Version,
+}
+
+fn parse_number(flag: &str, s: &str) -> Result<usize, String> {
+    let (digits, radix) = match s.get(..2) {
+        Some("0x") | Some("0X") => (&s[2..], 16),
+        Some("0o") | Some("0O") => (&s[2..], 8),
+        Some("0b") | Some("0B") => (&s[2..], 2),
+        _ => (s, 10),
+    };
+    usize::from_str_radix(digits, radix).map_err(|_| format!("invalid number for {flag}: '{s}'"))
+}
+
+fn parse_spacing(flag: &str, s: &str) -> Result<Spacing, String> {
+    match s {
+        "none" => Ok(Spacing::None),
+        "normal" => Ok(Spacing::Normal),
+        "wide" => Ok(Spacing::Wide),
+        "ultrawide" => Ok(Spacing::UltraWide),
+        _ => Err(format!("invalid spacing for {flag}: '{s}'")),
+    }
+}
+
+fn parse_leading_zero(flag: &str, s: &str) -> Result<LeadingZeroChar, String> {
+    match s {
+        "zero" => Ok(LeadingZeroChar::Zero),
+        "space" => Ok(LeadingZeroChar::Space),
+        "underscore" => Ok(LeadingZeroChar::Underscore),
+        _ => Err(format!("invalid leading zero character for {flag}: '{s}'")),
+    }
+}
+
+fn parse_language(flag: &str, s: &str) -> Result<SourceLanguage, String> {
+    match s {
+        "c" => Ok(SourceLanguage::C),
+        "rust" => Ok(SourceLanguage::RustArray),
+        "rust-bytes" => Ok(SourceLanguage::RustBytes),
+        "python" => Ok(SourceLanguage::PythonBytes),
+        _ => Err(format!("invalid language for {flag}: '{s}'")),
+    }
+}
+
+/// Derive a variable name from the input file name like `xxd -i` does,
+/// replacing any character which is not valid in an identifier with `_`.
+fn source_name(infile: Option<&str>, language: SourceLanguage) -> String {
+    let mut name: String = infile
+        .unwrap_or("data")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "__");
+    }
+    match language {
+        SourceLanguage::RustArray | SourceLanguage::RustBytes => name.to_ascii_uppercase(),
+        _ => name,
+    }
+}
+
+fn parse_encoding(flag: &str, s: &str) -> Result<TextEncoding, String> {
+    match s {
+        "ascii" => Ok(TextEncoding::Ascii),
+        "latin1" => Ok(TextEncoding::Latin1),
+        "cp437" => Ok(TextEncoding::Cp437),
+        "ebcdic" => Ok(TextEncoding::Ebcdic),
+        "utf8" => Ok(TextEncoding::Utf8),
+        _ => Err(format!("invalid encoding for {flag}: '{s}'")),
+    }
+}
+
+fn parse_checksum(flag: &str, s: &str) -> Result<Checksum, String> {
+    match s {
+        "crc32" => Ok(Checksum::Crc32),
+        "adler32" => Ok(Checksum::Adler32),
+        "sum8" => Ok(Checksum::Sum8),
+        "xor8" => Ok(Checksum::Xor8),
+        _ => Err(format!("invalid checksum for {flag}: '{s}'")),
+    }
+}
+
+fn parse_index_base(flag: &str, s: &str) -> Result<IndexBase, String> {
+    match s {
+        "hex" => Ok(IndexBase::Hex),
+        "decimal" => Ok(IndexBase::Decimal),
+        "octal" => Ok(IndexBase::Octal),
+        _ => Err(format!("invalid index base for {flag}: '{s}'")),
+    }
+}
+
+fn parse_elision_mode(flag: &str, s: &str) -> Result<ElisionMode, String> {
+    match s {
+        "uniform" => Ok(ElisionMode::Uniform),
+        "repeated" => Ok(ElisionMode::Repeated),
+        _ => Err(format!("invalid elision mode for {flag}: '{s}'")),
+    }
+}
+
+fn parse_elision_marker(marker: String) -> ElisionMarker {
+    match marker.as_str() {
+        "*" => ElisionMarker::Asterisk,
+        "counted" => ElisionMarker::Counted,
+        _ => ElisionMarker::Template(marker.into()),
+    }
+}
+
+fn group_size(flag: &str, bytes: usize) -> Result<GroupSize, String> {
+    match bytes {
+        1 => Ok(GroupSize::Byte),
+        2 => Ok(GroupSize::Short),
+        4 => Ok(GroupSize::Int),
+        8 => Ok(GroupSize::Long),
+        16 => Ok(GroupSize::ULong),
+        _ => Err(format!("invalid group size for {flag}: {bytes}")),
+    }
+}
+
+/// Short flags which take a value, e.g. `-s 16` or `-s16`.
+const VALUE_FLAGS: &[char] = &['s', 'l', 'c', 'g', 'O', 'A', 'R', 'n'];
+
+/// Split clustered short flags (`-ua`) into separate arguments (`-u -a`).
+fn expand_short_flags<I: Iterator<Item = String>>(args: I) -> impl Iterator<Item = String> {
+    args.flat_map(|arg| {
+        let mut chars = arg.chars();
+        match (chars.next(), chars.next()) {
+            (Some('-'), Some(c)) if c != '-' && arg.len() > 2 && !VALUE_FLAGS.contains(&c) => {
+                arg[1..].chars().map(|c| format!("-{c}")).collect()
+            }
+            _ => vec![arg],
+        }
+    })
+}
+
+fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = expand_short_flags(args);
+    let mut base = Base::Hex;
+    let mut leading_zero = None;
+    let mut cols = None;
+    let mut group = None;
+    let mut byte_spacing = None;
+    let mut group_spacing = None;
+    let mut skip = 0usize;
+    let mut length = None;
+    let mut options = HexdOptions::default().uppercase(false).autoskip(false);
+    let mut reverse = false;
+    let mut sections = Vec::new();
+    let mut list_sections = false;
+    let mut color = None;
+    let mut language = None;
+    let mut plain = false;
+    let mut name = None;
+    let mut files = Vec::new();
+
+    while let Some(arg) = args.next() {
+        // accept both `-s 16` and `-s16`, as well as `--skip=16`
+        let (flag, attached) = if let Some(long) = arg.strip_prefix("--") {
+            match long.split_once('=') {
+                Some((f, v)) => (format!("--{f}"), Some(v.to_string())),
+                None => (arg.clone(), None),
+            }
+        } else if arg.starts_with('-') && arg.len() > 2 {
+            (arg[..2].to_string(), Some(arg[2..].to_string()))
+        } else {
+            (arg.clone(), None)
+        };
+
+        let mut value = || -> Result<String, String> {
+            match attached.clone() {
+                Some(v) => Ok(v),
+                None => args
+                    .next()
+                    .ok_or_else(|| format!("missing value for {flag}")),
+            }
+        };
+
+        match flag.as_str() {
+            "-s" | "--skip" => skip = parse_number(&flag, &value()?)?,
+            "-l" | "--length" => length = Some(parse_number(&flag, &value()?)?),
+            "-c" | "--cols" => cols = Some(parse_number(&flag, &value()?)?),
+            "-g" | "--group" => group = Some(parse_number(&flag, &value()?)?),
+            "--byte-spacing" => byte_spacing = Some(parse_spacing(&flag, &value()?)?),
+            "--group-spacing" => group_spacing = Some(parse_spacing(&flag, &value()?)?),
+            "--leading-zero" => leading_zero = Some(parse_leading_zero(&flag, &value()?)?),
+            "-O" | "--offset" => options = options.relative_offset(parse_number(&flag, &value()?)?),
+            "-A" | "--absolute-offset" => {
+                options = options.absolute_offset(parse_number(&flag, &value()?)?)
+            }
+            "-b" | "--binary" => base = Base::Binary,
+            "-o" | "--octal" => base = Base::Octal(LeadingZeroChar::Zero),
+            "-d" | "--decimal" => base = Base::Decimal(LeadingZeroChar::Space),
+            "-u" | "--uppercase" => options = options.uppercase(true),
+            "-a" | "--autoskip" => options = options.autoskip(true),
+            "--elide" => options = options.elision_mode(parse_elision_mode(&flag, &value()?)?),
+            "--marker" => options = options.elision_marker(parse_elision_marker(value()?)),
+            "--min-elided" => options = options.min_elided_rows(parse_number(&flag, &value()?)?),
+            "--header" => options = options.header(Header::Top),
+            "--header-every" => {
+                options = options.header(Header::Every(parse_number(&flag, &value()?)?))
+            }
+            "--footer" => {
+                if options.footer == Footer::Hidden {
+                    options = options.footer(Footer::Summary(None))
+                }
+            }
+            "--checksum" => {
+                let checksum = parse_checksum(&flag, &value()?)?;
+                options = options.footer(Footer::Summary(Some(checksum)))
+            }
+            "--index-base" => options = options.index_base(parse_index_base(&flag, &value()?)?),
+            "--index-width" => {
+                let width = parse_number(&flag, &value()?)?;
+                options = options.index_width(width, Some(width))
+            }
+            "--no-index" => options = options.show_index(false),
+            "--no-ascii" => options = options.show_ascii(false),
+            "--unaligned" => options = options.aligned(false),
+            "--encoding" => options = options.encoding(parse_encoding(&flag, &value()?)?),
+            "-E" | "--ebcdic" => options = options.encoding(TextEncoding::Ebcdic),
+            "-R" | "--color" => color = Some(value()?),
+            "-p" | "--plain" | "--ps" | "--postscript" => plain = true,
+            "-i" | "--include" => language = Some(SourceLanguage::C),
+            "--source" => language = Some(parse_language(&flag, &value()?)?),
+            "-n" | "--name" => name = Some(value()?),
+            "--section" => sections.push(value()?),
+            "--list-sections" => list_sections = true,
+            "-r" | "--reverse" => reverse = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-" => files.push(arg),
+            f if f.starts_with('-') => return Err(format!("unknown option '{arg}'")),
+            _ => files.push(arg),
+        }
+    }
+
+    // Start from the preset for the chosen base, then apply explicit overrides.
+    options = match base {
+        Base::Hex => options.hexadecimal(),
+        Base::Binary => options.binary(),
+        Base::Octal(_) => options.octal(),
+        Base::Decimal(_) => options.decimal(),
+    };
+    if let Some(lzc) = leading_zero {
+        options.base = match options.base {
+            Base::Octal(_) => Base::Octal(lzc),
+            Base::Decimal(_) => Base::Decimal(lzc),
+            b => b,
+        };
+    }
+
+    let default_group = match base {
+        Base::Hex => 2,
+        _ => 1,
+    };
+    let source_cols = cols.unwrap_or(12);
+    let plain_cols = match cols {
+        Some(0) => None,
+        cols => Some(cols.unwrap_or(30)),
+    };
+    let cols = cols.unwrap_or(options.elt_width());
+    let group = group.unwrap_or(default_group);
+    // the hexdump layout does not apply to plain and source output
+    let hexdump_layout = !plain && language.is_none();
+    if cols == 0 && (hexdump_layout || language.is_some()) {
+        return Err("the number of columns must be greater than zero".to_string());
+    }
+
+    if hexdump_layout {
+        options = if group == 0 {
+            options.ungrouped(cols, byte_spacing.unwrap_or(Spacing::None))
+        } else {
+            if cols % group != 0 {
+                return Err(format!(
+                    "the number of columns ({cols}) must be a multiple of the group size ({group})"
+                ));
+            }
+            options.grouped(
+                (
+                    group_size("-g", group)?,
+                    byte_spacing.unwrap_or(Spacing::None),
+                ),
+                (cols / group, group_spacing.unwrap_or(Spacing::Normal)),
+            )
+        };
+    }
+
+    options = match length {
+        Some(length) => options.range(skip..skip.saturating_add(length)),
+        None => options.range(skip..),
+    };
+
+    if files.len() > 2 {
+        return Err("too many arguments".to_string());
+    }
+    let mut files = files
+        .into_iter()
+        .map(|f| if f == "-" { None } else { Some(f) });
+    let infile = files.next().flatten();
+    let outfile = files.next().flatten();
+
+    let color = match color.as_deref() {
+        None | Some("never") => false,
+        Some("always") => true,
+        Some("auto") => outfile.is_none() && io::stdout().is_terminal(),
+        Some(when) => return Err(format!("invalid value for --color: '{when}'")),
+    };
+    options = options.color(color);
+
+    if plain {
+        if language.is_some() {
+            return Err("plain and source output cannot be combined".to_string());
+        }
+        options = options.plain(plain_cols);
+    }
+
+    if reverse && (list_sections || !sections.is_empty()) {
+        return Err("ELF sections cannot be reversed".to_string());
+    }
+
+    if let Some(language) = language {
+        if reverse {
+            return Err("source output cannot be reversed".to_string());
+        }
+        let name = name.unwrap_or_else(|| source_name(infile.as_deref(), language));
+        options = options.format(SourceFormat::new(language, name).bytes_per_line(source_cols));
+    }
+    options.validate().map_err(|e| e.to_string())?;
+
+    Ok(Command::Run(Box::new(Args {
+        options,
+        reverse,
+        sections,
+        list_sections,
+        infile,
+        outfile,
+    })))
+}
+
+fn open_output(outfile: &Option<String>) -> io::Result<Box<dyn Write>> {
+    Ok(match outfile {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    })
+}
+
+fn dump<R: ReadBytes<Error = io::Error>>(
+    reader: R,
+    args: &Args,
+) -> Result<(), ReadWriteError<io::Error, io::Error>> {
+    let output = open_output(&args.outfile).map_err(ReadWriteError::Write)?;
+    FallibleHexd::new_with_options(reader, args.options.clone()).dump_io(output)
+}
+
+/// Dump `file`, seeking past skipped bytes if it is a regular file. Pipes
+/// cannot seek, and devices such as `/dev/zero` do not report their length.
+fn dump_file(file: File, args: &Args) -> Result<(), ReadWriteError<io::Error, io::Error>> {
+    if !file.metadata().is_ok_and(|m| m.is_file()) {
+        return dump(IOReader::new(BufReader::new(file)), args);
+    }
+    dump(
+        SeekReader::from_file(file).map_err(ReadWriteError::Read)?,
+        args,
+    )
+}
+
+/// Read the whole input as an ELF file, and list its sections and segments
+/// or dump the sections named by `--section`.
+fn elf<R: Read>(mut input: R, args: &Args) -> Result<(), ReadWriteError<io::Error, io::Error>> {
+    let mut data = Vec::new();
+    input.read_to_end(&mut data).map_err(ReadWriteError::Read)?;
+    let invalid = |e| ReadWriteError::Read(io::Error::new(ErrorKind::InvalidData, e));
+    let elf = Elf::parse(&data).map_err(invalid)?;
+
+    let mut output = open_output(&args.outfile).map_err(ReadWriteError::Write)?;
+    if args.list_sections {
+        let lines = elf.sections().iter().map(ToString::to_string);
+        let mut lines = lines.chain(elf.segments().iter().map(ToString::to_string));
+        return lines
+            .try_for_each(|line| writeln!(output, "{line}"))
+            .and_then(|_| output.flush())
+            .map_err(ReadWriteError::Write);
+    }
+    let names: Vec<&str> = args.sections.iter().map(String::as_str).collect();
+    elf.dump_sections_into(&names, &args.options, IOWriter::new(output))
+        .map_err(invalid)?
+        .map_err(ReadWriteError::Write)
+}
+
+fn reverse<R: Read>(mut input: R, args: &Args) -> Result<(), ReadWriteError<io::Error, io::Error>> {
+    let mut text = String::new();
+    input
+        .read_to_string(&mut text)
+        .map_err(ReadWriteError::Read)?;
+
+    let bytes = HexdParser::new_with_options(args.options.clone())
+        .parse_str(&text)
+        .map_err(|e| ReadWriteError::Read(io::Error::new(ErrorKind::InvalidData, e)))?;
+
+    let mut output = open_output(&args.outfile).map_err(ReadWriteError::Write)?;
+    output
+        .write_all(&bytes)
+        .and_then(|_| output.flush())
+        .map_err(ReadWriteError::Write)
+}
+
+fn run(args: Args) -> ExitCode {
+    let name = args.infile.as_deref().unwrap_or("<stdin>");
+
+    let elf_input = args.list_sections || !args.sections.is_empty();
+    let result = match &args.infile {
+        Some(path) => match File::open(path) {
+            Ok(f) if elf_input => elf(f, &args),
+            Ok(f) if args.reverse => reverse(f, &args),
+            Ok(f) => dump_file(f, &args),
+            Err(e) => Err(ReadWriteError::Read(e)),
+        },
+        None if elf_input => elf(io::stdin().lock(), &args),
+        None if args.reverse => reverse(io::stdin().lock(), &args),
+        None => dump(IOReader::new(io::stdin().lock()), &args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // the reader of our output went away (e.g. `hxd file | head`)
+        Err(ReadWriteError::Write(e)) if e.kind() == ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(ReadWriteError::Read(e)) => {
+            eprintln!("hxd: {name}: {e}");
+            ExitCode::from(1)
+        }
+        Err(ReadWriteError::Write(e)) => {
+            let out = args.outfile.as_deref().unwrap_or("<stdout>");
+            eprintln!("hxd: {out}: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => run(*args),
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Version) => {
+            println!("hxd {}", env!("CARGO_PKG_VERSION"));
+            ExitCode::SUCCESS
+        }
+        Err(msg) => {
+            eprintln!("hxd: {msg}");
+            eprint!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::options::Checksum;
+
+/// The CRC-32 lookup table for the reflected polynomial `0xEDB88320`.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// The largest prime below 2^16.
+const ADLER32_MODULUS: u32 = 65521;
+
+/// The number of bytes which can be summed before the Adler-32 sums have to
+/// be reduced without overflowing a `u32`.
+const ADLER32_BLOCK: usize = 5552;
+
+/// A checksum which is updated as bytes are read.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ChecksumState {
+    Crc32(u32),
+    Adler32 { a: u32, b: u32 },
+    Sum8(u8),
+    Xor8(u8),
+}
+
+impl ChecksumState {
+    pub(crate) fn new(checksum: Checksum) -> Self {
+        match checksum {
+            Checksum::Crc32 => Self::Crc32(!0),
+            Checksum::Adler32 => Self::Adler32 { a: 1, b: 0 },
+            Checksum::Sum8 => Self::Sum8(0),
+            Checksum::Xor8 => Self::Xor8(0),
+        }
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Crc32(crc) => {
+                for b in bytes {
+                    *crc = CRC32_TABLE[((*crc ^ *b as u32) & 0xff) as usize] ^ (*crc >> 8);
+                }
+            }
+            Self::Adler32 { a, b } => {
+                for block in bytes.chunks(ADLER32_BLOCK) {
+                    for byte in block {
+                        *a += *byte as u32;
+                        *b += *a;
+                    }
+                    *a %= ADLER32_MODULUS;
+                    *b %= ADLER32_MODULUS;
+                }
+            }
+            Self::Sum8(sum) => {
+                for b in bytes {
+                    *sum = sum.wrapping_add(*b);
+                }
+            }
+            Self::Xor8(x) => {
+                for b in bytes {
+                    *x ^= *b;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        match *self {
+            Self::Crc32(crc) => !crc,
+            Self::Adler32 { a, b } => (b << 16) | a,
+            Self::Sum8(sum) => sum as u32,
+            Self::Xor8(x) => x as u32,
+        }
+    }
+}
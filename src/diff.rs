@@ -0,0 +1,594 @@
+use alloc::{collections::VecDeque, vec::Vec};
+use core::{
+    cmp::max,
+    convert::Infallible,
+    fmt::{Debug, Display, Formatter},
+};
+#[cfg(feature = "std")]
+use std::io::Write;
+
+#[cfg(feature = "std")]
+use crate::writer::IOWriter;
+use crate::{
+    options::{HexdOptions, HexdOptionsBuilder},
+    reader::ReadBytes,
+    rows::ElidedRun,
+    writer::WriteHexdump,
+    ElisionMatch, HexdumpLineIterator, LineFormatter, LineIteratorResult, ReadWriteError,
+    RowBuffer, RowBytes, StackBuffer, TryDumpError,
+};
+
+/// How the rows of the two sides of a [`HexdDiff`] are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffLayout {
+    /// Rows which differ are printed on consecutive lines, prefixed with `-`
+    /// for the left side and `+` for the right side. Identical rows are
+    /// printed once, prefixed with a space.
+    ///
+    /// ```
+    /// use hxd::{AsHexd, diff::DiffLayout, options::{HexdOptionsBuilder, Spacing}};
+    ///
+    /// let diff = b"abcd".hexd()
+    ///     .ungrouped(4, Spacing::Normal)
+    ///     .diff(b"abXd".hexd())
+    ///     .layout(DiffLayout::Interleaved)
+    ///     .dump_to::<String>();
+    /// assert_eq!(diff, concat!(
+    ///     "-00000000: 61 62 63 64 |abcd|\n",
+    ///     "+00000000: 61 62 58 64 |abXd|\n",
+    ///     "                 ^^       ^\n",
+    /// ));
+    /// ```
+    #[default]
+    Interleaved,
+
+    /// Rows of both sides are printed next to each other on the same line.
+    /// The gutter between the sides shows `|` for rows which differ, and
+    /// `<` or `>` for rows which only exist on the left or right side.
+    ///
+    /// ```
+    /// use hxd::{AsHexd, diff::DiffLayout, options::{HexdOptionsBuilder, Spacing}};
+    ///
+    /// let diff = b"abcdefg".hexd()
+    ///     .ungrouped(4, Spacing::Normal)
+    ///     .diff(b"abXd".hexd())
+    ///     .layout(DiffLayout::SideBySide)
+    ///     .dump_to::<String>();
+    /// assert_eq!(diff, concat!(
+    ///     "00000000: 61 62 63 64 |abcd| | 61 62 58 64 |abXd|\n",
+    ///     "                ^^       ^           ^^       ^\n",
+    ///     "00000004: 65 66 67    |efg | <\n",
+    /// ));
+    /// ```
+    SideBySide,
+}
+
+/// Renders the differences between two byte sources as a hexdump.
+///
+/// Both sources are read row by row using the same [options](HexdOptions),
+/// so rows at the same index line up. Bytes which differ are marked with
+/// `^` on a line beneath the row, and are highlighted with the
+/// [`changed`](crate::options::Palette::changed) style if color is enabled.
+///
+/// Runs of identical rows are elided with `*` if [autoskip](HexdOptions::autoskip)
+/// is enabled. Use [`context`](Self::context) to only print the rows which differ.
+///
+/// Typically this is constructed with [`Hexd::diff`](crate::Hexd::diff), or with
+/// [`FallibleHexd::diff`](crate::FallibleHexd::diff) for readers which can fail.
+/// The methods which panic instead of returning errors are only available
+/// if neither reader can fail.
+pub struct HexdDiff<A: ReadBytes, B: ReadBytes> {
+    left: A,
+    right: B,
+    options: HexdOptions,
+    layout: DiffLayout,
+    context: Option<usize>,
+}
+
+impl<A: ReadBytes, B: ReadBytes> HexdDiff<A, B> {
+    /// Construct a new [`HexdDiff`] instance with the given readers and [default options](HexdOptions::default).
+    pub fn new(left: A, right: B) -> Self {
+        Self::new_with_options(left, right, HexdOptions::default())
+    }
+
+    /// Construct a new [`HexdDiff`] instance with the given readers and options.
+    pub fn new_with_options(left: A, right: B, options: HexdOptions) -> Self {
+        Self {
+            left,
+            right,
+            options,
+            layout: DiffLayout::default(),
+            context: None,
+        }
+    }
+
+    /// Set the [layout](DiffLayout) of the diff.
+    pub fn layout(self, layout: DiffLayout) -> Self {
+        Self { layout, ..self }
+    }
+
+    /// Only print rows which differ, along with up to `rows` identical rows
+    /// before and after them. Any other rows are elided with `*`.
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::{HexdOptionsBuilder, Spacing}};
+    ///
+    /// let left = b"aaaabbbbccccdddd";
+    /// let right = b"aaaabbbbccXcdddd";
+    ///
+    /// let diff = left.hexd()
+    ///     .ungrouped(4, Spacing::Normal)
+    ///     .autoskip(false)
+    ///     .diff(right.hexd())
+    ///     .context(1)
+    ///     .dump_to::<String>();
+    /// assert_eq!(diff, concat!(
+    ///     "*\n",
+    ///     " 00000004: 62 62 62 62 |bbbb|\n",
+    ///     "-00000008: 63 63 63 63 |cccc|\n",
+    ///     "+00000008: 63 63 58 63 |ccXc|\n",
+    ///     "                 ^^       ^\n",
+    ///     " 0000000C: 64 64 64 64 |dddd|\n",
+    /// ));
+    /// ```
+    pub fn context(self, rows: usize) -> Self {
+        Self {
+            context: Some(rows),
+            ..self
+        }
+    }
+
+    /// Construct a default instance of `W` and write a diff to it, returning
+    /// its output. The options are [validated](HexdOptions::validate) first,
+    /// and errors from either reader are returned rather than panicking.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// use hxd::{diff::{DiffReadError, HexdDiff}, reader::IOReader, TryDumpError};
+    /// use std::io::{self, Read};
+    ///
+    /// struct Broken;
+    ///
+    /// impl Read for Broken {
+    ///     fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+    ///         Err(io::ErrorKind::BrokenPipe.into())
+    ///     }
+    /// }
+    ///
+    /// let diff = HexdDiff::new(IOReader::new(&b"abcd"[..]), IOReader::new(Broken))
+    ///     .try_dump_to::<String>();
+    /// assert!(matches!(diff, Err(TryDumpError::Dump(DiffReadError::Right(_)))));
+    /// # }
+    /// ```
+    pub fn try_dump_to<W: WriteHexdump + Default>(
+        self,
+    ) -> Result<W::Output, TryDumpError<SideError<A, B>>> {
+        self.try_dump_into(W::default())
+    }
+
+    /// Like [`Self::try_dump_to`], but write the diff to an instance of `W`.
+    pub fn try_dump_into<W: WriteHexdump>(
+        self,
+        writer: W,
+    ) -> Result<W::Output, TryDumpError<SideError<A, B>>> {
+        self.options.validate()?;
+        HexdumpDiffWriter::new(self, writer)
+            .do_diff()
+            .map_err(TryDumpError::Dump)
+    }
+
+    /// Write a diff to an object that is [Write].
+    /// The object is wrapped in a [BufWriter](std::io::BufWriter)
+    /// for improved performance.
+    #[cfg(feature = "std")]
+    pub fn dump_io<W: Write>(
+        self,
+        write: W,
+    ) -> Result<(), ReadWriteError<SideError<A, B>, std::io::Error>> {
+        match HexdumpDiffWriter::new(self, IOWriter::new(write)).do_diff() {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(ReadWriteError::Write(e)),
+            Err(e) => Err(ReadWriteError::Read(e)),
+        }
+    }
+}
+
+impl<A: ReadBytes<Error = Infallible>, B: ReadBytes<Error = Infallible>> HexdDiff<A, B> {
+    /// Print a diff to `stdout`.
+    #[cfg(feature = "std")]
+    pub fn dump(self) {
+        self.dump_io(std::io::stdout())
+            .expect("could not print diff to stdout");
+    }
+
+    /// Print a diff to `stderr`.
+    #[cfg(feature = "std")]
+    pub fn dump_err(self) {
+        self.dump_io(std::io::stderr())
+            .expect("could not print diff to stderr");
+    }
+
+    /// Construct a default instance of `W` and write a diff to it, returning its output.
+    pub fn dump_to<W: WriteHexdump + Default>(self) -> W::Output {
+        self.dump_into(W::default())
+    }
+
+    /// Write a diff to an instance of `W` and return its output.
+    pub fn dump_into<W: WriteHexdump>(self, writer: W) -> W::Output {
+        match HexdumpDiffWriter::new(self, writer).do_diff() {
+            Ok(output) => output,
+            Err(DiffReadError::Left(e) | DiffReadError::Right(e)) => match e {},
+        }
+    }
+}
+
+/// [`HexdDiff`] implements [`HexdOptionsBuilder`] to allow for fluent
+/// configuration.
+impl<A: ReadBytes, B: ReadBytes> HexdOptionsBuilder for HexdDiff<A, B> {
+    fn map_options<F: FnOnce(HexdOptions) -> HexdOptions>(self, f: F) -> Self {
+        Self {
+            options: f(self.options),
+            ..self
+        }
+    }
+}
+
+/// An error raised by either of the readers of a [`HexdDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffReadError<A, B> {
+    /// The reader of the left side failed.
+    Left(A),
+    /// The reader of the right side failed.
+    Right(B),
+}
+
+impl<A: Debug, B: Debug> Display for DiffReadError<A, B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Left(e) => write!(f, "could not read the left side of the diff: {e:?}"),
+            Self::Right(e) => write!(f, "could not read the right side of the diff: {e:?}"),
+        }
+    }
+}
+
+impl<A: Debug, B: Debug> core::error::Error for DiffReadError<A, B> {}
+
+/// The [`DiffReadError`] of the readers `A` and `B`.
+type SideError<A, B> = DiffReadError<<A as ReadBytes>::Error, <B as ReadBytes>::Error>;
+
+/// The rows at the same index of both sides of a diff.
+/// A side is `None` if its reader has run out of bytes.
+#[derive(Debug, Clone)]
+struct RowPair {
+    left: Option<RowBuffer>,
+    right: Option<RowBuffer>,
+}
+
+impl RowPair {
+    fn row_index(&self) -> usize {
+        match (&self.left, &self.right) {
+            (Some(row), _) | (None, Some(row)) => row.row_index,
+            (None, None) => 0,
+        }
+    }
+
+    fn differs(&self) -> bool {
+        match (&self.left, &self.right) {
+            (Some(l), Some(r)) => l.buffer != r.buffer,
+            _ => true,
+        }
+    }
+}
+
+/// Add `pair` to the run of rows hidden between the context rows.
+fn hide(hidden: &mut Option<ElidedRun>, pair: &RowPair) {
+    let end = pair.row_index() + pair.left.as_ref().map_or(0, |row| row.length);
+    match hidden {
+        Some(run) => {
+            run.end = end;
+            run.rows += 1;
+        }
+        None => {
+            *hidden = Some(ElidedRun {
+                start: pair.row_index(),
+                end,
+                rows: 1,
+            })
+        }
+    }
+}
+
+/// Return a row without any bytes at the same position as `row`,
+/// used in place of the missing side of a [`RowPair`].
+fn missing_row(row: &RowBuffer) -> RowBuffer {
+    RowBuffer {
+        buffer: RowBytes::with_capacity(0),
+        length: 0,
+        row_index: row.row_index,
+        elt_index: row.elt_index,
+        preceding: StackBuffer::new(),
+        following: StackBuffer::new(),
+        unknown: Vec::new(),
+    }
+}
+
+struct HexdumpDiffWriter<A: ReadBytes, B: ReadBytes, W: WriteHexdump> {
+    left: HexdumpLineIterator<A>,
+    right: HexdumpLineIterator<B>,
+    formatter: LineFormatter<W>,
+    options: HexdOptions,
+    layout: DiffLayout,
+    context: Option<usize>,
+}
+
+type DiffResult<A, B, W> = Result<(), ReadWriteError<SideError<A, B>, <W as WriteHexdump>::Error>>;
+
+impl<A: ReadBytes, B: ReadBytes, W: WriteHexdump> HexdumpDiffWriter<A, B, W> {
+    fn new(diff: HexdDiff<A, B>, writer: W) -> Self {
+        // rows are compared before they are elided, so the line
+        // iterators must yield every row
+        let row_options = diff.options.clone().autoskip(false);
+        Self {
+            left: HexdumpLineIterator::new(diff.left, row_options.clone()),
+            right: HexdumpLineIterator::new(diff.right, row_options),
+            formatter: LineFormatter::new(writer, diff.options.clone()),
+            options: diff.options,
+            layout: diff.layout,
+            context: diff.context,
+        }
+    }
+
+    fn do_diff(mut self) -> Result<W::Output, DiffReadError<A::Error, B::Error>> {
+        let r = match self.context {
+            None => self.write_all_rows(),
+            Some(context) => self.write_context_rows(context),
+        };
+        let ll = match r {
+            Ok(_) => Ok(Ok(self.formatter.writer)),
+            Err(ReadWriteError::Write(e)) => Ok(Err(e)),
+            Err(ReadWriteError::Read(r)) => Err(r),
+        }?;
+        Ok(WriteHexdump::consume(ll))
+    }
+
+    fn next_pair(&mut self) -> Result<Option<RowPair>, DiffReadError<A::Error, B::Error>> {
+        let left = next_row(&mut self.left).map_err(DiffReadError::Left)?;
+        let right = next_row(&mut self.right).map_err(DiffReadError::Right)?;
+        Ok((left.is_some() || right.is_some()).then_some(RowPair { left, right }))
+    }
+
+    /// Write every row pair, eliding runs of identical rows like a
+    /// regular hexdump if autoskip is enabled.
+    fn write_all_rows(&mut self) -> DiffResult<A, B, W> {
+        let mut elision_match: Option<ElisionMatch> = None;
+        // the first pairs of the current run, which are written if the run is
+        // too short to be elided, along with the last pair and the run length
+        let mut elided: Option<(Vec<RowPair>, RowPair, usize)> = None;
+        let min_rows = self.options.elision.min_rows.max(1);
+
+        while let Some(pair) = self.next_pair().map_err(ReadWriteError::Read)? {
+            if let (Some(em), Some(row), false) = (&elision_match, &pair.left, pair.differs()) {
+                if em.matches(row, &self.options) {
+                    elided = Some(match elided.take() {
+                        Some((mut held, last, count)) => {
+                            if held.len() < min_rows {
+                                held.push(last);
+                            }
+                            (held, pair, count + 1)
+                        }
+                        None => (Vec::new(), pair, 1),
+                    });
+                    continue;
+                }
+            }
+
+            if let Some((held, last, count)) = elided.take() {
+                self.write_elided(held, &last, count)?;
+            }
+
+            elision_match = match (&pair.left, pair.differs()) {
+                (Some(row), false) if self.options.autoskip => {
+                    ElisionMatch::try_match(row, &self.options)
+                }
+                _ => None,
+            };
+            self.write_pair(&pair)?;
+        }
+
+        if let Some((held, last, count)) = elided {
+            self.write_elided(held, &last, count)?;
+        }
+        Ok(())
+    }
+
+    /// Write only the row pairs which differ, along with `context`
+    /// identical row pairs before and after them.
+    fn write_context_rows(&mut self, context: usize) -> DiffResult<A, B, W> {
+        let mut before: VecDeque<RowPair> = VecDeque::with_capacity(context + 1);
+        let mut after = 0usize;
+        let mut hidden: Option<ElidedRun> = None;
+        let mut printed = false;
+
+        while let Some(pair) = self.next_pair().map_err(ReadWriteError::Read)? {
+            if pair.differs() {
+                if let Some(run) = hidden.take() {
+                    self.write_elision(&run)?;
+                }
+                while let Some(p) = before.pop_front() {
+                    self.write_pair(&p)?;
+                }
+                self.write_pair(&pair)?;
+                after = context;
+                printed = true;
+            } else if after > 0 {
+                self.write_pair(&pair)?;
+                after -= 1;
+            } else {
+                before.push_back(pair);
+                if before.len() > context {
+                    let pair = before.pop_front().unwrap();
+                    hide(&mut hidden, &pair);
+                }
+            }
+        }
+
+        for pair in &before {
+            hide(&mut hidden, pair);
+        }
+        match hidden {
+            Some(run) if printed => self.write_elision(&run),
+            _ => Ok(()),
+        }
+    }
+
+    fn write_elided(
+        &mut self,
+        held: Vec<RowPair>,
+        last: &RowPair,
+        count: usize,
+    ) -> DiffResult<A, B, W> {
+        if count > self.options.elision.min_rows.max(1) {
+            let run = ElidedRun {
+                start: held[0].row_index(),
+                end: last.row_index(),
+                rows: count - 1,
+            };
+            self.write_elision(&run)?;
+        } else {
+            for pair in &held {
+                self.write_pair(pair)?;
+            }
+        }
+        self.write_pair(last)
+    }
+
+    fn write_elision(&mut self, run: &ElidedRun) -> DiffResult<A, B, W> {
+        let byte_hint = self.byte_hint();
+        self.formatter.write_elision(run, byte_hint);
+        self.flush_line()
+    }
+
+    fn write_pair(&mut self, pair: &RowPair) -> DiffResult<A, B, W> {
+        match self.layout {
+            DiffLayout::Interleaved => self.write_pair_interleaved(pair),
+            DiffLayout::SideBySide => self.write_pair_side_by_side(pair),
+        }
+    }
+
+    fn write_pair_interleaved(&mut self, pair: &RowPair) -> DiffResult<A, B, W> {
+        let index = pair.row_index();
+        let hint = self.byte_hint();
+
+        match (&pair.left, &pair.right) {
+            (Some(row), _) if !pair.differs() => {
+                self.formatter.push_slice(b" ");
+                self.write_row(index, row, None);
+                self.flush_line()
+            }
+            (left, right) => {
+                if let Some(l) = left {
+                    let other = right.clone().unwrap_or_else(|| missing_row(l));
+                    self.formatter.push_slice(b"-");
+                    self.write_row(index, l, Some(&other));
+                    self.flush_line()?;
+                }
+                if let Some(r) = right {
+                    let other = left.clone().unwrap_or_else(|| missing_row(r));
+                    self.formatter.push_slice(b"+");
+                    self.write_row(index, r, Some(&other));
+                    self.flush_line()?;
+                }
+                if let (Some(l), Some(r)) = (left, right) {
+                    self.formatter.push_slice(b" ");
+                    self.formatter.write_index_padding(index, hint);
+                    self.formatter.write_row_markers(l, r);
+                    self.formatter.trim_line_end();
+                    self.flush_line()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn write_pair_side_by_side(&mut self, pair: &RowPair) -> DiffResult<A, B, W> {
+        let index = pair.row_index();
+        let hint = self.byte_hint();
+        let differs = pair.differs();
+
+        let (left, right) = match (&pair.left, &pair.right) {
+            (Some(l), Some(r)) => (l.clone(), r.clone()),
+            (Some(l), None) => (l.clone(), missing_row(l)),
+            (None, Some(r)) => (missing_row(r), r.clone()),
+            (None, None) => return Ok(()),
+        };
+        let compare = |other| if differs { Some(other) } else { None };
+
+        self.formatter.write_row_index(index, hint);
+        if pair.left.is_some() {
+            self.formatter.write_row_bytes(&left, compare(&right));
+            self.formatter.write_row_ascii(&left, compare(&right));
+        } else {
+            self.formatter.write_row_markers(&left, &left);
+        }
+
+        self.formatter.push_slice(match (&pair.left, &pair.right) {
+            (Some(_), None) => b" < ",
+            (None, Some(_)) => b" > ",
+            _ if differs => b" | ",
+            _ => b"   ",
+        });
+
+        if pair.right.is_some() {
+            self.formatter.write_row_bytes(&right, compare(&left));
+            self.formatter.write_row_ascii(&right, compare(&left));
+        }
+        self.formatter.trim_line_end();
+        self.flush_line()?;
+
+        if differs && pair.left.is_some() && pair.right.is_some() {
+            self.formatter.write_index_padding(index, hint);
+            self.formatter.write_row_markers(&left, &right);
+            self.formatter.push_slice(b"   ");
+            self.formatter.write_row_markers(&right, &left);
+            self.formatter.trim_line_end();
+            self.flush_line()?;
+        }
+        Ok(())
+    }
+
+    fn write_row(&mut self, row_index: usize, row: &RowBuffer, other: Option<&RowBuffer>) {
+        let hint = self.byte_hint();
+        self.formatter.write_row_index(row_index, hint);
+        self.formatter.write_row_bytes(row, other);
+        self.formatter.write_row_ascii(row, other);
+    }
+
+    /// The index width must fit the longer of the two sides, so a hint
+    /// is only available if both readers provide one.
+    fn byte_hint(&self) -> Option<usize> {
+        match (
+            self.left.reader.total_byte_hint(),
+            self.right.reader.total_byte_hint(),
+        ) {
+            (Some(l), Some(r)) => Some(max(l, r)),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn flush_line(&mut self) -> DiffResult<A, B, W> {
+        self.formatter.flush_line().map_err(ReadWriteError::Write)
+    }
+}
+
+fn next_row<R: ReadBytes>(
+    rows: &mut HexdumpLineIterator<R>,
+) -> Result<Option<RowBuffer>, R::Error> {
+    match rows.next() {
+        Some(Ok(LineIteratorResult::Row(row) | LineIteratorResult::Elided(row, _))) => {
+            Ok(Some(row))
+        }
+        Some(Err(e)) => Err(e),
+        None => Ok(None),
+    }
+}
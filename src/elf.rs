@@ -0,0 +1,684 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Display};
+
+use crate::{
+    options::{Endianness, HexdOptions, HexdOptionsBuilder},
+    reader::{ByteSliceReader, FromEndianBytes},
+    writer::WriteHexdump,
+    Hexd,
+};
+
+const SHT_NULL: u32 = 0;
+const SHT_NOBITS: u32 = 8;
+
+/// The section header index which means that the real index is stored in
+/// the first section header.
+const SHN_XINDEX: u16 = 0xffff;
+
+/// Whether an ELF file has 32-bit or 64-bit addresses and offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfClass {
+    Elf32,
+    Elf64,
+}
+
+/// An error encountered while parsing an ELF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElfError {
+    /// The data does not start with the ELF magic number.
+    NotElf,
+    /// The class in the identification bytes is neither 32-bit nor 64-bit.
+    UnsupportedClass(u8),
+    /// The data encoding in the identification bytes is neither little
+    /// endian nor big endian.
+    UnsupportedEncoding(u8),
+    /// The named structure reaches past the end of the data.
+    Truncated(&'static str),
+    /// The named structure has an invalid size or refers to something which
+    /// does not exist.
+    Malformed(&'static str),
+    /// No section has this name.
+    NoSuchSection(String),
+    /// An address does not fit in a `usize` on this platform.
+    AddressOverflow(u64),
+}
+
+impl Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfError::NotElf => write!(f, "not an ELF file"),
+            ElfError::UnsupportedClass(c) => write!(f, "unsupported ELF class {c}"),
+            ElfError::UnsupportedEncoding(e) => write!(f, "unsupported ELF data encoding {e}"),
+            ElfError::Truncated(what) => write!(f, "{what} reaches past the end of the file"),
+            ElfError::Malformed(what) => write!(f, "invalid {what}"),
+            ElfError::NoSuchSection(name) => write!(f, "no section named '{name}'"),
+            ElfError::AddressOverflow(a) => write!(f, "address 0x{a:X} is too large"),
+        }
+    }
+}
+
+impl core::error::Error for ElfError {}
+
+/// A section of an ELF file, as described by its section header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+
+    /// The `sh_type` of the section, e.g. `1` for `PROGBITS`.
+    pub kind: u32,
+
+    /// The `sh_flags` of the section, e.g. `0x2` for an allocated section.
+    pub flags: u64,
+
+    /// The virtual address of the section when it is loaded, or zero.
+    pub address: u64,
+
+    /// The offset of the section in the file.
+    pub offset: u64,
+
+    /// The number of bytes in the section. `NOBITS` sections such as `.bss`
+    /// take no space in the file.
+    pub size: u64,
+
+    pub alignment: u64,
+}
+
+impl Section {
+    /// Whether the contents of the section are stored in the file.
+    pub fn has_data(&self) -> bool {
+        self.kind != SHT_NULL && self.kind != SHT_NOBITS
+    }
+
+    /// The name of the section type, if it is a common one.
+    pub fn kind_name(&self) -> Option<&'static str> {
+        Some(match self.kind {
+            0 => "NULL",
+            1 => "PROGBITS",
+            2 => "SYMTAB",
+            3 => "STRTAB",
+            4 => "RELA",
+            5 => "HASH",
+            6 => "DYNAMIC",
+            7 => "NOTE",
+            8 => "NOBITS",
+            9 => "REL",
+            10 => "SHLIB",
+            11 => "DYNSYM",
+            14 => "INIT_ARRAY",
+            15 => "FINI_ARRAY",
+            16 => "PREINIT_ARRAY",
+            17 => "GROUP",
+            18 => "SYMTAB_SHNDX",
+            0x6ffffff5 => "GNU_ATTRIBUTES",
+            0x6ffffff6 => "GNU_HASH",
+            0x6ffffffd => "VERDEF",
+            0x6ffffffe => "VERNEED",
+            0x6fffffff => "VERSYM",
+            _ => return None,
+        })
+    }
+}
+
+/// Describe the section on one line, e.g.
+/// `section .text (PROGBITS, AX): address 0x1040, offset 0x1040, 277 bytes`.
+///
+/// The flags are written as `W` (writable), `A` (allocated), `X`
+/// (executable), `M` (mergeable), `S` (strings), `I` (info link), `L` (link
+/// order), `G` (group) and `T` (thread-local).
+impl Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "section {} (", self.name)?;
+        write_kind(f, self.kind, self.kind_name())?;
+        let flags: String = [0x1, 0x2, 0x4, 0x10, 0x20, 0x40, 0x80, 0x200, 0x400]
+            .into_iter()
+            .zip("WAXMSILGT".chars())
+            .filter(|(bit, _)| self.flags & bit != 0)
+            .map(|(_, c)| c)
+            .collect();
+        if !flags.is_empty() {
+            write!(f, ", {flags}")?;
+        }
+        write!(
+            f,
+            "): address 0x{:X}, offset 0x{:X}, {} bytes",
+            self.address, self.offset, self.size
+        )
+    }
+}
+
+/// A segment of an ELF file, as described by its program header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The `p_type` of the segment, e.g. `1` for `LOAD`.
+    pub kind: u32,
+
+    /// The `p_flags` of the segment: `4` if it is readable, `2` if it is
+    /// writable and `1` if it is executable.
+    pub flags: u32,
+
+    /// The offset of the segment in the file.
+    pub offset: u64,
+
+    pub virtual_address: u64,
+    pub physical_address: u64,
+
+    /// The number of bytes of the segment in the file.
+    pub file_size: u64,
+
+    /// The number of bytes of the segment in memory, which may be more than
+    /// in the file. The rest is filled with zeros when it is loaded.
+    pub memory_size: u64,
+
+    pub alignment: u64,
+}
+
+impl Segment {
+    pub fn readable(&self) -> bool {
+        self.flags & 4 != 0
+    }
+
+    pub fn writable(&self) -> bool {
+        self.flags & 2 != 0
+    }
+
+    pub fn executable(&self) -> bool {
+        self.flags & 1 != 0
+    }
+
+    /// The name of the segment type, if it is a common one.
+    pub fn kind_name(&self) -> Option<&'static str> {
+        Some(match self.kind {
+            0 => "NULL",
+            1 => "LOAD",
+            2 => "DYNAMIC",
+            3 => "INTERP",
+            4 => "NOTE",
+            5 => "SHLIB",
+            6 => "PHDR",
+            7 => "TLS",
+            0x6474e550 => "GNU_EH_FRAME",
+            0x6474e551 => "GNU_STACK",
+            0x6474e552 => "GNU_RELRO",
+            0x6474e553 => "GNU_PROPERTY",
+            _ => return None,
+        })
+    }
+}
+
+/// Describe the segment on one line, e.g.
+/// `segment LOAD (R-X): address 0x401000, offset 0x1000, 277 bytes (277 in memory)`.
+impl Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "segment ")?;
+        write_kind(f, self.kind, self.kind_name())?;
+        let flag = |set: bool, c: char| if set { c } else { '-' };
+        write!(
+            f,
+            " ({}{}{}): address 0x{:X}, offset 0x{:X}, {} bytes ({} in memory)",
+            flag(self.readable(), 'R'),
+            flag(self.writable(), 'W'),
+            flag(self.executable(), 'X'),
+            self.virtual_address,
+            self.offset,
+            self.file_size,
+            self.memory_size
+        )
+    }
+}
+
+fn write_kind(f: &mut fmt::Formatter<'_>, kind: u32, name: Option<&str>) -> fmt::Result {
+    match name {
+        Some(name) => f.write_str(name),
+        None => write!(f, "0x{kind:X}"),
+    }
+}
+
+/// A parsed ELF file, either 32-bit or 64-bit and in either byte order.
+///
+/// Sections are dumped with their virtual address as the
+/// [absolute offset](crate::options::IndexOffset::Absolute), so the index of
+/// each row is the address of its first byte when the file is loaded:
+///
+/// ```no_run
+/// # #[cfg(feature = "std")] {
+/// use hxd::elf::Elf;
+///
+/// let data = std::fs::read("/bin/true")?;
+/// let elf = Elf::parse(&data)?;
+/// for section in elf.sections() {
+///     println!("{section}");
+/// }
+/// elf.section_hexd(".rodata")?.dump();
+/// # }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Elf<'a> {
+    data: &'a [u8],
+    class: ElfClass,
+    endianness: Endianness,
+    machine: u16,
+    entry: u64,
+    sections: Vec<Section>,
+    segments: Vec<Segment>,
+}
+
+impl<'a> Elf<'a> {
+    /// Parse the headers, section headers and program headers of the ELF
+    /// file in `data`.
+    pub fn parse(data: &'a [u8]) -> Result<Self, ElfError> {
+        if !data.starts_with(b"\x7fELF") {
+            return Err(ElfError::NotElf);
+        }
+        let ident = data.get(..16).ok_or(ElfError::Truncated("ELF header"))?;
+        let class = match ident[4] {
+            1 => ElfClass::Elf32,
+            2 => ElfClass::Elf64,
+            c => return Err(ElfError::UnsupportedClass(c)),
+        };
+        let endianness = match ident[5] {
+            1 => Endianness::LittleEndian,
+            2 => Endianness::BigEndian,
+            e => return Err(ElfError::UnsupportedEncoding(e)),
+        };
+        let fields = Fields {
+            data,
+            class,
+            endianness,
+        };
+        let header = fields.header()?;
+
+        let mut elf = Elf {
+            data,
+            class,
+            endianness,
+            machine: header.machine,
+            entry: header.entry,
+            sections: Vec::new(),
+            segments: Vec::new(),
+        };
+        // a program header has 4-byte type and flags fields, and six words
+        elf.segments = fields
+            .table(
+                header.phoff,
+                header.phnum,
+                (header.phentsize, fields.word_size() * 6 + 8),
+                "program header",
+            )?
+            .map(|at| fields.segment(at))
+            .collect::<Result<_, _>>()?;
+        if elf
+            .segments
+            .iter()
+            .any(|s| elf.bytes(s.offset, s.file_size).is_none())
+        {
+            return Err(ElfError::Truncated("segment"));
+        }
+        elf.sections = fields.sections(&header)?;
+        if elf
+            .sections
+            .iter()
+            .any(|s| s.has_data() && elf.bytes(s.offset, s.size).is_none())
+        {
+            return Err(ElfError::Truncated("section"));
+        }
+        Ok(elf)
+    }
+
+    pub fn class(&self) -> ElfClass {
+        self.class
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// The `e_machine` of the file, e.g. `62` for x86-64.
+    pub fn machine(&self) -> u16 {
+        self.machine
+    }
+
+    /// The address of the entry point, or zero.
+    pub fn entry(&self) -> u64 {
+        self.entry
+    }
+
+    /// The sections of the file in the order of their headers, starting
+    /// with the null section.
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// The segments of the file in the order of their program headers.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Return the first section named `name`.
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /// Return the contents of `section` in the file. This is empty for
+    /// sections which take no space in the file, such as `.bss`.
+    pub fn section_data(&self, section: &Section) -> &'a [u8] {
+        if !section.has_data() {
+            return &[];
+        }
+        self.bytes(section.offset, section.size).unwrap_or(&[])
+    }
+
+    /// Return the contents of `segment` in the file.
+    pub fn segment_data(&self, segment: &Segment) -> &'a [u8] {
+        self.bytes(segment.offset, segment.file_size).unwrap_or(&[])
+    }
+
+    /// Return a [`Hexd`] over the section named `name`, with the address of
+    /// the section as the [absolute offset](HexdOptionsBuilder::absolute_offset).
+    ///
+    /// The first dumped byte is shown at the address of the section, so to
+    /// dump a [range](HexdOptionsBuilder::range) of the section at its own
+    /// addresses, replace the offset with a
+    /// [relative offset](HexdOptionsBuilder::relative_offset) of the address.
+    pub fn section_hexd(&self, name: &str) -> Result<Hexd<ByteSliceReader<'a>>, ElfError> {
+        let section = self.named(name)?;
+        self.hexd(section, HexdOptions::default())
+    }
+
+    /// Construct a default instance of `W` and write a hexdump of each of
+    /// the sections named in `names` to it, returning its output. See
+    /// [`Self::dump_sections_into`].
+    pub fn dump_sections_to<W: WriteHexdump + Default>(
+        &self,
+        names: &[&str],
+        options: &HexdOptions,
+    ) -> Result<W::Output, ElfError> {
+        self.dump_sections_into(names, options, W::default())
+    }
+
+    /// Write a hexdump of each of the sections named in `names` to `writer`
+    /// and return its output. Each dump is preceded by a line describing
+    /// its section, and is indexed by the addresses of the section.
+    ///
+    /// Nothing is written if one of the sections does not exist.
+    ///
+    /// ```no_run
+    /// use hxd::{elf::Elf, options::HexdOptions};
+    ///
+    /// let data = std::fs::read("/bin/true")?;
+    /// let dump = Elf::parse(&data)?
+    ///     .dump_sections_to::<String>(&[".interp", ".rodata"], &HexdOptions::default())?;
+    /// assert!(dump.starts_with("section .interp (PROGBITS, A): address 0x"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn dump_sections_into<W: WriteHexdump>(
+        &self,
+        names: &[&str],
+        options: &HexdOptions,
+        mut writer: W,
+    ) -> Result<W::Output, ElfError> {
+        let dumps = names
+            .iter()
+            .map(|name| {
+                let section = self.named(name)?;
+                Ok((section, self.hexd(section, options.clone())?))
+            })
+            .collect::<Result<Vec<_>, ElfError>>()?;
+
+        let written = dumps.into_iter().try_for_each(|(section, hexd)| {
+            writer.write_str(&format!("{section}\n"))?;
+            writer.line_end()?;
+            hexd.dump_into(Sections(&mut writer))
+        });
+        Ok(W::consume(written.map(|_| writer)))
+    }
+
+    fn named(&self, name: &str) -> Result<&Section, ElfError> {
+        self.section(name)
+            .ok_or_else(|| ElfError::NoSuchSection(name.to_string()))
+    }
+
+    fn hexd(
+        &self,
+        section: &Section,
+        options: HexdOptions,
+    ) -> Result<Hexd<ByteSliceReader<'a>>, ElfError> {
+        let address = usize::try_from(section.address)
+            .map_err(|_| ElfError::AddressOverflow(section.address))?;
+        let reader = ByteSliceReader::new(self.section_data(section));
+        Ok(Hexd::new_with_options(reader, options).absolute_offset(address))
+    }
+
+    fn bytes(&self, offset: u64, len: u64) -> Option<&'a [u8]> {
+        slice(self.data, offset, len)
+    }
+}
+
+/// Return the `len` bytes at `offset` in `data`, if they are within it.
+fn slice(data: &[u8], offset: u64, len: u64) -> Option<&[u8]> {
+    let start = usize::try_from(offset).ok()?;
+    let len = usize::try_from(len).ok()?;
+    data.get(start..start.checked_add(len)?)
+}
+
+/// Writes the dumps of several sections into one writer.
+struct Sections<'w, W>(&'w mut W);
+
+impl<W: WriteHexdump> WriteHexdump for Sections<'_, W> {
+    type Error = W::Error;
+    type Output = Result<(), W::Error>;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.0.write_str(s)
+    }
+
+    fn line_end(&mut self) -> Result<(), Self::Error> {
+        self.0.line_end()
+    }
+
+    fn consume(r: Result<Self, Self::Error>) -> Self::Output {
+        r.map(|_| ())
+    }
+}
+
+/// The fields of the ELF header which locate the other headers.
+struct Header {
+    machine: u16,
+    entry: u64,
+    phoff: u64,
+    shoff: u64,
+    phentsize: usize,
+    phnum: usize,
+    shentsize: usize,
+    shnum: usize,
+    shstrndx: usize,
+}
+
+/// Reads the fields of an ELF file in its byte order.
+struct Fields<'a> {
+    data: &'a [u8],
+    class: ElfClass,
+    endianness: Endianness,
+}
+
+impl Fields<'_> {
+    fn get<const N: usize, T: FromEndianBytes<N>>(
+        &self,
+        at: usize,
+        what: &'static str,
+    ) -> Result<T, ElfError> {
+        let bytes = at
+            .checked_add(N)
+            .and_then(|end| self.data.get(at..end))
+            .ok_or(ElfError::Truncated(what))?;
+        Ok(T::from_bytes(bytes.try_into().unwrap(), self.endianness))
+    }
+
+    fn u16(&self, at: usize, what: &'static str) -> Result<u16, ElfError> {
+        self.get::<2, u16>(at, what)
+    }
+
+    fn u32(&self, at: usize, what: &'static str) -> Result<u32, ElfError> {
+        self.get::<4, u32>(at, what)
+    }
+
+    /// Read an address, offset or size, which is 4 bytes long in 32-bit
+    /// files and 8 bytes long in 64-bit files.
+    fn word(&self, at: usize, what: &'static str) -> Result<u64, ElfError> {
+        match self.class {
+            ElfClass::Elf32 => self.u32(at, what).map(u64::from),
+            ElfClass::Elf64 => self.get::<8, u64>(at, what),
+        }
+    }
+
+    fn word_size(&self) -> usize {
+        match self.class {
+            ElfClass::Elf32 => 4,
+            ElfClass::Elf64 => 8,
+        }
+    }
+
+    fn header(&self) -> Result<Header, ElfError> {
+        const WHAT: &str = "ELF header";
+        // the fields after the entry point move by the size of a word
+        let w = match self.class {
+            ElfClass::Elf32 => 0,
+            ElfClass::Elf64 => 4,
+        };
+        Ok(Header {
+            machine: self.u16(18, WHAT)?,
+            entry: self.word(24, WHAT)?,
+            phoff: self.word(28 + w, WHAT)?,
+            shoff: self.word(32 + 2 * w, WHAT)?,
+            phentsize: self.u16(42 + 3 * w, WHAT)?.into(),
+            phnum: self.u16(44 + 3 * w, WHAT)?.into(),
+            shentsize: self.u16(46 + 3 * w, WHAT)?.into(),
+            shnum: self.u16(48 + 3 * w, WHAT)?.into(),
+            shstrndx: self.u16(50 + 3 * w, WHAT)?.into(),
+        })
+    }
+
+    /// Return the offsets of the `count` entries of `size` bytes in the
+    /// table at `offset`, checking that the entries are at least `min_size`
+    /// bytes long and that the table is within the file.
+    fn table(
+        &self,
+        offset: u64,
+        count: usize,
+        (size, min_size): (usize, usize),
+        what: &'static str,
+    ) -> Result<impl Iterator<Item = usize>, ElfError> {
+        if count > 0 && size < min_size {
+            return Err(ElfError::Malformed(what));
+        }
+        let len = count.checked_mul(size).ok_or(ElfError::Truncated(what))?;
+        slice(self.data, offset, len as u64).ok_or(ElfError::Truncated(what))?;
+        let start = offset as usize;
+        Ok((0..count).map(move |i| start + i * size))
+    }
+
+    fn segment(&self, at: usize) -> Result<Segment, ElfError> {
+        const WHAT: &str = "program header";
+        Ok(match self.class {
+            ElfClass::Elf32 => Segment {
+                kind: self.u32(at, WHAT)?,
+                offset: self.word(at + 4, WHAT)?,
+                virtual_address: self.word(at + 8, WHAT)?,
+                physical_address: self.word(at + 12, WHAT)?,
+                file_size: self.word(at + 16, WHAT)?,
+                memory_size: self.word(at + 20, WHAT)?,
+                flags: self.u32(at + 24, WHAT)?,
+                alignment: self.word(at + 28, WHAT)?,
+            },
+            ElfClass::Elf64 => Segment {
+                kind: self.u32(at, WHAT)?,
+                flags: self.u32(at + 4, WHAT)?,
+                offset: self.word(at + 8, WHAT)?,
+                virtual_address: self.word(at + 16, WHAT)?,
+                physical_address: self.word(at + 24, WHAT)?,
+                file_size: self.word(at + 32, WHAT)?,
+                memory_size: self.word(at + 40, WHAT)?,
+                alignment: self.word(at + 48, WHAT)?,
+            },
+        })
+    }
+
+    /// Read a section header, returning the section with an empty name,
+    /// the offset of its name in the section name table and its `sh_link`.
+    fn section(&self, at: usize) -> Result<(Section, u32, u32), ElfError> {
+        const WHAT: &str = "section header";
+        // the offsets of sh_flags, sh_addr, sh_offset, sh_size, sh_link and sh_addralign
+        let w = match self.class {
+            ElfClass::Elf32 => [8, 12, 16, 20, 24, 32],
+            ElfClass::Elf64 => [8, 16, 24, 32, 40, 48],
+        };
+        let section = Section {
+            name: String::new(),
+            kind: self.u32(at + 4, WHAT)?,
+            flags: self.word(at + w[0], WHAT)?,
+            address: self.word(at + w[1], WHAT)?,
+            offset: self.word(at + w[2], WHAT)?,
+            size: self.word(at + w[3], WHAT)?,
+            alignment: self.word(at + w[5], WHAT)?,
+        };
+        let link = self.u32(at + w[4], WHAT)?;
+        Ok((section, self.u32(at, WHAT)?, link))
+    }
+
+    fn sections(&self, header: &Header) -> Result<Vec<Section>, ElfError> {
+        const WHAT: &str = "section header";
+        if header.shoff == 0 {
+            return Ok(Vec::new());
+        }
+        // the size of a section header: 4-byte name, type, link and info
+        // fields, and six words
+        let entry = (header.shentsize, self.word_size() * 6 + 16);
+        let (mut shnum, mut shstrndx) = (header.shnum, header.shstrndx);
+        if shnum == 0 || shstrndx == usize::from(SHN_XINDEX) {
+            // too many sections for the ELF header: the real numbers are in
+            // the size and link of the null section
+            let at = self.table(header.shoff, 1, entry, WHAT)?.next().unwrap();
+            let (null, _, link) = self.section(at)?;
+            if shnum == 0 {
+                shnum = usize::try_from(null.size).map_err(|_| ElfError::Malformed(WHAT))?;
+            }
+            if shstrndx == usize::from(SHN_XINDEX) {
+                shstrndx = link as usize;
+            }
+        }
+
+        let headers = self
+            .table(header.shoff, shnum, entry, WHAT)?
+            .map(|at| self.section(at))
+            .collect::<Result<Vec<_>, _>>()?;
+        let names = match shstrndx {
+            0 => None,
+            i => {
+                let (strtab, _, _) = headers
+                    .get(i)
+                    .ok_or(ElfError::Malformed("section name table index"))?;
+                let names = slice(self.data, strtab.offset, strtab.size)
+                    .ok_or(ElfError::Truncated("section name table"))?;
+                Some(names)
+            }
+        };
+
+        headers
+            .into_iter()
+            .map(|(mut section, name, _)| {
+                if let Some(names) = names {
+                    let name = names
+                        .get(name as usize..)
+                        .ok_or(ElfError::Malformed("section name"))?;
+                    let len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+                    section.name = String::from_utf8_lossy(&name[..len]).into_owned();
+                }
+                Ok(section)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,113 @@
+use crate::options::TextEncoding;
+
+/// The character shown in the text panel for UTF-8 continuation bytes
+/// which belong to a character decoded at an earlier byte.
+const UTF8_CONTINUATION: char = '·';
+
+/// The character shown in the text panel for bytes which cannot be displayed.
+const NON_PRINTABLE: char = '.';
+
+/// Return the character displayed in the text panel for the byte at `pos`
+/// in `window`. Only UTF-8 looks at the surrounding bytes, which allows
+/// characters to be decoded across group and row boundaries.
+pub(crate) fn text_char(encoding: TextEncoding, window: &[u8], pos: usize) -> char {
+    let b = window[pos];
+    let c = match encoding {
+        TextEncoding::Ascii => Some(b as char).filter(|c| c.is_ascii_graphic() || *c == ' '),
+        TextEncoding::Latin1 => displayable(b as char),
+        TextEncoding::Cp437 => match b {
+            0x00..=0x7E => Some(b as char).filter(|c| c.is_ascii_graphic() || *c == ' '),
+            0x7F => Some('⌂'),
+            _ => displayable(CP437_HIGH[b as usize - 0x80]),
+        },
+        TextEncoding::Ebcdic => displayable(CP037[b as usize]),
+        TextEncoding::Utf8 => return utf8_char(window, pos),
+    };
+    c.unwrap_or(NON_PRINTABLE)
+}
+
+fn utf8_char(window: &[u8], pos: usize) -> char {
+    let b = window[pos];
+    match b {
+        0x00..=0x7F => Some(b as char)
+            .filter(|c| c.is_ascii_graphic() || *c == ' ')
+            .unwrap_or(NON_PRINTABLE),
+        0x80..=0xBF => {
+            // a continuation byte is part of a character if one of the
+            // preceding bytes starts a valid sequence which covers it
+            let covered = (1..=3).filter(|back| *back <= pos).any(|back| {
+                let start = pos - back;
+                decode_utf8(&window[start..]).is_some_and(|(_, len)| len > back)
+            });
+            if covered {
+                UTF8_CONTINUATION
+            } else {
+                NON_PRINTABLE
+            }
+        }
+        _ => decode_utf8(&window[pos..])
+            .and_then(|(c, _)| displayable(c))
+            .unwrap_or(NON_PRINTABLE),
+    }
+}
+
+/// Decode the multi-byte UTF-8 sequence at the start of `bytes`,
+/// returning the character and the length of the sequence.
+fn decode_utf8(bytes: &[u8]) -> Option<(char, usize)> {
+    let len = match bytes.first()? {
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return None,
+    };
+    let s = core::str::from_utf8(bytes.get(..len)?).ok()?;
+    s.chars().next().map(|c| (c, len))
+}
+
+/// Filter out characters which would not take up exactly one column:
+/// control characters, whitespace other than the space, and common
+/// zero-width characters.
+fn displayable(c: char) -> Option<char> {
+    match c {
+        ' ' => Some(c),
+        '\u{AD}' | '\u{300}'..='\u{36F}' | '\u{200B}'..='\u{200F}' | '\u{2028}'..='\u{202E}' => {
+            None
+        }
+        '\u{FE00}'..='\u{FE0F}' | '\u{FEFF}' => None,
+        _ if c.is_control() || c.is_whitespace() => None,
+        _ => Some(c),
+    }
+}
+
+/// Characters of code page 437 for the bytes `0x80..=0xFF`.
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', 'É', 'æ', 'Æ',
+    'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó', 'ú', 'ñ', 'Ñ',
+    'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕',
+    '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦',
+    '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐',
+    '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±',
+    '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+/// Characters of EBCDIC code page 037 for every byte.
+const CP037: [char; 256] = [
+    '\u{0}', '\u{1}', '\u{2}', '\u{3}', '\u{9C}', '\u{9}', '\u{86}', '\u{7F}', '\u{97}', '\u{8D}',
+    '\u{8E}', '\u{B}', '\u{C}', '\u{D}', '\u{E}', '\u{F}', '\u{10}', '\u{11}', '\u{12}', '\u{13}',
+    '\u{9D}', '\u{85}', '\u{8}', '\u{87}', '\u{18}', '\u{19}', '\u{92}', '\u{8F}', '\u{1C}',
+    '\u{1D}', '\u{1E}', '\u{1F}', '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{A}',
+    '\u{17}', '\u{1B}', '\u{88}', '\u{89}', '\u{8A}', '\u{8B}', '\u{8C}', '\u{5}', '\u{6}',
+    '\u{7}', '\u{90}', '\u{91}', '\u{16}', '\u{93}', '\u{94}', '\u{95}', '\u{96}', '\u{4}',
+    '\u{98}', '\u{99}', '\u{9A}', '\u{9B}', '\u{14}', '\u{15}', '\u{9E}', '\u{1A}', ' ', '\u{A0}',
+    'â', 'ä', 'à', 'á', 'ã', 'å', 'ç', 'ñ', '¢', '.', '<', '(', '+', '|', '&', 'é', 'ê', 'ë', 'è',
+    'í', 'î', 'ï', 'ì', 'ß', '!', '$', '*', ')', ';', '¬', '-', '/', 'Â', 'Ä', 'À', 'Á', 'Ã', 'Å',
+    'Ç', 'Ñ', '¦', ',', '%', '_', '>', '?', 'ø', 'É', 'Ê', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', '`', ':',
+    '#', '@', '\'', '=', '"', 'Ø', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', '«', '»', 'ð', 'ý',
+    'þ', '±', '°', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 'ª', 'º', 'æ', '¸', 'Æ', '¤', 'µ',
+    '~', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '¡', '¿', 'Ð', 'Ý', 'Þ', '®', '^', '£', '¥', '·',
+    '©', '§', '¶', '¼', '½', '¾', '[', ']', '¯', '¨', '´', '×', '{', 'A', 'B', 'C', 'D', 'E', 'F',
+    'G', 'H', 'I', '\u{AD}', 'ô', 'ö', 'ò', 'ó', 'õ', '}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q',
+    'R', '¹', 'û', 'ü', 'ù', 'ú', 'ÿ', '\\', '÷', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '²', 'Ô',
+    'Ö', 'Ò', 'Ó', 'Õ', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '³', 'Û', 'Ü', 'Ù', 'Ú',
+    '\u{9F}',
+];
@@ -0,0 +1,128 @@
+use alloc::format;
+
+use crate::{
+    checksum::ChecksumState,
+    options::{Footer, HexdOptions},
+    reader::ReadBytes,
+    rows::ElidedRun,
+    writer::WriteHexdump,
+    HexdumpLineWriter, ReadWriteError, RowBuffer, RowBytes,
+};
+
+/// The totals of a dump which are reported in its [footer](Footer).
+#[derive(Debug, Clone)]
+pub(crate) struct Summary {
+    /// The offset of the first byte read, if any.
+    start: Option<usize>,
+    bytes: usize,
+    elided_rows: usize,
+    elided_bytes: usize,
+    /// The number of bytes which the reader has no value for. These are
+    /// not included in the checksum.
+    unknown: usize,
+    /// Set if every byte of the last row is unknown, so that elided
+    /// rows which repeat it are unknown as well.
+    last_unknown: bool,
+    checksum: Option<ChecksumState>,
+    /// The bytes of the last row, which elided rows repeat. Only kept
+    /// if they are needed for the checksum.
+    last_row: Option<RowBytes>,
+}
+
+impl Summary {
+    /// Return a new instance if `options` include a footer.
+    pub(crate) fn for_options(options: &HexdOptions) -> Option<Self> {
+        match options.footer {
+            Footer::Hidden => None,
+            Footer::Summary(checksum) => Some(Self {
+                start: None,
+                bytes: 0,
+                elided_rows: 0,
+                elided_bytes: 0,
+                unknown: 0,
+                last_unknown: false,
+                checksum: checksum.map(ChecksumState::new),
+                last_row: None,
+            }),
+        }
+    }
+
+    pub(crate) fn add_row(&mut self, row: &RowBuffer) {
+        self.start.get_or_insert(row.elt_index);
+        self.bytes += row.length;
+        self.unknown += row.unknown.iter().map(|r| r.len()).sum::<usize>();
+        self.last_unknown = row.is_all_unknown();
+        if let Some(checksum) = self.checksum.as_mut() {
+            // only the known bytes between the unknown ones are checksummed
+            let bytes = row.buffer.as_slice();
+            let mut known = 0;
+            for unknown in &row.unknown {
+                checksum.update(&bytes[known..unknown.start]);
+                known = unknown.end;
+            }
+            checksum.update(&bytes[known..]);
+            self.last_row = Some(row.buffer.clone());
+        }
+    }
+
+    pub(crate) fn add_elision(&mut self, run: &ElidedRun) {
+        self.bytes += run.end - run.start;
+        self.elided_rows += run.rows;
+        self.elided_bytes += run.end - run.start;
+        if self.last_unknown {
+            self.unknown += run.end - run.start;
+            return;
+        }
+        if let (Some(checksum), Some(row)) = (self.checksum.as_mut(), self.last_row.as_ref()) {
+            for _ in 0..run.rows {
+                checksum.update(row.as_slice());
+            }
+        }
+    }
+}
+
+impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
+    /// Write the summary line below the last row.
+    pub(crate) fn write_footer(&mut self) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        let (Footer::Summary(checksum), Some(summary)) = (self.options.footer, self.summary.take())
+        else {
+            return Ok(());
+        };
+        let byte_hint = self.rows.line_iterator.reader.total_byte_hint();
+        let start = summary.start.unwrap_or(self.options.print_range.skip);
+        let plural = |n: usize, unit: &str| {
+            if n == 1 {
+                format!("{n} {unit}")
+            } else {
+                format!("{n} {unit}s")
+            }
+        };
+
+        let f = &mut self.formatter;
+        f.push_slice(format!("total: {}, range: ", plural(summary.bytes, "byte")).as_bytes());
+        f.write_index_digits(start, byte_hint);
+        f.push_slice(b"-");
+        f.write_index_digits(start + summary.bytes, byte_hint);
+        f.push_slice(
+            format!(
+                ", elided: {} ({})",
+                plural(summary.elided_rows, "row"),
+                plural(summary.elided_bytes, "byte")
+            )
+            .as_bytes(),
+        );
+        if summary.unknown > 0 {
+            f.push_slice(format!(", unknown: {}", plural(summary.unknown, "byte")).as_bytes());
+        }
+        if let (Some(checksum), Some(state)) = (checksum, summary.checksum) {
+            let (value, width) = (state.finish(), checksum.width());
+            let value = if self.options.uppercase {
+                format!("{value:0width$X}")
+            } else {
+                format!("{value:0width$x}")
+            };
+            f.push_slice(format!(", {}: {value}", checksum.name()).as_bytes());
+        }
+        self.flush_line()
+    }
+}
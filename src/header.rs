@@ -0,0 +1,91 @@
+use alloc::string::String;
+
+use crate::{
+    options::{Header, HexdOptions},
+    reader::ReadBytes,
+    writer::WriteHexdump,
+    HexdumpLineWriter, LineFormatter, ReadWriteError, LOWER_LUT, UPPER_LUT,
+};
+
+impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
+    /// Write a header above the row at `row_index` if one is due.
+    pub(crate) fn write_header_before(
+        &mut self,
+        row_index: usize,
+    ) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        let due = match (self.options.header, self.rows_since_header) {
+            (Header::Hidden, _) => false,
+            (_, None) => true,
+            (Header::Every(n), Some(rows)) => n > 0 && rows == n,
+            (Header::Top, Some(_)) => false,
+        };
+        if due {
+            let byte_hint = self.rows.line_iterator.reader.total_byte_hint();
+            let style = self.options.color.map(|p| p.index).unwrap_or_default();
+            self.formatter.push_style(style);
+            self.formatter.write_index_padding(row_index, byte_hint);
+            self.formatter.write_header_labels();
+            self.formatter.trim_line_end();
+            self.formatter.push_reset(style);
+            self.flush_line()?;
+            self.rows_since_header = Some(0);
+        }
+        if let Some(rows) = self.rows_since_header.as_mut() {
+            *rows += 1;
+        }
+        Ok(())
+    }
+}
+
+impl<W: WriteHexdump> LineFormatter<W> {
+    /// Write the offset of each column within a row below the bytes of the
+    /// column, followed by the last digit of each offset above the text panel.
+    pub(crate) fn write_header_labels(&mut self) {
+        let radix = self.options.index_format.base.radix() as usize;
+        let lut = if self.options.uppercase {
+            &UPPER_LUT
+        } else {
+            &LOWER_LUT
+        };
+        let cell_width = self.options.base.cell_width();
+
+        for i in 0..self.options.elt_width() {
+            // at least two digits, right-aligned in the cell, keeping
+            // only the lowest digits of offsets which do not fit
+            let mut label = [b' '; 8];
+            let mut v = i;
+            for (n, c) in label[..cell_width].iter_mut().rev().enumerate() {
+                if v == 0 && n >= 2 {
+                    break;
+                }
+                *c = lut[v % radix];
+                v /= radix;
+            }
+            self.push_slice(&label[..cell_width]);
+            self.write_cell_spacing(i);
+        }
+
+        if self.options.show_ascii {
+            self.push_slice(b" ");
+            for i in 0..self.options.elt_width() {
+                self.push_slice(&[lut[i % radix]]);
+            }
+        }
+    }
+}
+
+/// Return the labels of a header row written with `options`, without the
+/// index column or surrounding whitespace.
+pub(crate) fn header_labels(options: &HexdOptions) -> String {
+    let mut formatter = LineFormatter::new(
+        String::new(),
+        HexdOptions {
+            color: None,
+            ..options.clone()
+        },
+    );
+    formatter.write_header_labels();
+    formatter.trim_line_end();
+    let _ = formatter.flush_line();
+    formatter.writer.trim().into()
+}
@@ -1,26 +1,84 @@
-#![doc = include_str!("../README.md")]
+// the examples of the readme print to stdout
+#![cfg_attr(feature = "std", doc = include_str!("../README.md"))]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::{
+extern crate alloc;
+
+use alloc::{format, string::String, vec::Vec};
+use core::{
     cmp::{max, min},
-    fmt::Debug,
-    io::{BufReader, Write},
+    fmt::{Debug, Display, Formatter},
+    ops::{Range, RangeBounds},
 };
+#[cfg(feature = "std")]
+use std::io::{BufReader, Write};
 
+use annotation::{Annotation, Annotations, Highlight};
+use diff::HexdDiff;
+use footer::Summary;
 use options::{
-    Endianness, Grouping, HexdOptions, HexdOptionsBuilder, IndexOffset, LeadingZeroChar, Spacing,
+    ByteClass, Color, ElisionMode, Endianness, Grouping, HexdOptions, HexdOptionsBuilder,
+    IndexBase, IndexOffset, LeadingZeroChar, MarkerPart, OptionsError, OutputFormat, Placeholder,
+    SourceFormat, Spacing, Style, TextEncoding,
 };
+#[cfg(feature = "std")]
+use reader::IOReader;
 use reader::{
-    ByteSliceReader, EndianBytes, GroupedIteratorReader, GroupedSliceByteReader, IOReader,
+    ByteSliceReader, EndianBytes, GroupedIteratorReader, GroupedSliceByteReader,
     IteratorByteReader, ReadBytes,
 };
-use writer::{IOWriter, WriteHexdump};
+use rows::{ElidedRun, Line, Rows};
+#[cfg(feature = "std")]
+use writer::IOWriter;
+use writer::{FmtWriter, WriteHexdump};
+
+/// Labeled byte ranges which are highlighted in a dump. See [`Annotation`](annotation::Annotation).
+pub mod annotation;
+
+/// Hexdumps of asynchronous byte streams. See [`AsyncHexd`](asynchronous::AsyncHexd).
+pub mod asynchronous;
+
+mod checksum;
+
+/// Side-by-side and interleaved diffs of two byte sources. See [`HexdDiff`](diff::HexdDiff).
+pub mod diff;
+
+/// Listing and dumping the sections and segments of ELF files. See [`Elf`](elf::Elf).
+pub mod elf;
+
+mod encoding;
+
+mod footer;
+
+mod header;
+
+/// Memory-mapped file input. See [`Mmap`](mmap::Mmap).
+#[cfg(all(feature = "mmap", target_os = "linux"))]
+pub mod mmap;
 
 /// All [`Hexd`] options.
 pub mod options;
 
+/// Parsing of hexdumps back into bytes. See [`HexdParser`](parser::HexdParser).
+pub mod parser;
+
+/// Reading the memory of live processes on Linux. See [`ProcessMemory`](process::ProcessMemory).
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod process;
+
 /// A collection of [reader](reader::ReadBytes) types that wrap common data types.
 pub mod reader;
 
+/// Structured rows of a dump, for laying out its bytes yourself. See [`Rows`](rows::Rows).
+pub mod rows;
+
+mod plain;
+
+/// Decoding of binary layouts into annotated fields. See [`Schema`](schema::Schema).
+pub mod schema;
+
+mod source;
+
 /// The [`WriteHexdump`] trait and several foreign type implementations.
 pub mod writer;
 
@@ -51,13 +109,14 @@ impl ToHex for u8 {
     }
 }
 
-#[inline]
-fn trunc_ceil_usize(n: usize, trunc: usize) -> usize {
-    let rem = n / trunc;
-    match rem {
-        0 => rem * trunc,
-        _ => (rem + 1) * trunc,
+/// The number of digits of `n` in base `radix`.
+fn digit_count(mut n: usize, radix: usize) -> usize {
+    let mut digits = 1;
+    while n >= radix {
+        n /= radix;
+        digits += 1;
     }
+    digits
 }
 
 trait HexVisualWidth {
@@ -82,8 +141,8 @@ struct StackBuffer<const N: usize> {
     len: usize,
 }
 
-impl<const N: usize> std::fmt::Debug for StackBuffer<N> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<const N: usize> core::fmt::Debug for StackBuffer<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("StackBuffer")
             .field("slice", &self.as_slice())
             .field("len", &self.len)
@@ -98,7 +157,7 @@ impl<const N: usize> StackBuffer<N> {
             len: 0,
         }
     }
-    fn as_slice<'a>(&'a self) -> &'a [u8] {
+    fn as_slice(&self) -> &[u8] {
         &self.buffer[..self.len]
     }
 
@@ -106,7 +165,7 @@ impl<const N: usize> StackBuffer<N> {
         self.len = 0
     }
 
-    fn as_mut_slice<'a>(&'a mut self) -> &'a mut [u8] {
+    fn as_mut_slice(&mut self) -> &mut [u8] {
         self.buffer.as_mut_slice()
     }
 
@@ -117,19 +176,23 @@ impl<const N: usize> StackBuffer<N> {
     }
 
     fn check_extension(&self, extend_by: usize) {
-        if self.len + extend_by >= N {
+        if !self.has_room_for(extend_by) {
             panic!("Stack-based buffer overflow");
         }
     }
 
+    fn has_room_for(&self, extend_by: usize) -> bool {
+        self.len + extend_by <= N
+    }
+
     fn extend_from_slice(&mut self, other: &[u8]) {
         self.check_extension(other.len());
         self.buffer[self.len..self.len + other.len()].copy_from_slice(other);
         self.len += other.len();
     }
 
-    fn as_str<'a>(&'a self) -> &'a str {
-        std::str::from_utf8(self.as_slice()).unwrap()
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(self.as_slice()).unwrap()
     }
 }
 
@@ -139,22 +202,206 @@ impl<const N: usize> AsRef<[u8]> for StackBuffer<N> {
     }
 }
 
+/// The bytes of a row. Rows of up to [`MAX_BUFFER_SIZE`] bytes are kept on
+/// the stack, while wider rows are allocated on the heap.
+// the stack variant is the point of this type, so it is not boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+enum RowBytes {
+    Stack(StackBuffer<MAX_BUFFER_SIZE>),
+    Heap(Vec<u8>),
+}
+
+impl RowBytes {
+    /// Return an empty buffer which can hold `capacity` bytes.
+    fn with_capacity(capacity: usize) -> Self {
+        if capacity <= MAX_BUFFER_SIZE {
+            Self::Stack(StackBuffer::new())
+        } else {
+            Self::Heap(Vec::with_capacity(capacity))
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Stack(b) => b.as_slice(),
+            Self::Heap(v) => v.as_slice(),
+        }
+    }
+
+    fn extend_from_slice(&mut self, other: &[u8]) {
+        match self {
+            Self::Stack(b) if b.has_room_for(other.len()) => b.extend_from_slice(other),
+            Self::Stack(b) => {
+                let mut v = Vec::with_capacity(b.len + other.len());
+                v.extend_from_slice(b.as_slice());
+                v.extend_from_slice(other);
+                *self = Self::Heap(v);
+            }
+            Self::Heap(v) => v.extend_from_slice(other),
+        }
+    }
+
+    /// Grow the buffer to `len` bytes with `fill`, which is given the space
+    /// after the current bytes and returns how much of it it filled.
+    fn fill_to<E>(
+        &mut self,
+        len: usize,
+        fill: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+    ) -> Result<usize, E> {
+        if let Self::Stack(b) = self {
+            if len > MAX_BUFFER_SIZE {
+                *self = Self::Heap(b.as_slice().to_vec());
+            }
+        }
+        match self {
+            Self::Stack(b) => {
+                let start = b.len;
+                let n = fill(&mut b.as_mut_slice()[start..len])?;
+                b.len += n;
+                Ok(n)
+            }
+            Self::Heap(v) => {
+                let start = v.len();
+                v.resize(len, 0);
+                match fill(&mut v[start..]) {
+                    Ok(n) => {
+                        v.truncate(start + n);
+                        Ok(n)
+                    }
+                    Err(e) => {
+                        v.truncate(start);
+                        Err(e)
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for RowBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for RowBytes {}
+
 #[derive(Debug, Clone)]
 struct RowBuffer {
-    buffer: StackBuffer<MAX_BUFFER_SIZE>,
+    buffer: RowBytes,
     length: usize,
     row_index: usize,
     elt_index: usize,
+    /// Up to [`UTF8_CONTEXT`] bytes read before and after the row, used
+    /// to decode UTF-8 characters which span row boundaries.
+    preceding: StackBuffer<{ UTF8_CONTEXT + 1 }>,
+    following: StackBuffer<{ UTF8_CONTEXT + 1 }>,
+    /// The positions in `buffer` of bytes which the reader has no value
+    /// for. These are zero in `buffer`.
+    unknown: Vec<Range<usize>>,
 }
 
 impl RowBuffer {
     fn is_right_aligned(&self) -> bool {
         self.elt_index != self.row_index
     }
+
+    fn is_unknown(&self, p: usize) -> bool {
+        self.unknown.iter().any(|r| r.contains(&p))
+    }
+
+    fn is_all_unknown(&self) -> bool {
+        self.unknown.first() == Some(&(0..self.buffer.len()))
+    }
 }
 
+/// The widest row which is kept on the stack.
 const MAX_BUFFER_SIZE: usize = 256;
 
+/// The number of bytes around a row needed to decode the UTF-8 characters
+/// which overlap it.
+const UTF8_CONTEXT: usize = 3;
+
+/// The size of the buffer used to build a line. Lines which are longer than this
+/// (e.g. wide colored rows) are written to the output in several pieces.
+const LINE_BUFFER_SIZE: usize = 512;
+
+/// Enough digits for any index, i.e. `usize::MAX` in octal.
+const MAX_INDEX_DIGITS: usize = usize::BITS.div_ceil(3) as usize;
+
+const SGR_RESET: &[u8] = b"\x1b[0m";
+
+/// Build the ANSI SGR escape sequence which enables the given style.
+fn sgr_sequence(style: &Style) -> StackBuffer<48> {
+    fn push_u8(buf: &mut StackBuffer<48>, n: u8) {
+        if n >= 100 {
+            buf.push(b'0' + n / 100);
+        }
+        if n >= 10 {
+            buf.push(b'0' + (n / 10) % 10);
+        }
+        buf.push(b'0' + n % 10);
+    }
+
+    fn push_color(buf: &mut StackBuffer<48>, color: Color, base: u8) {
+        let code = match color {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::BrightBlack => 60,
+            Color::BrightRed => 61,
+            Color::BrightGreen => 62,
+            Color::BrightYellow => 63,
+            Color::BrightBlue => 64,
+            Color::BrightMagenta => 65,
+            Color::BrightCyan => 66,
+            Color::BrightWhite => 67,
+            Color::Fixed(n) => {
+                push_u8(buf, base + 8);
+                buf.extend_from_slice(b";5;");
+                push_u8(buf, n);
+                buf.push(b';');
+                return;
+            }
+        };
+        push_u8(buf, base + code);
+        buf.push(b';');
+    }
+
+    let mut sgr = StackBuffer::<48>::new();
+    sgr.extend_from_slice(b"\x1b[");
+    for (enabled, code) in [
+        (style.bold, b"1;"),
+        (style.dim, b"2;"),
+        (style.underline, b"4;"),
+        (style.reverse, b"7;"),
+    ] {
+        if enabled {
+            sgr.extend_from_slice(code);
+        }
+    }
+    if let Some(fg) = style.foreground {
+        push_color(&mut sgr, fg, 30);
+    }
+    if let Some(bg) = style.background {
+        push_color(&mut sgr, bg, 40);
+    }
+    // replace the trailing separator with the terminator
+    sgr.len -= 1;
+    sgr.push(b'm');
+    sgr
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum HexdumpLineIteratorState {
     NotStarted,
@@ -168,52 +415,87 @@ struct HexdumpLineIterator<R: ReadBytes> {
     options: HexdOptions,
     state: HexdumpLineIteratorState,
     elision_match: Option<ElisionMatch>,
+    /// Bytes read past the end of the previous row, which start the next one.
+    lookahead: StackBuffer<{ UTF8_CONTEXT + 1 }>,
+    /// The last bytes of the previous row.
+    trailing: StackBuffer<{ UTF8_CONTEXT + 1 }>,
+    /// Unknown bytes which the reader skipped past the end of the previous
+    /// row, which start the next one.
+    unknown_ahead: usize,
+    /// Whether runs of unknown rows are elided in one step, rather than
+    /// by reading each of their rows.
+    pub(crate) collapse_unknown: bool,
+}
+
+/// Where [`read_fully`](HexdumpLineIterator::read_fully) records the
+/// bytes which the reader has no value for.
+struct UnknownBytes<'a> {
+    /// The positions of the unknown bytes in the row.
+    ranges: &'a mut Vec<Range<usize>>,
+    /// The position in the row of the buffer which is being filled.
+    offset: usize,
+    /// The number of unknown bytes which were skipped past the buffer.
+    ahead: &'a mut usize,
+    /// The most bytes which may be skipped from the start of the buffer.
+    max_skip: usize,
 }
 
 #[derive(Debug, Clone)]
 struct ElisionMatch {
-    buffer: StackBuffer<MAX_BUFFER_SIZE>,
+    buffer: RowBytes,
+    /// Set if every byte of the row is unknown, in which case the rows
+    /// which follow it are elided if they are unknown as well.
+    unknown: bool,
 }
 
 impl ElisionMatch {
     fn try_match(row: &RowBuffer, options: &HexdOptions) -> Option<Self> {
         let buffer = &row.buffer;
-        match options.grouping {
-            _ if buffer.len != options.elt_width() => None,
-            Grouping::Ungrouped {
-                byte_count: _,
-                spacing: _,
-            } => {
-                let sc = buffer.buffer[0];
-                if buffer.as_slice().iter().all(|b| *b == sc) {
-                    Some(ElisionMatch {
-                        buffer: buffer.clone(),
-                    })
-                } else {
-                    None
-                }
-            }
-            Grouping::Grouped {
-                group_size,
-                num_groups: _,
-                byte_spacing: _,
-                group_spacing: _,
-            } => {
-                let group_size = group_size.element_count();
-                let s = &buffer.as_slice()[..group_size];
-                if s.chunks(group_size).all(|chunk| chunk == s) {
-                    Some(ElisionMatch {
-                        buffer: buffer.clone(),
-                    })
-                } else {
-                    None
-                }
+        let bytes = buffer.as_slice();
+        let unit = match (options.elision.mode, options.grouping) {
+            _ if bytes.is_empty() || bytes.len() != options.elt_width() => return None,
+            _ if row.is_all_unknown() => {
+                return Some(ElisionMatch {
+                    buffer: buffer.clone(),
+                    unknown: true,
+                })
             }
+            _ if !row.unknown.is_empty() => return None,
+            (ElisionMode::Repeated, _) => bytes.len(),
+            (
+                ElisionMode::Uniform,
+                Grouping::Ungrouped {
+                    byte_count: _,
+                    spacing: _,
+                },
+            ) => 1,
+            (
+                ElisionMode::Uniform,
+                Grouping::Grouped {
+                    group_size,
+                    num_groups: _,
+                    byte_spacing: _,
+                    group_spacing: _,
+                },
+            ) => group_size.element_count(),
+        };
+        let first = &bytes[..unit];
+        if bytes.chunks(unit).all(|chunk| chunk == first) {
+            Some(ElisionMatch {
+                buffer: buffer.clone(),
+                unknown: false,
+            })
+        } else {
+            None
         }
     }
 
     fn matches(&self, row: &RowBuffer, options: &HexdOptions) -> bool {
-        if row.buffer.len == options.elt_width() {
+        if row.buffer.len() != options.elt_width() {
+            false
+        } else if self.unknown {
+            row.is_all_unknown()
+        } else if row.unknown.is_empty() {
             self.buffer == row.buffer
         } else {
             false
@@ -221,7 +503,7 @@ impl ElisionMatch {
     }
 }
 
-impl<'a, R: ReadBytes> HexdumpLineIterator<R> {
+impl<R: ReadBytes> HexdumpLineIterator<R> {
     pub fn new(reader: R, options: HexdOptions) -> Self {
         Self {
             reader,
@@ -229,30 +511,198 @@ impl<'a, R: ReadBytes> HexdumpLineIterator<R> {
             options,
             state: HexdumpLineIteratorState::NotStarted,
             elision_match: None,
+            lookahead: StackBuffer::new(),
+            trailing: StackBuffer::new(),
+            unknown_ahead: 0,
+            collapse_unknown: true,
         }
     }
 
     fn read_into_buffer(&mut self, len: usize) -> Result<RowBuffer, R::Error> {
-        let mut buffer = StackBuffer::<MAX_BUFFER_SIZE>::new();
-
-        let actually_read_len = {
-            let n = self.reader.next_n(&mut buffer.as_mut_slice()[..len])?;
-            n.len()
-        };
+        let mut buffer = RowBytes::with_capacity(len);
+
+        // bytes which were read ahead for the previous row come first
+        let stashed = min(len, self.lookahead.len);
+        buffer.extend_from_slice(&self.lookahead.as_slice()[..stashed]);
+        self.lookahead
+            .buffer
+            .copy_within(stashed..self.lookahead.len, 0);
+        self.lookahead.len -= stashed;
+
+        // then unknown bytes which were skipped for the previous row, which
+        // are only left once the stashed bytes are used up
+        let mut unknown = Vec::new();
+        let ahead = min(len - stashed, self.unknown_ahead);
+        if ahead > 0 {
+            buffer.fill_to(stashed + ahead, |buf| {
+                buf.fill(0);
+                Ok::<_, R::Error>(buf.len())
+            })?;
+            unknown.push(stashed..stashed + ahead);
+            self.unknown_ahead -= ahead;
+        }
 
-        buffer.len += actually_read_len;
+        let start = stashed + ahead;
+        let max_skip = self
+            .options
+            .print_range
+            .limit
+            .map(|limit| limit.saturating_sub(self.index + start))
+            .unwrap_or(usize::MAX);
+        let reader = &mut self.reader;
+        let unknown_ahead = &mut self.unknown_ahead;
+        let actually_read_len = start
+            + buffer.fill_to(len, |buf| {
+                let unknown = UnknownBytes {
+                    ranges: &mut unknown,
+                    offset: start,
+                    ahead: unknown_ahead,
+                    max_skip,
+                };
+                Self::read_fully(reader, buf, Some(unknown))
+            })?;
 
-        let o = RowBuffer {
+        let mut o = RowBuffer {
             buffer,
             length: actually_read_len,
             row_index: self.calculate_row_index(),
             elt_index: self.index,
+            preceding: StackBuffer::new(),
+            following: StackBuffer::new(),
+            unknown,
         };
         self.index += actually_read_len;
         self.state = HexdumpLineIteratorState::InProgress;
+
+        if self.context_len() > 0 {
+            self.read_utf8_context(&mut o)?;
+        }
         Ok(o)
     }
 
+    /// Read into `buf` until it is full or the input ends,
+    /// returning the number of bytes read.
+    ///
+    /// If `unknown` is given, bytes which the reader has no value for are
+    /// zeroed and recorded in it. Otherwise reading stops before them.
+    fn read_fully(
+        reader: &mut R,
+        buf: &mut [u8],
+        mut unknown: Option<UnknownBytes>,
+    ) -> Result<usize, R::Error> {
+        // readers may return fewer bytes than requested before the end of
+        // input (e.g. pipes), so keep reading until the buffer is full
+        let mut filled = 0;
+        while filled < buf.len() {
+            let mut n = reader.next_n(&mut buf[filled..])?.len();
+            if n == 0 {
+                let Some(unknown) = unknown.as_mut() else {
+                    break;
+                };
+                // skip as much as the reader allows, so that long runs of
+                // unknown bytes can be elided without reading each row
+                let skipped = reader.skip_unknown(unknown.max_skip.saturating_sub(filled))?;
+                if skipped == 0 {
+                    break;
+                }
+                n = min(skipped, buf.len() - filled);
+                *unknown.ahead += skipped - n;
+                buf[filled..filled + n].fill(0);
+                let (start, end) = (unknown.offset + filled, unknown.offset + filled + n);
+                match unknown.ranges.last_mut() {
+                    Some(last) if last.end == start => last.end = end,
+                    _ => unknown.ranges.push(start..end),
+                }
+            }
+            filled += n;
+        }
+        Ok(filled)
+    }
+
+    /// The number of bytes which are read ahead of each row to decode its text.
+    fn context_len(&self) -> usize {
+        if self.options.encoding == TextEncoding::Utf8 && self.options.show_ascii {
+            UTF8_CONTEXT
+        } else {
+            0
+        }
+    }
+
+    /// Attach the bytes surrounding `row` to it, reading ahead of the row
+    /// without going past the end of the print range.
+    fn read_utf8_context(&mut self, row: &mut RowBuffer) -> Result<(), R::Error> {
+        let remaining = self
+            .options
+            .print_range
+            .limit
+            .map(|limit| limit.saturating_sub(self.index))
+            .unwrap_or(usize::MAX);
+        // the bytes after unknown bytes which were skipped are not read yet
+        let want = if self.unknown_ahead > 0 {
+            0
+        } else {
+            min(UTF8_CONTEXT, remaining)
+        };
+        if self.lookahead.len < want {
+            let start = self.lookahead.len;
+            let buf = &mut self.lookahead.as_mut_slice()[start..want];
+            // unknown bytes are left for the next row to record
+            self.lookahead.len += Self::read_fully(&mut self.reader, buf, None)?;
+        }
+        row.following = self.lookahead.clone();
+
+        row.preceding = self.trailing.clone();
+        let mut trailing = StackBuffer::<{ UTF8_CONTEXT * 2 + 1 }>::new();
+        trailing.extend_from_slice(self.trailing.as_slice());
+        let tail = &row.buffer.as_slice()[row.length.saturating_sub(UTF8_CONTEXT)..];
+        trailing.extend_from_slice(tail);
+        let trailing = &trailing.as_slice()[trailing.len.saturating_sub(UTF8_CONTEXT)..];
+        self.trailing.clear();
+        self.trailing.extend_from_slice(trailing);
+        Ok(())
+    }
+
+    /// Elide the rows of unknown bytes which were skipped along with the
+    /// last row, if it is unknown as well, returning the last of them and
+    /// their number.
+    ///
+    /// The reader skips whole runs of unknown bytes at once, which may span
+    /// far more rows than could be read one by one (e.g. the gaps between
+    /// the mapped regions of a process). Runs which are too
+    /// [short](options::Elision::min_rows) to be elided are read as usual.
+    fn collapse_unknown_rows(&mut self) -> Result<Option<(RowBuffer, usize)>, R::Error> {
+        let width = self.options.elt_width();
+        let rows = self.unknown_ahead / width;
+        let elided = self.options.autoskip
+            && self.collapse_unknown
+            && self.elision_match.as_ref().is_some_and(|m| m.unknown)
+            && rows > self.options.elision.min_rows.max(1);
+        if !elided {
+            return Ok(None);
+        }
+        let mut buffer = RowBytes::with_capacity(width);
+        buffer.fill_to(width, |buf| {
+            buf.fill(0);
+            Ok::<_, R::Error>(buf.len())
+        })?;
+        let index = self.index + (rows - 1) * width;
+        let mut row = RowBuffer {
+            buffer,
+            length: width,
+            row_index: index,
+            elt_index: index,
+            preceding: StackBuffer::new(),
+            following: StackBuffer::new(),
+            unknown: core::iter::once(0..width).collect(),
+        };
+        self.index += rows * width;
+        self.unknown_ahead -= rows * width;
+        if self.context_len() > 0 {
+            self.read_utf8_context(&mut row)?;
+        }
+        Ok(Some((row, rows)))
+    }
+
     fn calculate_row_index(&self) -> usize {
         if !self.options.align {
             self.index
@@ -263,7 +713,9 @@ impl<'a, R: ReadBytes> HexdumpLineIterator<R> {
 }
 
 enum LineIteratorResult {
-    Elided(RowBuffer),
+    /// A row which is identical to the one before it, and the number of
+    /// such rows it stands for, of which it is the last.
+    Elided(RowBuffer, usize),
     Row(RowBuffer),
 }
 
@@ -277,7 +729,10 @@ impl<R: ReadBytes> Iterator for HexdumpLineIterator<R> {
                 if self.state == HexdumpLineIteratorState::NotStarted
                     && self.options.print_range.skip > 0
                 {
-                    self.reader.skip_n(self.options.print_range.skip).unwrap();
+                    if let Err(e) = self.reader.skip_n(self.options.print_range.skip) {
+                        self.state = HexdumpLineIteratorState::Completed;
+                        return Some(Err(e));
+                    }
                     self.index = self.options.print_range.skip;
                 }
 
@@ -286,13 +741,7 @@ impl<R: ReadBytes> Iterator for HexdumpLineIterator<R> {
                         .options
                         .print_range
                         .limit
-                        .map(|limit| {
-                            if self.index < limit {
-                                limit - self.index
-                            } else {
-                                0
-                            }
-                        })
+                        .map(|limit| limit.saturating_sub(self.index))
                         .unwrap_or(self.options.elt_width());
 
                     let ew = min(self.options.elt_width(), limit);
@@ -307,6 +756,16 @@ impl<R: ReadBytes> Iterator for HexdumpLineIterator<R> {
                     }
                 };
 
+                if self.state == HexdumpLineIteratorState::InProgress {
+                    match self.collapse_unknown_rows() {
+                        Ok(Some((row, rows))) => {
+                            return Some(Ok(LineIteratorResult::Elided(row, rows)))
+                        }
+                        Ok(None) => {}
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+
                 self.state = HexdumpLineIteratorState::InProgress;
                 let rowbuffer = self.read_into_buffer(read_len);
 
@@ -319,7 +778,7 @@ impl<R: ReadBytes> Iterator for HexdumpLineIterator<R> {
                 if self.options.autoskip {
                     if let Some(em) = &self.elision_match {
                         if em.matches(&rowbuffer, &self.options) {
-                            return Some(Ok(LineIteratorResult::Elided(rowbuffer)));
+                            return Some(Ok(LineIteratorResult::Elided(rowbuffer, 1)));
                         } else {
                             self.elision_match = None;
                         }
@@ -342,31 +801,63 @@ impl<R: ReadBytes> Iterator for HexdumpLineIterator<R> {
 }
 
 struct HexdumpLineWriter<R: ReadBytes, W: WriteHexdump> {
-    line_iterator: HexdumpLineIterator<R>,
-    writer: W,
-    elided_row: Option<(RowBuffer, usize)>,
-    str_buffer: StackBuffer<512>,
+    rows: Rows<R>,
+    formatter: LineFormatter<W>,
+    /// The number of bytes read from the rows so far.
+    bytes_read: usize,
+    /// The last row read when writing source code, which is held
+    /// back until the next one is read.
+    held_row: Option<RowBuffer>,
+    /// The number of bytes on the current line of plain output.
+    line_len: usize,
+    /// The number of rows written since the last header row, if any.
+    rows_since_header: Option<usize>,
+    /// The totals reported in the footer, if it is written.
+    summary: Option<Summary>,
     options: HexdOptions,
-    flush_idx: usize,
 }
 
 impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
     fn new(reader: R, writer: W, options: HexdOptions) -> Self {
-        let line_iterator = HexdumpLineIterator::new(reader, options.clone());
+        // source code is written with a fixed number of bytes on each line
+        let row_options = match &options.format {
+            OutputFormat::Hexdump => options.clone(),
+            OutputFormat::Source(format) => options
+                .clone()
+                .autoskip(false)
+                .aligned(false)
+                .ungrouped(format.bytes_per_line, Spacing::None),
+            // plain lines are not bound to rows, so read as much as possible at once
+            OutputFormat::Plain { .. } => options
+                .clone()
+                .autoskip(false)
+                .aligned(false)
+                .ungrouped(MAX_BUFFER_SIZE, Spacing::None),
+        };
+        let line_iterator = HexdumpLineIterator::new(reader, row_options);
         Self {
-            line_iterator,
-            writer,
-            elided_row: None,
-            str_buffer: StackBuffer::<512>::new(),
+            rows: Rows::new(line_iterator, options.clone()),
+            formatter: LineFormatter::new(writer, options.clone()),
+            bytes_read: 0,
+            held_row: None,
+            line_len: 0,
+            rows_since_header: None,
+            summary: Summary::for_options(&options),
             options,
-            flush_idx: 0,
         }
     }
 
+    /// Highlight the given annotations in the dump.
+    fn annotated(mut self, annotations: Vec<Annotation>) -> Self {
+        self.rows = self.rows.annotated(annotations.clone());
+        self.formatter.annotations = Annotations::new(annotations);
+        self
+    }
+
     fn do_hexdump(mut self) -> Result<W::Output, R::Error> {
         let r = self.do_hexdump_internal();
         let ll = match r {
-            Ok(_) => Ok(Ok(self.writer)),
+            Ok(_) => Ok(Ok(self.formatter.writer)),
             Err(ReadWriteError::Write(e)) => Ok(Err(e)),
             Err(ReadWriteError::Read(r)) => Err(r),
         }?;
@@ -374,56 +865,111 @@ impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
     }
 
     fn do_hexdump_internal(&mut self) -> Result<(), ReadWriteError<R::Error, W::Error>> {
-        let mut i = 0usize;
-        while let Some(r) = self.line_iterator.next() {
-            let r = r.map_err(ReadWriteError::Read)?;
-            match r {
-                LineIteratorResult::Row(r) => {
-                    if self.elided_row.is_some() {
-                        let (elided_row, start) = self.elided_row.clone().unwrap();
-
-                        if (i - start) > 1 {
-                            self.write_elision();
-                            self.flush_line()?;
-                        }
+        self.write_head()?;
+        while self.write_next()? {}
+        self.write_tail()
+    }
 
-                        self.write_row_index(r.row_index - self.options.elt_width());
-                        self.write_row_bytes(&elided_row);
-                        self.write_row_ascii(&elided_row);
-                        self.flush_line()?;
-                    }
-                    self.elided_row = None;
-                    self.write_row_index(r.row_index);
-                    self.write_row_bytes(&r);
-                    self.write_row_ascii(&r);
-                }
-                LineIteratorResult::Elided(r) => {
-                    if self.elided_row.is_none() {
-                        self.elided_row = Some((r, i));
-                    }
+    /// Write the lines which come before the first row.
+    fn write_head(&mut self) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        match self.options.format.clone() {
+            OutputFormat::Source(format) => self.write_source_head(format),
+            OutputFormat::Hexdump | OutputFormat::Plain { .. } => Ok(()),
+        }
+    }
+
+    /// Read the next row and write the lines which it completes.
+    /// Returns false once there are no more rows.
+    fn write_next(&mut self) -> Result<bool, ReadWriteError<R::Error, W::Error>> {
+        let Some(line) = self.rows.step() else {
+            return Ok(false);
+        };
+        let row = match line.map_err(ReadWriteError::Read)? {
+            None => return Ok(true),
+            Some(Line::Row(row)) => row.row,
+            Some(Line::Elided(run)) => {
+                if let Some(summary) = self.summary.as_mut() {
+                    summary.add_elision(&run);
                 }
+                let byte_hint = self.rows.line_iterator.reader.total_byte_hint();
+                self.formatter.write_elision(&run, byte_hint);
+                self.flush_line()?;
+                return Ok(true);
             }
+        };
+        self.bytes_read += row.length;
+        if let Some(summary) = self.summary.as_mut() {
+            summary.add_row(&row);
+        }
+        match self.options.format {
+            OutputFormat::Hexdump => self.write_row(&row)?,
+            OutputFormat::Source(SourceFormat { language, .. }) => {
+                self.write_source_next(language, row)?
+            }
+            OutputFormat::Plain { bytes_per_line } => self.write_plain_row(bytes_per_line, &row)?,
+        }
+        Ok(true)
+    }
 
-            self.flush_line()?;
-            i += 1;
+    /// Write the lines which come after the last row.
+    fn write_tail(&mut self) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        match self.options.format.clone() {
+            OutputFormat::Hexdump => self.write_footer(),
+            OutputFormat::Source(format) => self.write_source_tail(format),
+            OutputFormat::Plain { .. } => self.flush_line(),
         }
-        if let Some((r, start)) = self.elided_row.clone() {
-            if (i - start) > 1 {
-                self.write_elision();
+    }
+
+    fn write_row(&mut self, row: &RowBuffer) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        let row_index = row.row_index;
+        let byte_hint = self.rows.line_iterator.reader.total_byte_hint();
+        self.write_header_before(row_index)?;
+        self.formatter.write_row_index(row_index, byte_hint);
+        self.formatter.write_row_bytes(row, None);
+        self.formatter.write_row_ascii(row, None);
+        self.formatter.write_legend(row);
+        self.flush_line()?;
+
+        for lane in 0..self.formatter.annotations.lane_count() {
+            if self.formatter.lane_in_row(row, lane) {
+                self.formatter.write_index_padding(row_index, byte_hint);
+                self.formatter.write_annotation_lane(row, lane);
+                self.formatter.trim_line_end();
                 self.flush_line()?;
             }
+        }
+        Ok(())
+    }
 
-            // let row_index = (i - 1) * self.options.elt_width();
-            let row_index = self.line_iterator.index - self.options.elt_width();
+    #[inline]
+    fn flush_line(&mut self) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        self.formatter.flush_line().map_err(ReadWriteError::Write)
+    }
+}
 
-            let elided_row = r;
-            self.write_row_index(row_index);
-            self.write_row_bytes(&elided_row);
-            self.write_row_ascii(&elided_row);
-            self.flush_line()?;
-        };
+/// Renders the parts of a hexdump line (index, bytes, ASCII panel, elision
+/// markers) into a line buffer and hands complete lines to the writer.
+struct LineFormatter<W: WriteHexdump> {
+    writer: W,
+    str_buffer: StackBuffer<LINE_BUFFER_SIZE>,
+    options: HexdOptions,
+    /// Set when part of the current line has already been handed
+    /// to the writer because it did not fit into `str_buffer`.
+    line_spilled: bool,
+    write_error: Option<W::Error>,
+    annotations: Annotations,
+}
 
-        Ok(())
+impl<W: WriteHexdump> LineFormatter<W> {
+    fn new(writer: W, options: HexdOptions) -> Self {
+        Self {
+            writer,
+            str_buffer: StackBuffer::<LINE_BUFFER_SIZE>::new(),
+            options,
+            line_spilled: false,
+            write_error: None,
+            annotations: Annotations::default(),
+        }
     }
 
     #[inline]
@@ -435,46 +981,128 @@ impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
         }
     }
 
-    fn write_row_index(&mut self, row_index: usize) {
+    /// Return the value shown in the index column for the row at `row_index`.
+    fn index_value(&self, row_index: usize) -> usize {
+        let v_index = match self.options.index_offset {
+            IndexOffset::Absolute(o) => {
+                (row_index - min(row_index, self.options.print_range.skip)).saturating_add(o)
+            }
+            IndexOffset::Relative(o) => row_index.saturating_add(o),
+        };
+        v_index / self.options.index_unit()
+    }
+
+    /// Return the number of digits used to write `value` in the index column.
+    fn index_width(&self, value: usize, byte_hint: Option<usize>) -> usize {
+        let format = &self.options.index_format;
+        let largest = byte_hint.map(|h| {
+            let h = match self.options.index_offset {
+                IndexOffset::Absolute(a) => a.saturating_add(h),
+                IndexOffset::Relative(r) => self
+                    .options
+                    .print_range
+                    .skip
+                    .saturating_add(r)
+                    .saturating_add(h),
+            };
+            h / self.options.index_unit()
+        });
+
+        let radix = format.base.radix() as usize;
+        let width = match (largest, format.base) {
+            // hex indices are as wide as whole bytes
+            (Some(l), IndexBase::Hex) => l.hex_visual_width().div_ceil(2) * 2,
+            (Some(l), _) => digit_count(l, radix),
+            // without a length, the minimum width is kept until an index
+            // does not fit, so it can be raised to keep the column stable
+            (None, _) => 0,
+        };
+        let width = max(width, max(format.min_width, 1));
+        let width = format.max_width.map_or(width, |m| min(width, m));
+        // digits of the index itself are never dropped
+        max(width, digit_count(value, radix))
+    }
+
+    fn write_row_index(&mut self, row_index: usize, byte_hint: Option<usize>) {
         if !self.options.show_index {
             return;
         }
 
-        let v_index = match self.options.index_offset {
-            IndexOffset::Absolute(o) => {
-                row_index - min(row_index, self.options.print_range.skip) + o
-            }
-            IndexOffset::Relative(o) => row_index + o,
+        let style = self.options.color.map(|p| p.index).unwrap_or_default();
+        self.push_style(style);
+        self.write_index_digits(row_index, byte_hint);
+        self.push_reset(style);
+        let separator = self.options.index_format.separator.clone();
+        self.push_slice(separator.as_bytes());
+    }
+
+    /// Write the prefix and digits of the index of `row_index`, without any styling.
+    fn write_index_digits(&mut self, row_index: usize, byte_hint: Option<usize>) {
+        let value = self.index_value(row_index);
+        let width = self.index_width(value, byte_hint);
+        let format = self.options.index_format.clone();
+        let lut = if self.options.uppercase {
+            &UPPER_LUT
+        } else {
+            &LOWER_LUT
         };
 
-        let bytes = &v_index.to_be_bytes();
-        let bl = bytes.len();
+        let radix = format.base.radix() as usize;
+        let mut digits = [b'0'; MAX_INDEX_DIGITS];
+        let mut start = digits.len();
+        let mut v = value;
+        while v > 0 {
+            start -= 1;
+            digits[start] = lut[v % radix];
+            v /= radix;
+        }
+        let len = digits.len() - start;
 
-        let slice = self
-            .line_iterator
-            .reader
-            .total_byte_hint()
-            .map(|h| match self.options.index_offset {
-                IndexOffset::Absolute(a) => a + h,
-                IndexOffset::Relative(r) => self.options.print_range.skip + r + h,
-            })
-            .map(|h| (h.hex_visual_width() + 1) / 2)
-            .map(|h| max(h, 4))
-            .map(|h| &bytes[(bl - h)..])
-            .unwrap_or_else(|| {
-                &bytes[(bl - max(4, trunc_ceil_usize(v_index.hex_visual_width() / 2, 2)))..]
-            });
+        self.push_slice(format.prefix.as_bytes());
+        for _ in len..width {
+            self.push_slice(b"0");
+        }
+        self.push_slice(&digits[start..]);
+    }
 
-        for b in slice {
-            let [high, low] = self.u8_to_hex(*b);
-            self.str_buffer.push(high);
-            self.str_buffer.push(low);
+    /// Write blank space as wide as the index written by [`Self::write_row_index`].
+    fn write_index_padding(&mut self, row_index: usize, byte_hint: Option<usize>) {
+        if !self.options.show_index {
+            return;
+        }
+
+        let format = &self.options.index_format;
+        let width = self.index_width(self.index_value(row_index), byte_hint)
+            + format.prefix.chars().count()
+            + format.separator.chars().count();
+        for _ in 0..width {
+            self.push_slice(b" ");
         }
-        self.str_buffer.extend_from_slice(b": ");
     }
 
-    fn write_elision(&mut self) {
-        self.str_buffer.extend_from_slice(b"*");
+    fn write_elision(&mut self, run: &ElidedRun, byte_hint: Option<usize>) {
+        let style = self.options.color.map(|p| p.elision).unwrap_or_default();
+        self.push_style(style);
+        let marker = self.options.elision.marker.clone();
+        for part in marker.parts(run.rows) {
+            match part {
+                MarkerPart::Text(text) => self.push_slice(text.as_bytes()),
+                MarkerPart::Rows => self.push_slice(format!("{}", run.rows).as_bytes()),
+                MarkerPart::Bytes => self.push_slice(format!("{}", run.end - run.start).as_bytes()),
+                MarkerPart::HexBytes => {
+                    let bytes = run.end - run.start;
+                    let hex = if self.options.uppercase {
+                        format!("{bytes:X}")
+                    } else {
+                        format!("{bytes:x}")
+                    };
+                    self.push_slice(hex.as_bytes())
+                }
+                MarkerPart::Start => self.write_index_digits(run.start, byte_hint),
+                MarkerPart::End => self.write_index_digits(run.end, byte_hint),
+            }
+        }
+        self.push_reset(style);
     }
 
     #[inline]
@@ -486,28 +1114,52 @@ impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
         }
     }
 
-    fn write_row_bytes(&mut self, row: &RowBuffer) {
-        let elt_width = self.options.elt_width();
-        for i in 0..elt_width {
-            self.write_byte(self.read_row_byte_aligned(row, i));
-            if i != elt_width - 1 || self.options.show_ascii {
-                self.str_buffer
-                    .extend_from_slice(self.options.grouping.spacing_for_index(i).as_spaces());
+    /// Write the bytes of a row. If `other` is given, bytes which differ
+    /// from the bytes at the same position in `other` are highlighted.
+    fn write_row_bytes(&mut self, row: &RowBuffer, other: Option<&RowBuffer>) {
+        for i in 0..self.options.elt_width() {
+            let style = self.cell_style(row, other, i);
+            if self.is_unknown_aligned(row, i) {
+                self.write_unknown_byte(style);
+            } else {
+                self.write_byte(self.read_row_byte_aligned(row, i), style);
             }
+            self.write_cell_spacing(i);
         }
+    }
 
-        if self.options.show_ascii
-            && self
-                .options
-                .grouping
-                .spacing_for_index(self.options.elt_width() - 1)
-                == Spacing::None
-        {
-            self.str_buffer.push(b' ');
+    /// Write the [placeholder](options::Placeholder) of a byte which the
+    /// reader has no value for, as wide as the digits of a byte.
+    fn write_unknown_byte(&mut self, style: Style) {
+        self.push_style(style);
+        let placeholder = [self.options.placeholder.into(); 8];
+        self.push_slice(&placeholder[..self.options.base.cell_width()]);
+        self.push_reset(style);
+    }
+
+    /// Write the spacing which follows the `i`th byte of a row.
+    #[inline]
+    fn write_cell_spacing(&mut self, i: usize) {
+        self.write_cell_spacing_filled(i, || b' ');
+    }
+
+    /// Write the spacing which follows the `i`th byte of a row
+    /// with the characters returned by `fill` in place of the spaces between bytes.
+    fn write_cell_spacing_filled<F: FnMut() -> u8>(&mut self, i: usize, mut fill: F) {
+        let elt_width = self.options.elt_width();
+        let spacing = self.options.grouping.spacing_for_index(i);
+        if i != elt_width - 1 || self.options.show_ascii {
+            for _ in spacing.as_spaces() {
+                self.push_slice(&[fill()]);
+            }
+        }
+        if i == elt_width - 1 && self.options.show_ascii && spacing == Spacing::None {
+            self.push_slice(b" ");
         }
     }
 
-    fn write_byte(&mut self, b: Option<u8>) {
+    fn write_byte(&mut self, b: Option<u8>, style: Style) {
+        self.push_style(style);
         match (self.options.base, b) {
             (options::Base::Binary, Some(b)) => {
                 let chars = [
@@ -518,17 +1170,17 @@ impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
                     self.bchar_for_u8((b >> 3) & 1),
                     self.bchar_for_u8((b >> 2) & 1),
                     self.bchar_for_u8((b >> 1) & 1),
-                    self.bchar_for_u8((b >> 0) & 1),
+                    self.bchar_for_u8(b & 1),
                 ];
-                self.str_buffer.extend_from_slice(&chars);
+                self.push_slice(&chars);
             }
             (options::Base::Binary, None) => {
-                self.str_buffer.extend_from_slice(b"        ");
+                self.push_slice(b"        ");
             }
 
             (options::Base::Octal(lzc), Some(b)) => {
                 let lead_char: u8 = lzc.into();
-                let cc = [(b >> 6) & 0x7, (b >> 3) & 0x7, (b >> 0) & 0x7];
+                let cc = [(b >> 6) & 0x7, (b >> 3) & 0x7, b & 0x7];
 
                 let chars = [
                     if cc[0] == 0 && cc[1] != 0 {
@@ -543,15 +1195,15 @@ impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
                     },
                     self.bchar_for_u8(cc[2]),
                 ];
-                self.str_buffer.extend_from_slice(&chars);
+                self.push_slice(&chars);
             }
             (options::Base::Octal(_), None) => {
-                self.str_buffer.extend_from_slice(b"   ");
+                self.push_slice(b"   ");
             }
 
             (options::Base::Decimal(lzc), Some(b)) => {
                 let lead_char: u8 = lzc.into();
-                let cc = [(b / 100) % 10, (b / 10) % 10, (b / 1) % 10];
+                let cc = [(b / 100) % 10, (b / 10) % 10, b % 10];
 
                 let chars = [
                     if cc[0] == 0 {
@@ -566,37 +1218,81 @@ impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
                     },
                     self.bchar_for_u8(cc[2]),
                 ];
-                self.str_buffer.extend_from_slice(&chars);
+                self.push_slice(&chars);
             }
             (options::Base::Decimal(_), None) => {
-                self.str_buffer.extend_from_slice(b"   ");
+                self.push_slice(b"   ");
             }
 
             (options::Base::Hex, Some(b)) => {
                 let chars = [
                     self.bchar_for_u8((b >> 4) & 0xf),
-                    self.bchar_for_u8((b >> 0) & 0xf),
+                    self.bchar_for_u8(b & 0xf),
                 ];
-                self.str_buffer.extend_from_slice(&chars);
+                self.push_slice(&chars);
             }
             (options::Base::Hex, None) => {
-                self.str_buffer.extend_from_slice(b"  ");
+                self.push_slice(b"  ");
+            }
+        }
+        self.push_reset(style);
+    }
+
+    /// Return the style of the `i`th byte of a row.
+    #[inline]
+    fn cell_style(&self, row: &RowBuffer, other: Option<&RowBuffer>, i: usize) -> Style {
+        let b = self.read_row_byte_aligned(row, i);
+        match (self.options.color, other) {
+            (Some(palette), Some(other)) if self.read_row_byte_aligned(other, i) != b => {
+                palette.changed
             }
+            _ => self
+                .row_position(row, i)
+                .and_then(|p| self.annotations.style_at(row.elt_index + p))
+                .or_else(|| {
+                    if self.is_unknown_aligned(row, i) {
+                        self.options.color.map(|palette| palette.elision)
+                    } else {
+                        b.map(|b| self.byte_style(b))
+                    }
+                })
+                .unwrap_or_default(),
         }
     }
 
+    /// Return the style of a byte according to its [class](ByteClass).
+    #[inline]
+    fn byte_style(&self, b: u8) -> Style {
+        self.options
+            .color
+            .map(|palette| palette.style_for(ByteClass::of(b)))
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn is_unknown_aligned(&self, row: &RowBuffer, i: usize) -> bool {
+        self.row_position(row, i).is_some_and(|p| row.is_unknown(p))
+    }
+
     #[inline]
     fn read_row_byte_aligned(&self, row: &RowBuffer, i: usize) -> Option<u8> {
+        self.row_position(row, i).map(|p| row.buffer.as_slice()[p])
+    }
+
+    /// Return the position in the row buffer of the byte displayed in
+    /// the `i`th column, if any.
+    #[inline]
+    fn row_position(&self, row: &RowBuffer, i: usize) -> Option<usize> {
         let ee = row.elt_index % self.options.elt_width();
         if self.options.align && row.is_right_aligned() {
-            if i < ee || i >= row.buffer.len + ee {
+            if i < ee || i >= row.buffer.len() + ee {
                 None
             } else {
-                Some(row.buffer.as_slice()[i - ee])
+                Some(i - ee)
             }
         } else {
-            if i < row.buffer.len {
-                Some(row.buffer.as_slice()[i])
+            if i < row.buffer.len() {
+                Some(i)
             } else {
                 None
             }
@@ -604,42 +1300,137 @@ impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
     }
 
     #[inline]
-    fn write_row_ascii(&mut self, row: &RowBuffer) {
+    fn write_row_ascii(&mut self, row: &RowBuffer, other: Option<&RowBuffer>) {
         if !self.options.show_ascii {
             return;
         }
 
-        self.str_buffer.push(b'|');
+        // the bytes around the row are included so that characters
+        // which cross its boundaries can be decoded
+        let mut window = RowBytes::with_capacity(row.buffer.len() + UTF8_CONTEXT * 2);
+        window.extend_from_slice(row.preceding.as_slice());
+        window.extend_from_slice(row.buffer.as_slice());
+        window.extend_from_slice(row.following.as_slice());
+        let offset = row.preceding.len;
+
+        self.push_slice(b"|");
         for i in 0..self.options.elt_width() {
-            let b = self.read_row_byte_aligned(row, i).unwrap_or(b' ');
-            self.str_buffer.push(if Self::is_printable_char(b as char) {
-                b
-            } else {
-                b'.'
-            });
+            let ch = match self.row_position(row, i) {
+                Some(p) if row.is_unknown(p) => u8::from(self.options.placeholder) as char,
+                Some(p) => {
+                    encoding::text_char(self.options.encoding, window.as_slice(), offset + p)
+                }
+                None => ' ',
+            };
+            let style = self.cell_style(row, other, i);
+            self.push_style(style);
+            self.push_slice(ch.encode_utf8(&mut [0u8; 4]).as_bytes());
+            self.push_reset(style);
+        }
+        self.push_slice(b"|");
+    }
+
+    /// Write a line of `^` markers under the bytes of `row` which differ
+    /// from the bytes at the same position in `other`, in both the byte
+    /// columns and the ASCII panel.
+    fn write_row_markers(&mut self, row: &RowBuffer, other: &RowBuffer) {
+        let cell_width = self.options.base.cell_width();
+        for i in 0..self.options.elt_width() {
+            let mark = self.marker_for(row, other, i);
+            for _ in 0..cell_width {
+                self.push_slice(&[mark]);
+            }
+            self.write_cell_spacing(i);
+        }
+
+        if self.options.show_ascii {
+            self.push_slice(b" ");
+            for i in 0..self.options.elt_width() {
+                let mark = self.marker_for(row, other, i);
+                self.push_slice(&[mark]);
+            }
+            self.push_slice(b" ");
         }
-        self.str_buffer.push(b'|');
     }
 
     #[inline]
-    fn is_printable_char(ch: char) -> bool {
-        ch.is_ascii_alphanumeric() || ch.is_ascii_punctuation() || ch == ' '
+    fn marker_for(&self, row: &RowBuffer, other: &RowBuffer, i: usize) -> u8 {
+        if self.read_row_byte_aligned(row, i) != self.read_row_byte_aligned(other, i) {
+            b'^'
+        } else {
+            b' '
+        }
     }
 
+    /// Append bytes to the current line. If they do not fit into the line
+    /// buffer, the buffered part of the line is handed to the writer first,
+    /// along with the bytes themselves if they do not fit into an empty
+    /// buffer either.
     #[inline]
-    fn flush_line(&mut self) -> Result<(), ReadWriteError<R::Error, W::Error>> {
-        if self.str_buffer.len > 0 {
-            self.str_buffer.push(b'\n');
+    fn push_slice(&mut self, s: &[u8]) {
+        if self.str_buffer.has_room_for(s.len()) {
+            self.str_buffer.extend_from_slice(s);
+            return;
         }
-        let s = self.str_buffer.as_str();
-        if s.len() > 0 {
-            self.writer.write_str(s).map_err(ReadWriteError::Write)?;
-            self.writer.line_end().map_err(ReadWriteError::Write)?;
+
+        let fits = s.len() <= LINE_BUFFER_SIZE;
+        // the parts of a line are always whole characters
+        let unbuffered = if fits {
+            ""
+        } else {
+            core::str::from_utf8(s).unwrap()
+        };
+        for part in [self.str_buffer.as_str(), unbuffered] {
+            if self.write_error.is_none() && !part.is_empty() {
+                if let Err(e) = self.writer.write_str(part) {
+                    self.write_error = Some(e);
+                }
+            }
         }
+        self.str_buffer.clear();
+        self.line_spilled = true;
+        if fits {
+            self.str_buffer.extend_from_slice(s);
+        }
+    }
+
+    #[inline]
+    fn push_style(&mut self, style: Style) {
+        if !style.is_plain() {
+            self.push_slice(sgr_sequence(&style).as_slice());
+        }
+    }
 
-        self.flush_idx += 1;
+    #[inline]
+    fn push_reset(&mut self, style: Style) {
+        if !style.is_plain() {
+            self.push_slice(SGR_RESET);
+        }
+    }
+
+    /// Remove trailing spaces from the buffered part of the current line.
+    fn trim_line_end(&mut self) {
+        while self.str_buffer.as_slice().last() == Some(&b' ') {
+            self.str_buffer.len -= 1;
+        }
+    }
+
+    #[inline]
+    fn flush_line(&mut self) -> Result<(), W::Error> {
+        if self.str_buffer.len > 0 || self.line_spilled {
+            self.push_slice(b"\n");
+        }
+        if let Some(e) = self.write_error.take() {
+            return Err(e);
+        }
+        let s = self.str_buffer.as_str();
+        if !s.is_empty() {
+            self.writer.write_str(s)?;
+            self.writer.line_end()?;
+        }
 
         self.str_buffer.clear();
+        self.line_spilled = false;
         Ok(())
     }
 }
@@ -652,26 +1443,46 @@ impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
 pub struct Hexd<R: ReadBytes> {
     reader: R,
     options: HexdOptions,
+    annotations: Vec<Annotation>,
 }
 
 /// A fallible variant of `Hexd` that surfaces errors from the underlying [reader](reader::ReadBytes).
 pub struct FallibleHexd<R: ReadBytes> {
     reader: R,
     options: HexdOptions,
+    annotations: Vec<Annotation>,
 }
 
 impl<R: ReadBytes> Hexd<R> {
     /// Construct a new [`FallibleHexd`] instance with the given reader and [default options](HexdOptions::default).
     pub fn new(reader: R) -> Self {
+        Self::new_with_options(reader, HexdOptions::default())
+    }
+
+    /// Construct a new [`FallibleHexd`] instance with the given reader and options.
+    pub fn new_with_options(reader: R, options: HexdOptions) -> Self {
         Hexd {
             reader,
-            options: HexdOptions::default(),
+            options,
+            annotations: Vec::new(),
         }
     }
 
-    /// Construct a new [`FallibleHexd`] instance with the given reader and options.
-    pub fn new_with_options(reader: R, options: HexdOptions) -> Self {
-        Hexd { reader, options }
+    /// Label the bytes in `range` and highlight them in the dump.
+    /// See [`Annotation`].
+    pub fn annotate<B: RangeBounds<usize>, L: Into<String>>(
+        self,
+        range: B,
+        label: L,
+        highlight: Highlight,
+    ) -> Self {
+        self.annotations([Annotation::new(range, label, highlight)])
+    }
+
+    /// Add several [annotations](Annotation) to the dump.
+    pub fn annotations<I: IntoIterator<Item = Annotation>>(mut self, annotations: I) -> Self {
+        self.annotations.extend(annotations);
+        self
     }
 
     /// Print a hexdump to `stdout`.
@@ -683,6 +1494,7 @@ impl<R: ReadBytes> Hexd<R> {
     ///
     /// v.hexd().dump(); // print a hexdump
     /// ```
+    #[cfg(feature = "std")]
     pub fn dump(self) {
         self.dump_io(std::io::stdout())
             .expect("could not print hexdump to stdout");
@@ -697,6 +1509,7 @@ impl<R: ReadBytes> Hexd<R> {
     ///
     /// v.hexd().dump_err(); // print a hexdump to stderr
     /// ```
+    #[cfg(feature = "std")]
     pub fn dump_err(self) {
         self.dump_io(std::io::stderr())
             .expect("could not print hexdump to stderr");
@@ -710,7 +1523,8 @@ impl<R: ReadBytes> Hexd<R> {
     /// let dump = [0u8; 64].hexd().dump_to::<String>();
     /// ```
     pub fn dump_to<W: WriteHexdump + Default>(self) -> W::Output {
-        let hlw = HexdumpLineWriter::new(self.reader, W::default(), self.options);
+        let hlw = HexdumpLineWriter::new(self.reader, W::default(), self.options)
+            .annotated(self.annotations);
         hlw.do_hexdump().unwrap()
     }
 
@@ -723,10 +1537,49 @@ impl<R: ReadBytes> Hexd<R> {
     /// let dump = [0u8; 64].hexd().dump_into(v);
     /// ```
     pub fn dump_into<W: WriteHexdump>(self, writer: W) -> W::Output {
-        let hlw = HexdumpLineWriter::new(self.reader, writer, self.options);
+        let hlw =
+            HexdumpLineWriter::new(self.reader, writer, self.options).annotated(self.annotations);
         hlw.do_hexdump().unwrap()
     }
 
+    /// Like [`Self::dump_to`], but [validate](HexdOptions::validate) the
+    /// options first instead of panicking if they are invalid.
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::{HexdOptionsBuilder, OptionsError, Spacing}};
+    ///
+    /// let dump = [0u8; 64].hexd().ungrouped(0, Spacing::Normal).try_dump_to::<String>();
+    /// assert_eq!(dump, Err(OptionsError::EmptyRow));
+    /// ```
+    pub fn try_dump_to<W: WriteHexdump + Default>(self) -> Result<W::Output, OptionsError> {
+        self.try_dump_into(W::default())
+    }
+
+    /// Like [`Self::dump_into`], but [validate](HexdOptions::validate) the
+    /// options first instead of panicking if they are invalid.
+    pub fn try_dump_into<W: WriteHexdump>(self, writer: W) -> Result<W::Output, OptionsError> {
+        self.options.validate()?;
+        Ok(self.dump_into(writer))
+    }
+
+    /// Return an iterator over the structured rows of the hexdump,
+    /// regardless of the [output format](HexdOptions::format). See [`Rows`].
+    ///
+    /// ```
+    /// use hxd::{AsHexd, rows::Line};
+    ///
+    /// let mut rows = [0u8; 20].hexd().rows();
+    /// let Some(Ok(Line::Row(first))) = rows.next() else { panic!() };
+    /// assert_eq!(first.bytes(), [0u8; 16]);
+    /// ```
+    pub fn rows(self) -> Rows<R> {
+        Rows::new(
+            HexdumpLineIterator::new(self.reader, self.options.clone()),
+            self.options,
+        )
+        .annotated(self.annotations)
+    }
+
     /// Write a hexdump to an object that is [Write].
     /// The object is wrapped in a [BufWriter](std::io::BufWriter)
     /// for improved performance.
@@ -745,11 +1598,21 @@ impl<R: ReadBytes> Hexd<R> {
     ///
     /// v.hexd().dump_io(f).expect("could not write hexdump to file");
     /// ```
+    #[cfg(feature = "std")]
     pub fn dump_io<W: Write>(self, write: W) -> Result<(), std::io::Error> {
-        let hlw = HexdumpLineWriter::new(self.reader, IOWriter::new(write), self.options);
+        let hlw = HexdumpLineWriter::new(self.reader, IOWriter::new(write), self.options)
+            .annotated(self.annotations);
         hlw.do_hexdump().unwrap()
     }
 
+    /// Like [`Self::dump_io`], but [validate](HexdOptions::validate) the
+    /// options first instead of panicking if they are invalid.
+    #[cfg(feature = "std")]
+    pub fn try_dump_io<W: Write>(self, write: W) -> Result<(), TryDumpError<std::io::Error>> {
+        self.options.validate()?;
+        self.dump_io(write).map_err(TryDumpError::Dump)
+    }
+
     /// Write a hexdump to an object that is [Write].
     /// Unlike [`Self::dump_io`], this method does not wrap the object in a
     /// [BufWriter](std::io::BufWriter).
@@ -768,11 +1631,33 @@ impl<R: ReadBytes> Hexd<R> {
     ///
     /// v.hexd().dump_io_unbuffered(f).expect("could not write hexdump to file");
     /// ```
+    #[cfg(feature = "std")]
     pub fn dump_io_unbuffered<W: Write>(self, write: W) -> Result<(), std::io::Error> {
         let hlw =
-            HexdumpLineWriter::new(self.reader, IOWriter::new_unbuffered(write), self.options);
+            HexdumpLineWriter::new(self.reader, IOWriter::new_unbuffered(write), self.options)
+                .annotated(self.annotations);
         hlw.do_hexdump().unwrap()
     }
+
+    /// Compare this instance with another one, row by row. The options of
+    /// this instance are used for both sides; the options of `other` are ignored.
+    ///
+    /// ```
+    /// use hxd::AsHexd;
+    ///
+    /// let before = b"Hello, world!";
+    /// let after = b"Hello, World!";
+    ///
+    /// let diff = before.hexd().diff(after.hexd()).dump_to::<String>();
+    /// assert_eq!(diff, concat!(
+    ///     "-00000000: 4865 6C6C 6F2C 2077 6F72 6C64 21        |Hello, world!   |\n",
+    ///     "+00000000: 4865 6C6C 6F2C 2057 6F72 6C64 21        |Hello, World!   |\n",
+    ///     "                            ^^                             ^\n",
+    /// ));
+    /// ```
+    pub fn diff<B: ReadBytes>(self, other: Hexd<B>) -> HexdDiff<R, B> {
+        HexdDiff::new_with_options(self.reader, other.reader, self.options)
+    }
 }
 
 /// Wrapper type for an error that can occur during reading or writing.
@@ -784,18 +1669,51 @@ pub enum ReadWriteError<R, W> {
     Write(W),
 }
 
+/// Wrapper type for an error returned by the `try_` dump methods, such as
+/// [`FallibleHexd::try_dump_to`]: either the options were
+/// [invalid](HexdOptions::validate) and nothing was dumped, or dumping failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryDumpError<E> {
+    Options(OptionsError),
+    Dump(E),
+}
+
+impl<E> From<OptionsError> for TryDumpError<E> {
+    fn from(e: OptionsError) -> Self {
+        Self::Options(e)
+    }
+}
+
 impl<R: ReadBytes> FallibleHexd<R> {
     /// Construct a new [`Hexd`] instance with the given reader and [default options](HexdOptions::default).
     pub fn new(reader: R) -> Self {
+        Self::new_with_options(reader, HexdOptions::default())
+    }
+
+    /// Construct a new [`Hexd`] instance with the given reader and options.
+    pub fn new_with_options(reader: R, options: HexdOptions) -> Self {
         Self {
             reader,
-            options: HexdOptions::default(),
+            options,
+            annotations: Vec::new(),
         }
     }
 
-    /// Construct a new [`Hexd`] instance with the given reader and options.
-    pub fn new_with_options(reader: R, options: HexdOptions) -> Self {
-        Self { reader, options }
+    /// Label the bytes in `range` and highlight them in the dump.
+    /// See [`Annotation`].
+    pub fn annotate<B: RangeBounds<usize>, L: Into<String>>(
+        self,
+        range: B,
+        label: L,
+        highlight: Highlight,
+    ) -> Self {
+        self.annotations([Annotation::new(range, label, highlight)])
+    }
+
+    /// Add several [annotations](Annotation) to the dump.
+    pub fn annotations<I: IntoIterator<Item = Annotation>>(mut self, annotations: I) -> Self {
+        self.annotations.extend(annotations);
+        self
     }
 
     /// Print a hexdump to `stdout`.
@@ -808,6 +1726,7 @@ impl<R: ReadBytes> FallibleHexd<R> {
     ///
     /// f.hexd().dump().expect("could not read file"); // print a hexdump
     /// ```
+    #[cfg(feature = "std")]
     pub fn dump(self) -> Result<(), R::Error> {
         match self.dump_io(std::io::stdout()) {
             Ok(()) => Ok(()),
@@ -826,6 +1745,7 @@ impl<R: ReadBytes> FallibleHexd<R> {
     ///
     /// f.hexd().dump_err().expect("could not read file"); // print a hexdump to stderr
     /// ```
+    #[cfg(feature = "std")]
     pub fn dump_err(self) -> Result<(), R::Error> {
         match self.dump_io(std::io::stderr()) {
             Ok(()) => Ok(()),
@@ -837,21 +1757,25 @@ impl<R: ReadBytes> FallibleHexd<R> {
     /// Construct a default instance of `W` and write a hexdump to it, returning its output.
     ///
     /// ```no_run
+    /// # #[cfg(feature = "std")] {
     /// use hxd::IntoFallibleHexd;
     /// use std::fs::OpenOptions;
     ///
     /// let f = OpenOptions::new().read(true).open("file.txt").unwrap();
     ///
     /// let dump = f.hexd().dump_to::<String>().expect("could not read file");
+    /// # }
     /// ```
     pub fn dump_to<W: WriteHexdump + Default>(self) -> Result<W::Output, R::Error> {
-        let hlw = HexdumpLineWriter::new(self.reader, W::default(), self.options);
+        let hlw = HexdumpLineWriter::new(self.reader, W::default(), self.options)
+            .annotated(self.annotations);
         hlw.do_hexdump()
     }
 
     /// Write a hexdump to an instance of `W` and return its output.
     ///
     /// ```no_run
+    /// # #[cfg(feature = "std")] {
     /// use hxd::IntoFallibleHexd;
     /// use std::fs::OpenOptions;
     ///
@@ -859,12 +1783,63 @@ impl<R: ReadBytes> FallibleHexd<R> {
     ///
     /// let v: Vec<String> = Vec::new();
     /// let dump = f.hexd().dump_into(v).expect("could not read file");
+    /// # }
     /// ```
     pub fn dump_into<W: WriteHexdump>(self, writer: W) -> Result<W::Output, R::Error> {
-        let hlw = HexdumpLineWriter::new(self.reader, writer, self.options);
+        let hlw =
+            HexdumpLineWriter::new(self.reader, writer, self.options).annotated(self.annotations);
         hlw.do_hexdump()
     }
 
+    /// Like [`Self::dump_to`], but [validate](HexdOptions::validate) the
+    /// options first instead of panicking if they are invalid.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// use hxd::{IntoFallibleHexd, TryDumpError, options::{HexdOptionsBuilder, OptionsError}};
+    ///
+    /// let f = std::io::Cursor::new([0u8; 64]);
+    ///
+    /// let dump = f.hexd().range(0x20..0x10).try_dump_to::<String>();
+    /// assert!(matches!(
+    ///     dump,
+    ///     Err(TryDumpError::Options(OptionsError::ReversedRange { start: 0x20, end: 0x10 }))
+    /// ));
+    /// # }
+    /// ```
+    pub fn try_dump_to<W: WriteHexdump + Default>(
+        self,
+    ) -> Result<W::Output, TryDumpError<R::Error>> {
+        self.try_dump_into(W::default())
+    }
+
+    /// Like [`Self::dump_into`], but [validate](HexdOptions::validate) the
+    /// options first instead of panicking if they are invalid.
+    pub fn try_dump_into<W: WriteHexdump>(
+        self,
+        writer: W,
+    ) -> Result<W::Output, TryDumpError<R::Error>> {
+        self.options.validate()?;
+        self.dump_into(writer).map_err(TryDumpError::Dump)
+    }
+
+    /// Return an iterator over the structured rows of the hexdump,
+    /// regardless of the [output format](HexdOptions::format). See [`Rows`].
+    pub fn rows(self) -> Rows<R> {
+        Rows::new(
+            HexdumpLineIterator::new(self.reader, self.options.clone()),
+            self.options,
+        )
+        .annotated(self.annotations)
+    }
+
+    /// Compare this hexdump with `other`, using the options of this one.
+    /// Errors from either reader are returned by the fallible methods of
+    /// [`HexdDiff`], such as [`try_dump_to`](HexdDiff::try_dump_to).
+    pub fn diff<B: ReadBytes>(self, other: FallibleHexd<B>) -> HexdDiff<R, B> {
+        HexdDiff::new_with_options(self.reader, other.reader, self.options)
+    }
+
     /// Write a hexdump to an object that is [Write].
     /// The object is wrapped in a [BufWriter](std::io::BufWriter)
     /// for improved performance.
@@ -886,11 +1861,13 @@ impl<R: ReadBytes> FallibleHexd<R> {
     ///     ReadWriteError::Write(e) => panic!("could not write to file: {:?}", e),
     /// });
     /// ```
+    #[cfg(feature = "std")]
     pub fn dump_io<W: Write>(
         self,
         write: W,
     ) -> Result<(), ReadWriteError<R::Error, std::io::Error>> {
-        let hlw = HexdumpLineWriter::new(self.reader, IOWriter::new(write), self.options);
+        let hlw = HexdumpLineWriter::new(self.reader, IOWriter::new(write), self.options)
+            .annotated(self.annotations);
         match hlw.do_hexdump() {
             Ok(Ok(_)) => Ok(()),
             Ok(Err(e)) => Err(ReadWriteError::Write(e)),
@@ -898,6 +1875,17 @@ impl<R: ReadBytes> FallibleHexd<R> {
         }
     }
 
+    /// Like [`Self::dump_io`], but [validate](HexdOptions::validate) the
+    /// options first instead of panicking if they are invalid.
+    #[cfg(feature = "std")]
+    pub fn try_dump_io<W: Write>(
+        self,
+        write: W,
+    ) -> Result<(), TryDumpError<ReadWriteError<R::Error, std::io::Error>>> {
+        self.options.validate()?;
+        self.dump_io(write).map_err(TryDumpError::Dump)
+    }
+
     /// Write a hexdump to an object that is [Write].
     /// Unlike [`Self::dump_io`], this method does not wrap the object in a
     /// [BufWriter](std::io::BufWriter).
@@ -919,12 +1907,14 @@ impl<R: ReadBytes> FallibleHexd<R> {
     ///     ReadWriteError::Write(e) => panic!("could not write to file: {:?}", e),
     /// });
     /// ```
+    #[cfg(feature = "std")]
     pub fn dump_io_unbuffered<W: Write>(
         self,
         write: W,
     ) -> Result<(), ReadWriteError<R::Error, std::io::Error>> {
         let hlw =
-            HexdumpLineWriter::new(self.reader, IOWriter::new_unbuffered(write), self.options);
+            HexdumpLineWriter::new(self.reader, IOWriter::new_unbuffered(write), self.options)
+                .annotated(self.annotations);
         match hlw.do_hexdump() {
             Ok(Ok(_)) => Ok(()),
             Ok(Err(e)) => Err(ReadWriteError::Write(e)),
@@ -944,6 +1934,120 @@ impl<R: ReadBytes> HexdOptionsBuilder for Hexd<R> {
     }
 }
 
+/// A [`Hexd`] whose reader can be cloned can be used in format strings.
+/// It is rendered exactly as [`dump`](Hexd::dump) would print it, writing
+/// each line straight into the formatter.
+///
+/// ```
+/// use hxd::{AsHexd, options::{HexdOptionsBuilder, Spacing}};
+///
+/// let payload = b"ping";
+///
+/// let s = format!("payload:\n{}", payload.hexd().ungrouped(4, Spacing::Normal));
+/// assert_eq!(s, "payload:\n00000000: 70 69 6E 67 |ping|\n");
+/// ```
+impl<R: ReadBytes + Clone> Display for Hexd<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let hexd = Hexd {
+            reader: self.reader.clone(),
+            options: self.options.clone(),
+            annotations: self.annotations.clone(),
+        };
+        hexd.dump_into(FmtWriter(f)).map(|_| ())
+    }
+}
+
+/// The number of bytes shown by the compact form of [`HexdDebug`].
+const DEBUG_PREVIEW_LEN: usize = 16;
+
+/// Wraps a byte sequence so that its [`Debug`] impl renders a hexdump,
+/// e.g. for the fields of structs which derive `Debug`.
+///
+/// The alternate form (`{:#?}`) is a hexdump with [default options](HexdOptions::default),
+/// without a line break after its last row.
+/// The normal form (`{:?}`) is a single-line preview of the first 16 bytes,
+/// followed by the total length if there are more.
+///
+/// ```
+/// use hxd::HexdDebug;
+///
+/// #[derive(Debug)]
+/// struct Packet {
+///     id: u16,
+///     payload: HexdDebug<Vec<u8>>,
+/// }
+///
+/// let packet = Packet { id: 7, payload: HexdDebug(b"hello, world! (again)".to_vec()) };
+///
+/// assert_eq!(
+///     format!("{packet:?}"),
+///     "Packet { id: 7, payload: 68 65 6C 6C 6F 2C 20 77 6F 72 6C 64 21 20 28 61 |hello, world! (a| ... (21 bytes) }"
+/// );
+/// assert_eq!(format!("{packet:#?}"), concat!(
+///     "Packet {\n",
+///     "    id: 7,\n",
+///     "    payload: 00000000: 6865 6C6C 6F2C 2077 6F72 6C64 2120 2861 |hello, world! (a|\n",
+///     "    00000010: 6761 696E 29                            |gain)           |,\n",
+///     "}",
+/// ));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HexdDebug<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> Debug for HexdDebug<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let bytes = self.0.as_ref();
+        if f.alternate() {
+            return bytes.as_hexd().dump_into(TrimmedWriter {
+                f,
+                line_break: false,
+            });
+        }
+        if bytes.is_empty() {
+            return f.write_str("(0 bytes)");
+        }
+
+        let preview = &bytes[..min(bytes.len(), DEBUG_PREVIEW_LEN)];
+        preview
+            .as_hexd()
+            .show_index(false)
+            .ungrouped(preview.len(), Spacing::Normal)
+            .dump_into(TrimmedWriter {
+                f,
+                line_break: false,
+            })?;
+        if bytes.len() > preview.len() {
+            write!(f, " ... ({} bytes)", bytes.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes lines into a formatter without a line break after the last one,
+/// so that a dump can be followed by other fields.
+struct TrimmedWriter<'a, 'f> {
+    f: &'a mut Formatter<'f>,
+    line_break: bool,
+}
+
+impl WriteHexdump for TrimmedWriter<'_, '_> {
+    type Error = core::fmt::Error;
+    type Output = core::fmt::Result;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        if self.line_break {
+            self.f.write_str("\n")?;
+        }
+        let line = s.strip_suffix('\n');
+        self.line_break = line.is_some();
+        self.f.write_str(line.unwrap_or(s))
+    }
+
+    fn consume(r: Result<Self, Self::Error>) -> Self::Output {
+        r.map(|_| ())
+    }
+}
+
 /// [`FallibleHexd`] implements [`HexdOptionsBuilder`] to allow for fluent
 /// configuration.
 impl<R: ReadBytes> HexdOptionsBuilder for FallibleHexd<R> {
@@ -965,6 +2069,15 @@ impl From<LeadingZeroChar> for u8 {
     }
 }
 
+impl From<Placeholder> for u8 {
+    fn from(value: Placeholder) -> Self {
+        match value {
+            Placeholder::Question => b'?',
+            Placeholder::Underscore => b'_',
+        }
+    }
+}
+
 /// Yield an owning version of [`Hexd`]
 /// over byte sequences.
 pub trait IntoHexd<R: ReadBytes>: Sized {
@@ -976,10 +2089,7 @@ pub trait IntoHexd<R: ReadBytes>: Sized {
 
 impl<I: Iterator<Item = u8>> IntoHexd<IteratorByteReader<I>> for I {
     fn into_hexd(self) -> Hexd<IteratorByteReader<I>> {
-        Hexd {
-            reader: IteratorByteReader::new(self),
-            options: HexdOptions::default(),
-        }
+        Hexd::new(IteratorByteReader::new(self))
     }
 }
 
@@ -991,12 +2101,10 @@ pub trait IntoFallibleHexd<R: ReadBytes>: Sized {
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: std::io::Read> IntoFallibleHexd<IOReader<BufReader<R>>> for R {
     fn into_hexd(self) -> FallibleHexd<IOReader<BufReader<R>>> {
-        FallibleHexd {
-            reader: IOReader::new(BufReader::new(self)),
-            options: HexdOptions::default(),
-        }
+        FallibleHexd::new(IOReader::new(BufReader::new(self)))
     }
 }
 
@@ -1105,6 +2213,7 @@ pub trait AsHexdGrouped<'a, R: ReadBytes> {
 ///
 /// ## Examples
 /// ```
+/// # #[cfg(feature = "std")] {
 /// use hxd::AsHexd;
 ///
 /// let v = vec![0u8; 24];
@@ -1114,15 +2223,13 @@ pub trait AsHexdGrouped<'a, R: ReadBytes> {
 /// v.as_hexd().dump();
 /// x.as_hexd().dump();
 /// s.as_hexd().dump();
+/// # }
 /// ```
 impl<'a, T: AsRef<[u8]>> AsHexd<'a, ByteSliceReader<'a>> for T {
     fn as_hexd(&'a self) -> Hexd<ByteSliceReader<'a>> {
         let slice = self.as_ref();
         let reader = ByteSliceReader::new(slice);
-        Hexd {
-            reader,
-            options: HexdOptions::default(),
-        }
+        Hexd::new(reader)
     }
 }
 
@@ -1130,10 +2237,7 @@ impl<'a, T: AsRef<[i8]>> AsHexd<'a, GroupedSliceByteReader<'a, i8, 1>> for T {
     fn as_hexd(&'a self) -> Hexd<GroupedSliceByteReader<'a, i8, 1>> {
         let slice = self.as_ref();
         let reader = GroupedSliceByteReader::new(slice, Endianness::BigEndian);
-        Hexd {
-            reader,
-            options: HexdOptions::default(),
-        }
+        Hexd::new(reader)
     }
 }
 
@@ -1145,7 +2249,7 @@ impl<'a, const N: usize, E: EndianBytes<N>, T: AsRef<[E]>>
         let reader = GroupedSliceByteReader::new(slice, endianness);
         let grouping = grouping_for_bytewidth(N);
         let options = HexdOptions::default().grouping(grouping);
-        Hexd { reader, options }
+        Hexd::new_with_options(reader, options)
     }
 }
 
@@ -1156,7 +2260,7 @@ impl<const N: usize, E: EndianBytes<N>, I: Iterator<Item = E>>
         let reader = GroupedIteratorReader::new(self, endianness);
         let grouping = grouping_for_bytewidth(N);
         let options = HexdOptions::default().grouping(grouping);
-        Hexd { reader, options }
+        Hexd::new_with_options(reader, options)
     }
 }
 
@@ -0,0 +1,220 @@
+use core::{
+    cmp::min,
+    ffi::{c_int, c_long, c_void},
+    ptr::{self, NonNull},
+    slice,
+};
+use std::{
+    fs::File,
+    io::{self, ErrorKind},
+    os::fd::AsRawFd,
+    path::Path,
+};
+
+use crate::reader::ReadBytes;
+
+const PROT_READ: c_int = 1;
+const MAP_PRIVATE: c_int = 2;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: c_long,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+/// The number of bytes read between checks that a mapped file was not
+/// truncated. This is the smallest page size on Linux.
+const CHECK_INTERVAL: usize = 4096;
+
+/// A read-only memory map of a whole file.
+///
+/// The map is dumped through its [reader](Self::reader), which reads the file
+/// without copying it:
+///
+/// ```no_run
+/// use hxd::{mmap::Mmap, options::HexdOptionsBuilder, FallibleHexd};
+///
+/// let map = Mmap::open("core.1234")?;
+/// FallibleHexd::new(map.reader()).range(0x1000..0x1100).dump()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// The mapped pages can also be borrowed as a slice with
+/// [`as_slice`](Self::as_slice), which is `unsafe` because the file may be
+/// truncated by another process while the slice is alive.
+pub struct Mmap {
+    file: File,
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// the map is read-only and owns its pages
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    /// Map the whole of `file`, which must be open for reading.
+    pub fn map(file: File) -> io::Result<Self> {
+        let len = usize::try_from(file.metadata()?.len()).map_err(|_| {
+            io::Error::new(ErrorKind::InvalidInput, "file is too large to be mapped")
+        })?;
+        if len == 0 {
+            // empty maps are rejected by `mmap`
+            return Ok(Self {
+                file,
+                ptr: NonNull::dangling(),
+                len,
+            });
+        }
+        // SAFETY: a new private, read-only mapping does not alias any memory
+        let ptr = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            file,
+            ptr: NonNull::new(ptr.cast()).ok_or_else(io::Error::last_os_error)?,
+            len,
+        })
+    }
+
+    /// Open and map the file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::map(File::open(path)?)
+    }
+
+    /// Return the length of the map, which is the length of the file when it
+    /// was mapped.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the mapped file was empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the mapped bytes.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be truncated or modified while the slice is alive.
+    /// Touching a page past the new end of a truncated file raises `SIGBUS`,
+    /// and changes to the file may show through the slice, which breaks the
+    /// guarantee that a shared slice does not change. Use
+    /// [`reader`](Self::reader) for files which may be modified while they
+    /// are dumped.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        // SAFETY: the pages stay mapped until `self` is dropped
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Return a [reader](MmapReader) over the map which stops cleanly if the
+    /// file is truncated.
+    pub fn reader(&self) -> MmapReader<'_> {
+        MmapReader {
+            map: self,
+            index: 0,
+            valid_len: self.len,
+            checked_until: 0,
+        }
+    }
+
+    /// Return the current length of the file, which may be less than the
+    /// length of the map if the file was truncated.
+    fn file_len(&self) -> io::Result<usize> {
+        let len = self.file.metadata()?.len();
+        Ok(usize::try_from(len).unwrap_or(usize::MAX))
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: the pages were mapped by `mmap` with this length, and
+            // no slices of them outlive `self`
+            unsafe { munmap(self.ptr.as_ptr().cast(), self.len) };
+        }
+    }
+}
+
+/// A [reader](ReadBytes) over a [`Mmap`].
+///
+/// Skips are free, and the length of the file is reported as the
+/// [total byte hint](ReadBytes::total_byte_hint). The length of the file is
+/// checked before each page is read, so truncating the file while it is
+/// dumped ends the dump with an [`UnexpectedEof`](ErrorKind::UnexpectedEof)
+/// error instead of a `SIGBUS`. A truncation which races with the read of a
+/// page can still fault.
+pub struct MmapReader<'a> {
+    map: &'a Mmap,
+    index: usize,
+    /// The length of the file when it was last checked.
+    valid_len: usize,
+    /// The end of the pages which were checked to be within the file.
+    checked_until: usize,
+}
+
+impl MmapReader<'_> {
+    /// Check the length of the file if the next `n` bytes reach past the
+    /// pages which were last checked.
+    fn check_len(&mut self, n: usize) -> io::Result<()> {
+        let end = min(self.index.saturating_add(n), self.valid_len);
+        if end <= self.checked_until {
+            return Ok(());
+        }
+        self.valid_len = min(self.valid_len, self.map.file_len()?);
+        self.checked_until = min(
+            end.div_ceil(CHECK_INTERVAL).saturating_mul(CHECK_INTERVAL),
+            self.valid_len,
+        );
+        Ok(())
+    }
+}
+
+impl ReadBytes for MmapReader<'_> {
+    type Error = io::Error;
+
+    fn next_n<'buf>(&mut self, buf: &'buf mut [u8]) -> Result<&'buf [u8], Self::Error> {
+        self.check_len(buf.len())?;
+        if self.index >= self.valid_len && self.valid_len < self.map.len {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("file was truncated to {} bytes", self.valid_len),
+            ));
+        }
+        let start = min(self.index, self.valid_len);
+        let end = min(self.index.saturating_add(buf.len()), self.checked_until);
+        // SAFETY: the bytes are within the length of the file which was
+        // checked above, and they are copied out before they are returned
+        let bytes = unsafe { &self.map.as_slice()[start..end.max(start)] };
+        buf[..bytes.len()].copy_from_slice(bytes);
+        self.index += bytes.len();
+        Ok(&buf[..bytes.len()])
+    }
+
+    fn skip_n(&mut self, n: usize) -> Result<usize, Self::Error> {
+        let n = min(n, self.map.len.saturating_sub(self.index));
+        self.index += n;
+        Ok(n)
+    }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        Some(self.map.len)
+    }
+}
@@ -1,11 +1,12 @@
-use std::ops::{Bound, RangeBounds};
+use alloc::{borrow::Cow, vec, vec::Vec};
+use core::ops::{Bound, RangeBounds};
 
 /// Display options for [`Hexd`](crate::Hexd).
 ///
 /// *Note: these options may be set directly, but the
 /// [`HexdOptionsBuilder`] trait provides a more convenient way to fluently build
 /// options off of a default or a known base set.*
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct HexdOptions {
     /// Base system to use
     pub base: Base,
@@ -38,6 +39,29 @@ pub struct HexdOptions {
     /// ```
     pub autoskip: bool,
 
+    /// Which rows are elided when [`autoskip`](Self::autoskip) is enabled,
+    /// and how many repetitions it takes to elide them. See [`Elision`].
+    ///
+    /// ```rust
+    /// use hxd::{AsHexd, options::{HexdOptionsBuilder, ElisionMode}};
+    ///
+    /// let v = b"0123456789abcdef".repeat(4);
+    ///
+    /// let dump = v.hexd().elision_mode(ElisionMode::Repeated).dump_to::<String>();
+    /// assert_eq!(dump, concat!(
+    ///     "00000000: 3031 3233 3435 3637 3839 6162 6364 6566 |0123456789abcdef|\n",
+    ///     "*\n",
+    ///     "00000030: 3031 3233 3435 3637 3839 6162 6364 6566 |0123456789abcdef|\n",
+    /// ));
+    ///
+    /// let dump = v.hexd()
+    ///     .elision_mode(ElisionMode::Repeated)
+    ///     .min_elided_rows(3)
+    ///     .dump_to::<String>();
+    /// assert_eq!(dump.lines().count(), 4);
+    /// ```
+    pub elision: Elision,
+
     /// If true, the hex values are printed in uppercase.
     /// Otherwise, the hex values are printed in lowercase.
     pub uppercase: bool,
@@ -46,8 +70,8 @@ pub struct HexdOptions {
     /// on the right side of the hex values.
     pub show_ascii: bool,
 
-    /// If true, a hexidecimal index of the bytes is printed
-    /// to the left of the hex dump.
+    /// If true, an index of the bytes is printed to the left of the
+    /// hex dump. See [`index_format`](Self::index_format).
     pub show_index: bool,
 
     /// If true and if combined with a [`print_range`](Self::print_range)
@@ -154,6 +178,159 @@ pub struct HexdOptions {
     /// ));
     /// ```
     pub index_offset: IndexOffset,
+
+    /// Whether a header row with the offset of each column within a row is
+    /// written above the dump. See [`Header`].
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::{Header, HexdOptionsBuilder, Spacing}};
+    ///
+    /// let dump = b"Hello, world!".hexd()
+    ///     .ungrouped(8, Spacing::Normal)
+    ///     .header(Header::Top)
+    ///     .dump_to::<String>();
+    /// assert_eq!(dump, concat!(
+    ///     "          00 01 02 03 04 05 06 07  01234567\n",
+    ///     "00000000: 48 65 6C 6C 6F 2C 20 77 |Hello, w|\n",
+    ///     "00000008: 6F 72 6C 64 21          |orld!   |\n",
+    /// ));
+    /// ```
+    pub header: Header,
+
+    /// Whether a summary line with the totals of the dump is written below
+    /// it. See [`Footer`].
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::{Checksum, Footer, HexdOptionsBuilder}};
+    ///
+    /// let v = [vec![0u8; 64], b"end".to_vec()].concat();
+    ///
+    /// let dump = v.hexd()
+    ///     .range(0x10..)
+    ///     .footer(Footer::Summary(Some(Checksum::Crc32)))
+    ///     .dump_to::<String>();
+    /// assert_eq!(dump, concat!(
+    ///     "00000010: 0000 0000 0000 0000 0000 0000 0000 0000 |................|\n",
+    ///     "*\n",
+    ///     "00000030: 0000 0000 0000 0000 0000 0000 0000 0000 |................|\n",
+    ///     "00000040: 656E 64                                 |end             |\n",
+    ///     "total: 51 bytes, range: 00000010-00000043, elided: 1 row (16 bytes), crc32: D7AFBF31\n",
+    /// ));
+    /// ```
+    pub footer: Footer,
+
+    /// How the index column is written. See [`IndexFormat`].
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::{HexdOptionsBuilder, IndexBase}};
+    ///
+    /// let v = vec![0u8; 32];
+    ///
+    /// let dump = v.hexd()
+    ///     .index_base(IndexBase::Decimal)
+    ///     .index_width(4, Some(4))
+    ///     .index_separator(" | ")
+    ///     .autoskip(false)
+    ///     .dump_to::<String>();
+    /// assert_eq!(dump, concat!(
+    ///     "0000 | 0000 0000 0000 0000 0000 0000 0000 0000 |................|\n",
+    ///     "0016 | 0000 0000 0000 0000 0000 0000 0000 0000 |................|\n",
+    /// ));
+    /// ```
+    pub index_format: IndexFormat,
+
+    /// If set, bytes, indices and elision markers are wrapped in ANSI
+    /// escape sequences using the styles of the given [`Palette`].
+    /// Bytes are styled according to their [`ByteClass`].
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::{HexdOptionsBuilder, Palette, Spacing, Style, Color}};
+    ///
+    /// let palette = Palette {
+    ///     printable: Style::plain().fg(Color::Cyan),
+    ///     ..Palette::plain()
+    /// };
+    ///
+    /// let dump = b"A\0".hexd()
+    ///     .ungrouped(2, Spacing::Normal)
+    ///     .palette(palette)
+    ///     .dump_to::<String>();
+    /// assert_eq!(dump, "00000000: \x1b[36m41\x1b[0m 00 |\x1b[36mA\x1b[0m.|\n");
+    /// ```
+    pub color: Option<Palette>,
+
+    /// The layout of the output. Besides the hexdump layout, the bytes
+    /// can be written as source code; see [`SourceFormat`].
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::HexdOptionsBuilder};
+    ///
+    /// let dump = b"hexd".hexd().c_array("word").dump_to::<String>();
+    /// assert_eq!(dump, concat!(
+    ///     "unsigned char word[] = {\n",
+    ///     "  0x68, 0x65, 0x78, 0x64\n",
+    ///     "};\n",
+    ///     "unsigned int word_len = 4;\n",
+    /// ));
+    /// ```
+    pub format: OutputFormat,
+
+    /// The character encoding used to decode bytes for the text panel.
+    /// Bytes which do not decode to a printable character are shown as `.`.
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::{HexdOptionsBuilder, Spacing, TextEncoding}};
+    ///
+    /// let v = b"\xC8\x85\x93\x93\x96";
+    ///
+    /// let dump = v.hexd().ungrouped(5, Spacing::Normal).dump_to::<String>();
+    /// assert_eq!(dump, "00000000: C8 85 93 93 96 |.....|\n");
+    ///
+    /// let dump = v.hexd()
+    ///     .ungrouped(5, Spacing::Normal)
+    ///     .encoding(TextEncoding::Ebcdic)
+    ///     .dump_to::<String>();
+    /// assert_eq!(dump, "00000000: C8 85 93 93 96 |Hello|\n");
+    /// ```
+    pub encoding: TextEncoding,
+
+    /// How bytes which the reader has [no value for](crate::reader::ReadBytes::skip_unknown)
+    /// are written. Rows of such bytes are elided like identical rows if
+    /// [`autoskip`](Self::autoskip) is enabled.
+    ///
+    /// ```
+    /// use hxd::{options::{HexdOptionsBuilder, Placeholder, Spacing}, reader::ReadBytes, Hexd};
+    ///
+    /// /// Two known bytes, then two unknown ones.
+    /// struct Gap(usize);
+    ///
+    /// impl ReadBytes for Gap {
+    ///     type Error = core::convert::Infallible;
+    ///
+    ///     fn next_n<'buf>(&mut self, buf: &'buf mut [u8]) -> Result<&'buf [u8], Self::Error> {
+    ///         let n = buf.len().min(2usize.saturating_sub(self.0));
+    ///         buf[..n].fill(b'A');
+    ///         self.0 += n;
+    ///         Ok(&buf[..n])
+    ///     }
+    ///
+    ///     fn skip_unknown(&mut self, max: usize) -> Result<usize, Self::Error> {
+    ///         let n = max.min(4usize.saturating_sub(self.0));
+    ///         self.0 += n;
+    ///         Ok(n)
+    ///     }
+    /// }
+    ///
+    /// let dump = Hexd::new(Gap(0)).ungrouped(4, Spacing::Normal).dump_to::<String>();
+    /// assert_eq!(dump, "00000000: 41 41 ?? ?? |AA??|\n");
+    ///
+    /// let dump = Hexd::new(Gap(0))
+    ///     .ungrouped(4, Spacing::Normal)
+    ///     .placeholder(Placeholder::Underscore)
+    ///     .dump_to::<String>();
+    /// assert_eq!(dump, "00000000: 41 41 __ __ |AA__|\n");
+    /// ```
+    pub placeholder: Placeholder,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -164,6 +341,392 @@ pub enum Base {
     Binary,
 }
 
+impl Base {
+    /// The number of characters used to display a single byte.
+    pub fn cell_width(&self) -> usize {
+        match self {
+            Self::Hex => 2,
+            Self::Decimal(_) | Self::Octal(_) => 3,
+            Self::Binary => 8,
+        }
+    }
+}
+
+/// A color that can be displayed by ANSI terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+
+    /// One of the 256 colors of the extended ANSI palette.
+    Fixed(u8),
+}
+
+/// A text style, rendered as an ANSI SGR escape sequence.
+///
+/// ```
+/// use hxd::options::{Style, Color};
+///
+/// let style = Style::plain().fg(Color::Red).bold();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    /// A style without any attributes. Text with this style is written
+    /// without escape sequences.
+    pub const fn plain() -> Self {
+        Self {
+            foreground: None,
+            background: None,
+            bold: false,
+            dim: false,
+            underline: false,
+            reverse: false,
+        }
+    }
+
+    /// Set the foreground color.
+    pub const fn fg(self, color: Color) -> Self {
+        Self {
+            foreground: Some(color),
+            ..self
+        }
+    }
+
+    /// Set the background color.
+    pub const fn bg(self, color: Color) -> Self {
+        Self {
+            background: Some(color),
+            ..self
+        }
+    }
+
+    /// Render text in bold.
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// Render text faintly.
+    pub const fn dim(self) -> Self {
+        Self { dim: true, ..self }
+    }
+
+    /// Underline text.
+    pub const fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+
+    /// Swap the foreground and background colors.
+    pub const fn reverse(self) -> Self {
+        Self {
+            reverse: true,
+            ..self
+        }
+    }
+
+    /// Returns true if this style has no attributes.
+    pub fn is_plain(&self) -> bool {
+        *self == Self::plain()
+    }
+}
+
+/// Broad categories of byte values, used to pick a [`Style`] from a [`Palette`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteClass {
+    /// The zero byte (`0x00`).
+    Null,
+
+    /// Printable ASCII characters, excluding the space.
+    Printable,
+
+    /// ASCII whitespace: space, tab, line feed, vertical tab, form feed and carriage return.
+    Whitespace,
+
+    /// Any other ASCII byte, i.e. control characters and `DEL`.
+    Control,
+
+    /// Bytes outside of the ASCII range (`0x80..=0xFF`).
+    High,
+}
+
+impl ByteClass {
+    /// Return the class of the given byte.
+    pub fn of(b: u8) -> Self {
+        match b {
+            0 => Self::Null,
+            b' ' | b'\t' | b'\n' | 0x0b | 0x0c | b'\r' => Self::Whitespace,
+            _ if b.is_ascii_graphic() => Self::Printable,
+            _ if b.is_ascii() => Self::Control,
+            _ => Self::High,
+        }
+    }
+}
+
+/// The styles used for each part of a colored dump.
+/// See [`HexdOptions::color`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub null: Style,
+    pub printable: Style,
+    pub whitespace: Style,
+    pub control: Style,
+    pub high: Style,
+    pub index: Style,
+    pub elision: Style,
+
+    /// The style of bytes which differ between the two sides of a
+    /// [diff](crate::diff::HexdDiff).
+    pub changed: Style,
+}
+
+impl Palette {
+    /// A palette in which every style is [plain](Style::plain).
+    pub const fn plain() -> Self {
+        Self {
+            null: Style::plain(),
+            printable: Style::plain(),
+            whitespace: Style::plain(),
+            control: Style::plain(),
+            high: Style::plain(),
+            index: Style::plain(),
+            elision: Style::plain(),
+            changed: Style::plain(),
+        }
+    }
+
+    /// Return the style for bytes of the given class.
+    pub fn style_for(&self, class: ByteClass) -> Style {
+        match class {
+            ByteClass::Null => self.null,
+            ByteClass::Printable => self.printable,
+            ByteClass::Whitespace => self.whitespace,
+            ByteClass::Control => self.control,
+            ByteClass::High => self.high,
+        }
+    }
+}
+
+/// The default palette.
+///
+/// ```rust,no_run
+/// # use hxd::options::{Palette, Style, Color};
+/// Palette {
+///     null: Style::plain().fg(Color::BrightBlack),
+///     printable: Style::plain().fg(Color::Cyan),
+///     whitespace: Style::plain().fg(Color::Green),
+///     control: Style::plain().fg(Color::Magenta),
+///     high: Style::plain().fg(Color::Yellow),
+///     index: Style::plain().dim(),
+///     elision: Style::plain().bold(),
+///     changed: Style::plain().fg(Color::Red).reverse(),
+/// };
+/// ```
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            null: Style::plain().fg(Color::BrightBlack),
+            printable: Style::plain().fg(Color::Cyan),
+            whitespace: Style::plain().fg(Color::Green),
+            control: Style::plain().fg(Color::Magenta),
+            high: Style::plain().fg(Color::Yellow),
+            index: Style::plain().dim(),
+            elision: Style::plain().bold(),
+            changed: Style::plain().fg(Color::Red).reverse(),
+        }
+    }
+}
+
+/// The layout of a dump. See [`HexdOptions::format`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// The hexdump layout, made of an index, the bytes and an ASCII panel.
+    #[default]
+    Hexdump,
+
+    /// Source code which defines the bytes as an array or literal.
+    Source(SourceFormat),
+
+    /// A continuous run of bytes without an index, ASCII panel, spacing or
+    /// padding, like `xxd -p`. A line break is written after every
+    /// `bytes_per_line` bytes, or only at the end if it is `None`.
+    ///
+    /// Rows of this format are not limited in width, and only the
+    /// [`base`](HexdOptions::base), [`print_range`](HexdOptions::print_range),
+    /// [`uppercase`](HexdOptions::uppercase) and [`color`](HexdOptions::color)
+    /// options apply to it.
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::HexdOptionsBuilder};
+    ///
+    /// let v = (0..20).collect::<Vec<u8>>();
+    ///
+    /// let dump = v.hexd().plain(Some(8)).uppercase(false).dump_to::<String>();
+    /// assert_eq!(dump, concat!(
+    ///     "0001020304050607\n",
+    ///     "08090a0b0c0d0e0f\n",
+    ///     "10111213\n",
+    /// ));
+    ///
+    /// let dump = v.hexd().plain(None).range(..12).dump_to::<String>();
+    /// assert_eq!(dump, "000102030405060708090A0B\n");
+    /// ```
+    Plain { bytes_per_line: Option<usize> },
+}
+
+impl From<SourceFormat> for OutputFormat {
+    fn from(value: SourceFormat) -> Self {
+        Self::Source(value)
+    }
+}
+
+/// The character encodings available for the text panel.
+/// See [`HexdOptions::encoding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    /// 7-bit ASCII. Only printable characters and the space are shown.
+    #[default]
+    Ascii,
+
+    /// ISO-8859-1, which extends ASCII with the accented letters and
+    /// symbols of Western European languages.
+    Latin1,
+
+    /// IBM PC code page 437, including its box drawing characters.
+    /// The control range `0x00..0x20` is not shown as pictographs.
+    Cp437,
+
+    /// EBCDIC code page 037, used on IBM mainframes.
+    Ebcdic,
+
+    /// UTF-8. A multi-byte character is shown under its first byte and
+    /// its continuation bytes are shown as `·`, including characters which
+    /// span group and row boundaries. Invalid sequences are shown as `.`.
+    /// Characters which are wide on a terminal, such as emoji, take up two
+    /// columns and shift the rest of the row.
+    ///
+    /// ```
+    /// use hxd::{AsHexd, options::{HexdOptionsBuilder, Spacing, TextEncoding}};
+    ///
+    /// let dump = "añb€".hexd()
+    ///     .ungrouped(4, Spacing::Normal)
+    ///     .encoding(TextEncoding::Utf8)
+    ///     .dump_to::<String>();
+    /// assert_eq!(dump, concat!(
+    ///     "00000000: 61 C3 B1 62 |añ·b|\n",
+    ///     "00000004: E2 82 AC    |€·· |\n",
+    /// ));
+    /// ```
+    Utf8,
+}
+
+/// Options for writing bytes as source code.
+///
+/// Only the [`print_range`](HexdOptions::print_range) and
+/// [`uppercase`](HexdOptions::uppercase) options apply to source output;
+/// the other options only affect the hexdump layout.
+///
+/// ```
+/// use hxd::{AsHexd, options::{HexdOptionsBuilder, SourceFormat, SourceLanguage}};
+///
+/// let format = SourceFormat::new(SourceLanguage::PythonBytes, "magic").bytes_per_line(2);
+///
+/// let dump = b"\x7fELF".hexd().format(format).uppercase(false).dump_to::<String>();
+/// assert_eq!(dump, concat!(
+///     "magic = (\n",
+///     "    b\"\\x7f\\x45\"\n",
+///     "    b\"\\x4c\\x46\"\n",
+///     ")\n",
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFormat {
+    /// The language of the source code.
+    pub language: SourceLanguage,
+
+    /// The name of the variable or constant which holds the bytes.
+    pub name: Cow<'static, str>,
+
+    /// The number of bytes written on each line.
+    pub bytes_per_line: usize,
+}
+
+impl SourceFormat {
+    /// Return a new instance which writes 12 bytes per line, like `xxd -i`.
+    pub fn new<S: Into<Cow<'static, str>>>(language: SourceLanguage, name: S) -> Self {
+        Self {
+            language,
+            name: name.into(),
+            bytes_per_line: 12,
+        }
+    }
+
+    /// Set the number of bytes written on each line.
+    pub fn bytes_per_line(self, bytes_per_line: usize) -> Self {
+        Self {
+            bytes_per_line,
+            ..self
+        }
+    }
+}
+
+/// The languages supported by [`SourceFormat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceLanguage {
+    /// A C `unsigned char` array, followed by an `unsigned int` with its
+    /// length named `<name>_len`, as written by `xxd -i`.
+    C,
+
+    /// A Rust `[u8; N]` array constant, followed by a `usize` constant
+    /// with its length named `<name>_LEN`.
+    RustArray,
+
+    /// A Rust byte string constant (`b"..."`), followed by a `usize`
+    /// constant with its length named `<name>_LEN`.
+    RustBytes,
+
+    /// A Python `bytes` literal.
+    PythonBytes,
+}
+
+/// The character written in place of each digit of a byte which the
+/// reader has [no value for](crate::reader::ReadBytes::skip_unknown), such
+/// as an unmapped page or a hole in a sparse file. It is also written in
+/// place of the byte's character in the text panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Placeholder {
+    /// Unknown bytes are written as `??` in hex (`????????` in binary).
+    #[default]
+    Question,
+
+    /// Unknown bytes are written as `__` in hex (`________` in binary).
+    Underscore,
+}
+
 /// This enum is used to specify how leading zeroes are printed
 /// in [decimal](Base::Decimal) and [octal](Base::Octal) bases.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -206,11 +769,11 @@ impl HexdRange {
         let skip = match r.start_bound() {
             Bound::Unbounded => 0usize,
             Bound::Included(s) => *s,
-            Bound::Excluded(s) => s + 1,
+            Bound::Excluded(s) => s.saturating_add(1),
         };
         let limit = match r.end_bound() {
             Bound::Unbounded => None,
-            Bound::Included(s) => Some(*s + 1),
+            Bound::Included(s) => Some(s.saturating_add(1)),
             Bound::Excluded(s) => Some(*s),
         };
 
@@ -218,9 +781,148 @@ impl HexdRange {
     }
 
     /// If [`limit`](field@HexdRange::limit) is not None, return the length
-    /// of the range.
+    /// of the range. A range which ends before it starts is empty.
     pub fn length(&self) -> Option<usize> {
-        self.limit.map(|lim| lim - self.skip)
+        self.limit.map(|lim| lim.saturating_sub(self.skip))
+    }
+}
+
+/// Controls which rows are elided when [`autoskip`](HexdOptions::autoskip)
+/// is enabled.
+///
+/// A run of rows equal to the row before it is elided up to its last row,
+/// which is shown after the [marker](ElisionMarker) to close the run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elision {
+    /// Which rows may start a run of elided rows.
+    pub mode: ElisionMode,
+
+    /// The minimum number of rows that a run must hide before it is
+    /// elided. Shorter runs are printed in full. A value of 0 is
+    /// treated as 1.
+    pub min_rows: usize,
+
+    /// The line written in place of the elided rows.
+    pub marker: ElisionMarker,
+}
+
+impl Default for Elision {
+    fn default() -> Self {
+        Self {
+            mode: ElisionMode::default(),
+            min_rows: 1,
+            marker: ElisionMarker::default(),
+        }
+    }
+}
+
+/// Which rows may start a run of elided rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ElisionMode {
+    /// Only rows which consist of a single repeated group (or a single
+    /// repeated byte, if ungrouped) are elided, such as rows of padding.
+    #[default]
+    Uniform,
+
+    /// Any row which is equal to the row before it is elided,
+    /// like `hexdump -C` does.
+    Repeated,
+}
+
+/// The line written in place of a run of elided rows.
+///
+/// ```
+/// use hxd::{AsHexd, options::{HexdOptionsBuilder, ElisionMarker}};
+///
+/// let v = vec![0u8; 0x4000];
+///
+/// let dump = v.hexd().elision_marker(ElisionMarker::Counted).dump_to::<String>();
+/// assert_eq!(dump, concat!(
+///     "00000000: 0000 0000 0000 0000 0000 0000 0000 0000 |................|\n",
+///     "* (1022 rows, 0x3FE0 bytes identical)\n",
+///     "00003FF0: 0000 0000 0000 0000 0000 0000 0000 0000 |................|\n",
+/// ));
+///
+/// let dump = v.hexd()
+///     .elision_marker(ElisionMarker::Template("-- {start} to {end}: {bytes} bytes --".into()))
+///     .dump_to::<String>();
+/// assert_eq!(dump.lines().nth(1), Some("-- 00000010 to 00003FF0: 16352 bytes --"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ElisionMarker {
+    /// A single `*`, like `xxd` and `hexdump`.
+    #[default]
+    Asterisk,
+
+    /// A `*` followed by the number of elided rows and bytes,
+    /// e.g. `* (1234 rows, 0x4D20 bytes identical)`.
+    Counted,
+
+    /// A line built from a template, in which `{rows}` and `{bytes}` are
+    /// replaced by the number of elided rows and bytes in decimal, and
+    /// `{start}` and `{end}` are replaced by the index of the first elided
+    /// byte and of the byte after the last one, written like the index
+    /// column. A template without any of these is written as is.
+    Template(Cow<'static, str>),
+}
+
+/// A part of an [elision marker](ElisionMarker), as written by the
+/// formatter and matched by the parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MarkerPart<'a> {
+    Text(&'a str),
+    Rows,
+    Bytes,
+    HexBytes,
+    Start,
+    End,
+}
+
+impl ElisionMarker {
+    /// Split the marker into its parts. The row count of a [counted](Self::Counted)
+    /// marker is followed by `row` or `rows` depending on `rows`.
+    pub(crate) fn parts(&self, rows: usize) -> Vec<MarkerPart<'_>> {
+        match self {
+            Self::Asterisk => vec![MarkerPart::Text("*")],
+            Self::Counted => vec![
+                MarkerPart::Text("* ("),
+                MarkerPart::Rows,
+                MarkerPart::Text(if rows == 1 { " row, 0x" } else { " rows, 0x" }),
+                MarkerPart::HexBytes,
+                MarkerPart::Text(" bytes identical)"),
+            ],
+            Self::Template(template) => {
+                let mut parts = Vec::new();
+                let mut text_start = 0;
+                let mut pos = 0;
+                while let Some(open) = template[pos..].find('{').map(|i| pos + i) {
+                    let Some(close) = template[open..].find('}').map(|i| open + i) else {
+                        break;
+                    };
+                    let field = match &template[open + 1..close] {
+                        "rows" => MarkerPart::Rows,
+                        "bytes" => MarkerPart::Bytes,
+                        "start" => MarkerPart::Start,
+                        "end" => MarkerPart::End,
+                        // unknown fields are written as is
+                        _ => {
+                            pos = open + 1;
+                            continue;
+                        }
+                    };
+                    if open > text_start {
+                        parts.push(MarkerPart::Text(&template[text_start..open]));
+                    }
+                    parts.push(field);
+                    text_start = close + 1;
+                    pos = close + 1;
+                }
+                if text_start < template.len() {
+                    parts.push(MarkerPart::Text(&template[text_start..]));
+                }
+                parts
+            }
+        }
     }
 }
 
@@ -230,6 +932,193 @@ pub enum IndexOffset {
     Absolute(usize),
 }
 
+/// Controls whether a header row is written above the rows of a
+/// [hexdump](OutputFormat::Hexdump). The header labels each column with its
+/// offset within the row, written in the [base of the index](IndexFormat::base),
+/// and each character of the text panel with the last digit of its offset.
+/// Diffs do not include a header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Header {
+    /// No header is written.
+    #[default]
+    Hidden,
+
+    /// A header is written above the first row.
+    Top,
+
+    /// A header is written above the first row and repeated before every
+    /// `n` further rows. `Every(0)` is the same as [`Top`](Self::Top).
+    Every(usize),
+}
+
+/// Controls whether a summary line is written below the rows of a
+/// [hexdump](OutputFormat::Hexdump). The summary reports the number of
+/// bytes read, the range they were read from (written like the index column),
+/// the number of elided rows and bytes, and optionally a [`Checksum`] of the
+/// bytes. If the reader has no value for some of the bytes, their number is
+/// reported as well. Diffs do not include a footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Footer {
+    /// No footer is written.
+    #[default]
+    Hidden,
+
+    /// A summary line is written after the last row, including the given
+    /// checksum, if any.
+    Summary(Option<Checksum>),
+}
+
+/// A checksum reported in the [footer](Footer) of a dump. The checksum covers
+/// every byte read, including those in elided rows, but not
+/// [unknown bytes](crate::reader::ReadBytes::skip_unknown).
+///
+/// ```
+/// use hxd::options::Checksum;
+///
+/// assert_eq!(Checksum::Crc32.of(b"123456789"), 0xCBF43926);
+/// assert_eq!(Checksum::Adler32.of(b"Wikipedia"), 0x11E60398);
+/// assert_eq!(Checksum::Sum8.of(&[0xff, 0x02]), 0x01);
+/// assert_eq!(Checksum::Xor8.of(&[0x0f, 0xf1]), 0xfe);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// The CRC-32 used by zlib, gzip and PNG.
+    Crc32,
+
+    /// The Adler-32 checksum used by zlib.
+    Adler32,
+
+    /// The sum of all bytes, modulo 256.
+    Sum8,
+
+    /// The exclusive or of all bytes.
+    Xor8,
+}
+
+impl Checksum {
+    /// Return the checksum of `bytes`.
+    pub fn of(self, bytes: &[u8]) -> u32 {
+        let mut state = crate::checksum::ChecksumState::new(self);
+        state.update(bytes);
+        state.finish()
+    }
+
+    /// The name of the checksum, as written in the footer.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Crc32 => "crc32",
+            Self::Adler32 => "adler32",
+            Self::Sum8 => "sum8",
+            Self::Xor8 => "xor8",
+        }
+    }
+
+    /// The number of hex digits of the checksum.
+    pub fn width(self) -> usize {
+        match self {
+            Self::Crc32 | Self::Adler32 => 8,
+            Self::Sum8 | Self::Xor8 => 2,
+        }
+    }
+}
+
+/// Controls how the index column on the left of each row is written.
+///
+/// The column is at least [`min_width`](Self::min_width) digits wide and
+/// otherwise as wide as the largest index of the dump, if the reader knows
+/// its length. For readers of unknown length the column keeps its minimum
+/// width, so raise `min_width` to keep it from changing in the middle of a
+/// dump of a long stream. Digits of an index are never dropped, so an index
+/// which does not fit widens its row.
+///
+/// ```
+/// use hxd::{AsHexd, AsHexdGrouped, options::{HexdOptionsBuilder, IndexFormat, IndexUnits}};
+///
+/// let v = [0x10u32, 0x20, 0x30, 0x40, 0x50, 0x60];
+///
+/// let format = IndexFormat {
+///     prefix: "0x".into(),
+///     min_width: 4,
+///     units: IndexUnits::Elements,
+///     ..IndexFormat::default()
+/// };
+/// let dump = v.hexd_le().index_format(format).dump_to::<String>();
+/// assert_eq!(dump, concat!(
+///     "0x0000: 10000000 20000000 30000000 40000000 |.... ...0...@...|\n",
+///     "0x0004: 50000000 60000000                   |P...`...        |\n",
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexFormat {
+    /// The base in which the index is written.
+    pub base: IndexBase,
+
+    /// The minimum number of digits. Shorter indices are padded with zeroes.
+    pub min_width: usize,
+
+    /// If set, the maximum number of digits of the column. Longer indices
+    /// are still written in full.
+    pub max_width: Option<usize>,
+
+    /// Written before the digits, e.g. `0x`.
+    pub prefix: Cow<'static, str>,
+
+    /// Written between the index and the bytes of the row.
+    pub separator: Cow<'static, str>,
+
+    /// Whether the index counts bytes or groups of bytes.
+    pub units: IndexUnits,
+}
+
+impl Default for IndexFormat {
+    fn default() -> Self {
+        Self {
+            base: IndexBase::default(),
+            min_width: 8,
+            max_width: None,
+            prefix: Cow::Borrowed(""),
+            separator: Cow::Borrowed(": "),
+            units: IndexUnits::default(),
+        }
+    }
+}
+
+/// The base in which the index column is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexBase {
+    /// Hexadecimal digits, in upper or lower case depending on
+    /// [`uppercase`](HexdOptions::uppercase). The column is as wide as
+    /// whole bytes of the largest index.
+    #[default]
+    Hex,
+    Decimal,
+    Octal,
+}
+
+impl IndexBase {
+    pub fn radix(self) -> u32 {
+        match self {
+            Self::Hex => 16,
+            Self::Decimal => 10,
+            Self::Octal => 8,
+        }
+    }
+}
+
+/// What the index column counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexUnits {
+    /// The index is the offset of the first byte of the row.
+    #[default]
+    Bytes,
+
+    /// The index is the offset of the first byte of the row divided by the
+    /// [group size](GroupSize), e.g. the index of a `u32` in a dump made
+    /// with [`hexd_le`](crate::AsHexdGrouped::hexd_le).
+    /// Ungrouped rows are counted in bytes.
+    Elements,
+}
+
 /// This trait controls how bytes are grouped in the hexdump.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Grouping {
@@ -274,12 +1163,12 @@ pub enum Grouping {
 
 impl Grouping {
     pub fn elt_width(&self) -> usize {
-        match self {
-            &Grouping::Ungrouped {
+        match *self {
+            Grouping::Ungrouped {
                 byte_count,
                 spacing: _,
             } => byte_count,
-            &Grouping::Grouped {
+            Grouping::Grouped {
                 group_size,
                 num_groups,
                 byte_spacing: _,
@@ -289,12 +1178,12 @@ impl Grouping {
     }
 
     pub fn spacing_for_index(&self, index: usize) -> Spacing {
-        match self {
-            &Grouping::Ungrouped {
+        match *self {
+            Grouping::Ungrouped {
                 byte_count: _,
                 spacing,
             } => spacing,
-            &Grouping::Grouped {
+            Grouping::Grouped {
                 group_size,
                 num_groups: _,
                 byte_spacing,
@@ -415,17 +1304,25 @@ impl Spacing {
 /// The default options for [`Hexd`](crate::Hexd).
 ///
 /// ```rust,no_run
-/// # use hxd::options::{HexdOptions, HexdRange, Grouping, IndexOffset, Base};
+/// # use hxd::options::{HexdOptions, HexdRange, Grouping, IndexOffset, Header, Footer, IndexFormat, Base, OutputFormat, TextEncoding, Elision, Placeholder};
 /// HexdOptions {
 ///     base: Base::Hex,
 ///     autoskip: true,
+///     elision: Elision::default(),
 ///     uppercase: true,
 ///     show_index: true,
 ///     show_ascii: true,
 ///     align: true,
 ///     grouping: Grouping::default(),
 ///     print_range: HexdRange { skip: 0, limit: None },
-///     index_offset: IndexOffset::Relative(0)
+///     index_offset: IndexOffset::Relative(0),
+///     header: Header::Hidden,
+///     footer: Footer::Hidden,
+///     index_format: IndexFormat::default(),
+///     color: None,
+///     format: OutputFormat::Hexdump,
+///     encoding: TextEncoding::Ascii,
+///     placeholder: Placeholder::Question,
 /// };
 /// ```
 impl Default for HexdOptions {
@@ -433,6 +1330,7 @@ impl Default for HexdOptions {
         Self {
             base: Base::Hex,
             autoskip: true,
+            elision: Elision::default(),
             uppercase: true,
             show_ascii: true,
             show_index: true,
@@ -443,6 +1341,13 @@ impl Default for HexdOptions {
                 limit: None,
             },
             index_offset: IndexOffset::Relative(0),
+            header: Header::Hidden,
+            footer: Footer::Hidden,
+            index_format: IndexFormat::default(),
+            color: None,
+            format: OutputFormat::Hexdump,
+            encoding: TextEncoding::Ascii,
+            placeholder: Placeholder::Question,
         }
     }
 }
@@ -611,11 +1516,188 @@ pub trait HexdOptionsBuilder: Sized {
         self.map_options(|o| HexdOptions { autoskip, ..o })
     }
 
+    /// Set which rows may be elided when autoskip is enabled.
+    /// See [`ElisionMode`].
+    fn elision_mode(self, mode: ElisionMode) -> Self {
+        self.map_options(|o| HexdOptions {
+            elision: Elision { mode, ..o.elision },
+            ..o
+        })
+    }
+
+    /// Set the line written in place of elided rows. See [`ElisionMarker`].
+    fn elision_marker(self, marker: ElisionMarker) -> Self {
+        self.map_options(|o| HexdOptions {
+            elision: Elision {
+                marker,
+                ..o.elision
+            },
+            ..o
+        })
+    }
+
+    /// Set the minimum number of rows that a run must hide before it is
+    /// elided. See [`Elision::min_rows`].
+    fn min_elided_rows(self, min_rows: usize) -> Self {
+        self.map_options(|o| HexdOptions {
+            elision: Elision {
+                min_rows,
+                ..o.elision
+            },
+            ..o
+        })
+    }
+
     /// Set the value of the [`index_offset`](HexdOptions::index_offset) field.
     fn offset(self, index_offset: IndexOffset) -> Self {
         self.map_options(|o| HexdOptions { index_offset, ..o })
     }
 
+    /// Set the value of the [`header`](HexdOptions::header) field.
+    fn header(self, header: Header) -> Self {
+        self.map_options(|o| HexdOptions { header, ..o })
+    }
+
+    /// Set the value of the [`footer`](HexdOptions::footer) field.
+    fn footer(self, footer: Footer) -> Self {
+        self.map_options(|o| HexdOptions { footer, ..o })
+    }
+
+    /// Set the value of the [`index_format`](HexdOptions::index_format) field.
+    fn index_format(self, index_format: IndexFormat) -> Self {
+        self.map_options(|o| HexdOptions { index_format, ..o })
+    }
+
+    /// Set the base in which the index column is written.
+    fn index_base(self, base: IndexBase) -> Self {
+        self.map_options(|o| HexdOptions {
+            index_format: IndexFormat {
+                base,
+                ..o.index_format
+            },
+            ..o
+        })
+    }
+
+    /// Set the minimum and maximum number of digits of the index column.
+    /// See [`IndexFormat::min_width`] and [`IndexFormat::max_width`].
+    fn index_width(self, min_width: usize, max_width: Option<usize>) -> Self {
+        self.map_options(|o| HexdOptions {
+            index_format: IndexFormat {
+                min_width,
+                max_width,
+                ..o.index_format
+            },
+            ..o
+        })
+    }
+
+    /// Set the text written before the digits of the index column.
+    fn index_prefix<S: Into<Cow<'static, str>>>(self, prefix: S) -> Self {
+        self.map_options(|o| HexdOptions {
+            index_format: IndexFormat {
+                prefix: prefix.into(),
+                ..o.index_format
+            },
+            ..o
+        })
+    }
+
+    /// Set the text written between the index column and the bytes.
+    fn index_separator<S: Into<Cow<'static, str>>>(self, separator: S) -> Self {
+        self.map_options(|o| HexdOptions {
+            index_format: IndexFormat {
+                separator: separator.into(),
+                ..o.index_format
+            },
+            ..o
+        })
+    }
+
+    /// Set whether the index column counts bytes or groups of bytes.
+    /// See [`IndexUnits`].
+    fn index_units(self, units: IndexUnits) -> Self {
+        self.map_options(|o| HexdOptions {
+            index_format: IndexFormat {
+                units,
+                ..o.index_format
+            },
+            ..o
+        })
+    }
+
+    /// Enable or disable colored output. If enabled, the [default palette](Palette::default)
+    /// is used.
+    /// This is equivalent to setting the value of the [`color`](HexdOptions::color) field.
+    fn color(self, color: bool) -> Self {
+        self.map_options(|o| HexdOptions {
+            color: color.then(Palette::default),
+            ..o
+        })
+    }
+
+    /// Enable colored output using the given palette.
+    fn palette(self, palette: Palette) -> Self {
+        self.map_options(|o| HexdOptions {
+            color: Some(palette),
+            ..o
+        })
+    }
+
+    /// Set the value of the [`format`](HexdOptions::format) field.
+    fn format<F: Into<OutputFormat>>(self, format: F) -> Self {
+        self.map_options(|o| HexdOptions {
+            format: format.into(),
+            ..o
+        })
+    }
+
+    /// Set the value of the [`encoding`](HexdOptions::encoding) field.
+    fn encoding(self, encoding: TextEncoding) -> Self {
+        self.map_options(|o| HexdOptions { encoding, ..o })
+    }
+
+    /// Set the value of the [`placeholder`](HexdOptions::placeholder) field.
+    fn placeholder(self, placeholder: Placeholder) -> Self {
+        self.map_options(|o| HexdOptions { placeholder, ..o })
+    }
+
+    /// Write the bytes as a continuous run of digits with `bytes_per_line`
+    /// bytes on each line, or on a single line if it is `None`.
+    /// This is equivalent to calling [`format`](Self::format) with
+    /// [`OutputFormat::Plain`].
+    fn plain(self, bytes_per_line: Option<usize>) -> Self {
+        self.format(OutputFormat::Plain { bytes_per_line })
+    }
+
+    /// Write the bytes as a C array named `name`.
+    /// This is equivalent to calling [`format`](Self::format) with
+    /// `SourceFormat::new(SourceLanguage::C, name)`.
+    fn c_array<S: Into<Cow<'static, str>>>(self, name: S) -> Self {
+        self.format(SourceFormat::new(SourceLanguage::C, name))
+    }
+
+    /// Write the bytes as a Rust array constant named `name`.
+    /// This is equivalent to calling [`format`](Self::format) with
+    /// `SourceFormat::new(SourceLanguage::RustArray, name)`.
+    fn rust_array<S: Into<Cow<'static, str>>>(self, name: S) -> Self {
+        self.format(SourceFormat::new(SourceLanguage::RustArray, name))
+    }
+
+    /// Write the bytes as a Rust byte string constant named `name`.
+    /// This is equivalent to calling [`format`](Self::format) with
+    /// `SourceFormat::new(SourceLanguage::RustBytes, name)`.
+    fn rust_bytes<S: Into<Cow<'static, str>>>(self, name: S) -> Self {
+        self.format(SourceFormat::new(SourceLanguage::RustBytes, name))
+    }
+
+    /// Write the bytes as a Python `bytes` literal assigned to `name`.
+    /// This is equivalent to calling [`format`](Self::format) with
+    /// `SourceFormat::new(SourceLanguage::PythonBytes, name)`.
+    fn python_bytes<S: Into<Cow<'static, str>>>(self, name: S) -> Self {
+        self.format(SourceFormat::new(SourceLanguage::PythonBytes, name))
+    }
+
     /// Set the value of the [`index_offset`](HexdOptions::index_offset) field to [`IndexOffset::Relative`].
     fn relative_offset(self, offset: usize) -> Self {
         self.map_options(|o| HexdOptions {
@@ -640,7 +1722,118 @@ impl HexdOptionsBuilder for HexdOptions {
 }
 
 impl HexdOptions {
+    /// The widest row, in bytes, which [`validate`](Self::validate) accepts.
+    pub const MAX_ROW_WIDTH: usize = 1 << 16;
+
     pub fn elt_width(&self) -> usize {
         self.grouping.elt_width()
     }
+
+    /// The number of bytes counted by each step of the index column.
+    pub(crate) fn index_unit(&self) -> usize {
+        match (self.index_format.units, self.grouping) {
+            (IndexUnits::Elements, Grouping::Grouped { group_size, .. }) => {
+                group_size.element_count()
+            }
+            _ => 1,
+        }
+    }
+
+    /// Check that the options describe a dump which can be written.
+    /// Dumping with invalid options may panic; the `try_` entry points such as
+    /// [`Hexd::try_dump_to`](crate::Hexd::try_dump_to) report the error instead.
+    ///
+    /// ```
+    /// use hxd::options::{HexdOptions, HexdOptionsBuilder, OptionsError, Spacing};
+    ///
+    /// let options = HexdOptions::default().ungrouped(0, Spacing::Normal);
+    /// assert_eq!(options.validate(), Err(OptionsError::EmptyRow));
+    ///
+    /// let options = HexdOptions::default().range(0x40..0x10);
+    /// assert_eq!(
+    ///     options.validate(),
+    ///     Err(OptionsError::ReversedRange { start: 0x40, end: 0x10 })
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        // source code is read in rows of its own width, and plain
+        // lines are not bound to rows, so their grouping does not matter
+        let width = match &self.format {
+            OutputFormat::Hexdump => self.elt_width(),
+            OutputFormat::Source(format) => format.bytes_per_line,
+            OutputFormat::Plain { bytes_per_line } => bytes_per_line.unwrap_or(1),
+        };
+        if width == 0 {
+            return Err(OptionsError::EmptyRow);
+        }
+        if width > Self::MAX_ROW_WIDTH {
+            return Err(OptionsError::RowTooWide {
+                width,
+                max: Self::MAX_ROW_WIDTH,
+            });
+        }
+        let IndexFormat {
+            min_width,
+            max_width,
+            ..
+        } = self.index_format;
+        if let Some(max) = max_width.filter(|max| *max == 0 || *max < min_width) {
+            return Err(OptionsError::IndexWidth {
+                min: min_width,
+                max,
+            });
+        }
+        if let Some(end) = self.print_range.limit {
+            if end < self.print_range.skip {
+                return Err(OptionsError::ReversedRange {
+                    start: self.print_range.skip,
+                    end,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The ways [`HexdOptions`] can be invalid. See [`HexdOptions::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionsError {
+    /// Rows (or lines of source code or plain hex) would hold no bytes, e.g.
+    /// because the bytes are ungrouped with a byte count of 0.
+    EmptyRow,
+
+    /// Rows would be wider than [`HexdOptions::MAX_ROW_WIDTH`].
+    RowTooWide { width: usize, max: usize },
+
+    /// The [print range](HexdOptions::print_range) ends before it starts.
+    ReversedRange { start: usize, end: usize },
+
+    /// The maximum width of the [index column](IndexFormat) is 0 or less
+    /// than its minimum width.
+    IndexWidth { min: usize, max: usize },
+}
+
+impl core::fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::EmptyRow => write!(f, "rows must hold at least one byte"),
+            Self::RowTooWide { width, max } => {
+                write!(
+                    f,
+                    "rows of {width} bytes are wider than the maximum of {max}"
+                )
+            }
+            Self::ReversedRange { start, end } => {
+                write!(f, "the range ends ({end:#x}) before it starts ({start:#x})")
+            }
+            Self::IndexWidth { min, max } => {
+                write!(
+                    f,
+                    "invalid index width: at least {min} and at most {max} digits"
+                )
+            }
+        }
+    }
 }
+
+impl core::error::Error for OptionsError {}
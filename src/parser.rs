@@ -0,0 +1,373 @@
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+use crate::{
+    header::header_labels,
+    options::{
+        Base, Footer, Header, HexdOptions, HexdOptionsBuilder, LeadingZeroChar, MarkerPart,
+        OutputFormat,
+    },
+};
+
+/// Parses hexdumps back into the bytes they were rendered from.
+///
+/// The parser must be configured with the same [options](HexdOptions) that
+/// were used to produce the dump, since the layout of a line (base, grouping,
+/// spacing, index and ASCII columns) cannot be reliably inferred from its text.
+///
+/// ```
+/// use hxd::{AsHexd, options::{HexdOptionsBuilder, Spacing}, parser::HexdParser};
+///
+/// let v = (0..0x40).collect::<Vec<u8>>();
+/// let dump = v.hexd().ungrouped(8, Spacing::Normal).dump_to::<String>();
+///
+/// let parsed = HexdParser::new()
+///     .ungrouped(8, Spacing::Normal)
+///     .parse_str(&dump)
+///     .unwrap();
+/// assert_eq!(parsed, v);
+/// ```
+///
+/// Elided rows (`*`) are filled in with copies of the preceding row. This
+/// requires the index column to be shown, since it is the only way to know how
+/// many rows were skipped, unless the [marker](crate::options::ElisionMarker)
+/// includes the number of rows.
+///
+/// ```
+/// use hxd::{AsHexd, parser::HexdParser};
+///
+/// let v = vec![0x5au8; 256];
+/// let dump = v.hexd().dump_to::<String>();
+/// assert_eq!(dump.lines().count(), 3);
+///
+/// assert_eq!(HexdParser::new().parse_str(&dump).unwrap(), v);
+/// ```
+///
+/// [Plain](OutputFormat::Plain) dumps are read as a continuous run of digits.
+/// Hexadecimal and binary digits may be separated by any whitespace.
+///
+/// ```
+/// use hxd::{options::HexdOptionsBuilder, parser::HexdParser};
+///
+/// let parsed = HexdParser::new()
+///     .plain(None)
+///     .parse_str("48656c6c6f2c\n20776f726c6421\n")
+///     .unwrap();
+/// assert_eq!(parsed, b"Hello, world!");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HexdParser {
+    options: HexdOptions,
+}
+
+/// An error encountered while parsing a hexdump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line (starting at 1) on which the error occurred.
+    pub line: usize,
+    /// The kind of error that occurred.
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The index column is missing or could not be parsed.
+    InvalidIndex,
+
+    /// The byte cell at the given position (starting at 0) in the row
+    /// could not be parsed.
+    InvalidByte(usize),
+
+    /// An elision marker was found without a preceding row.
+    UnexpectedElision,
+
+    /// An elision marker was found, but the number of elided
+    /// rows could not be determined. This happens when the index of the row
+    /// following the marker is not a whole number of rows past the elided
+    /// row, or when the index column is hidden and the marker does not
+    /// include the number of rows.
+    UnsizedElision,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.kind {
+            ParseErrorKind::InvalidIndex => write!(f, "line {}: invalid index", self.line),
+            ParseErrorKind::InvalidByte(i) => {
+                write!(f, "line {}: invalid byte at position {}", self.line, i)
+            }
+            ParseErrorKind::UnexpectedElision => {
+                write!(f, "line {}: elision without a preceding row", self.line)
+            }
+            ParseErrorKind::UnsizedElision => {
+                write!(f, "line {}: cannot determine elided row count", self.line)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// A parsed row: its index (if shown) and the bytes it contains.
+struct ParsedRow {
+    index: Option<usize>,
+    bytes: Vec<u8>,
+}
+
+impl HexdParser {
+    /// Construct a new parser with [default options](HexdOptions::default).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a new parser with the given options.
+    pub fn new_with_options(options: HexdOptions) -> Self {
+        Self { options }
+    }
+
+    /// Parse a hexdump held in a single string.
+    pub fn parse_str(&self, dump: &str) -> Result<Vec<u8>, ParseError> {
+        self.parse_lines(dump.split('\n'))
+    }
+
+    /// Parse a hexdump from a sequence of lines, such as the output of
+    /// `dump_to::<Vec<String>>()`. Trailing newlines and empty lines are ignored,
+    /// as are [header rows](crate::options::Header) and [footers](Footer) if the
+    /// options include them.
+    pub fn parse_lines<S: AsRef<str>, I: IntoIterator<Item = S>>(
+        &self,
+        lines: I,
+    ) -> Result<Vec<u8>, ParseError> {
+        if let OutputFormat::Plain { .. } = self.options.format {
+            return self.parse_plain_lines(lines);
+        }
+
+        let header = (self.options.header != Header::Hidden).then(|| header_labels(&self.options));
+        let mut out = Vec::new();
+        let mut last_row: Option<ParsedRow> = None;
+        // the row count given by the last elision marker, if it was not followed by a row yet
+        let mut elision_pending: Option<Option<usize>> = None;
+
+        for (i, line) in lines.into_iter().enumerate() {
+            let line = line.as_ref();
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let err = |kind| ParseError { line: i + 1, kind };
+
+            if line.trim().is_empty() || header.as_deref() == Some(line.trim()) {
+                continue;
+            }
+            if self.options.footer != Footer::Hidden && line.starts_with("total: ") {
+                continue;
+            }
+
+            if let Some(rows) = self.parse_elision(line) {
+                if last_row.is_none() {
+                    return Err(err(ParseErrorKind::UnexpectedElision));
+                }
+                elision_pending = Some(rows);
+                continue;
+            }
+
+            let row = self.parse_row(line).map_err(err)?;
+
+            if let Some(rows) = elision_pending.take() {
+                let prev = last_row.as_ref().unwrap();
+                let count = self
+                    .elided_row_count(prev, &row)
+                    .or(rows)
+                    .ok_or(err(ParseErrorKind::UnsizedElision))?;
+                for _ in 0..count {
+                    out.extend_from_slice(&prev.bytes);
+                }
+            }
+
+            out.extend_from_slice(&row.bytes);
+            last_row = Some(row);
+        }
+
+        Ok(out)
+    }
+
+    fn parse_plain_lines<S: AsRef<str>, I: IntoIterator<Item = S>>(
+        &self,
+        lines: I,
+    ) -> Result<Vec<u8>, ParseError> {
+        let cell_width = self.options.base.cell_width();
+        // leading zero characters may be spaces, so whitespace is only
+        // insignificant in bases which do not use them
+        let skip_whitespace = matches!(self.options.base, Base::Hex | Base::Binary);
+
+        let mut out = Vec::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            let line = line.as_ref();
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let err = |kind| ParseError { line: i + 1, kind };
+
+            let mut cell = [0u8; 8];
+            let mut cell_len = 0usize;
+            let mut position = 0usize;
+            for c in line.bytes() {
+                if skip_whitespace && c.is_ascii_whitespace() {
+                    continue;
+                }
+                cell[cell_len] = c;
+                cell_len += 1;
+                if cell_len == cell_width {
+                    let b = self
+                        .parse_cell(&cell[..cell_width])
+                        .ok_or(err(ParseErrorKind::InvalidByte(position)))?;
+                    out.push(b);
+                    cell_len = 0;
+                    position += 1;
+                }
+            }
+            if cell_len > 0 {
+                return Err(err(ParseErrorKind::InvalidByte(position)));
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// If `line` is an [elision marker](crate::options::ElisionMarker),
+    /// return the number of elided rows if the marker includes it.
+    fn parse_elision(&self, line: &str) -> Option<Option<usize>> {
+        let marker = &self.options.elision.marker;
+        // the wording of a marker may depend on the number of rows
+        [marker.parts(1), marker.parts(2)]
+            .iter()
+            .find_map(|parts| match_marker(line.trim_end(), parts))
+    }
+
+    fn elided_row_count(&self, prev: &ParsedRow, next: &ParsedRow) -> Option<usize> {
+        let ew = self.options.elt_width();
+        let start = prev.index? + ew;
+        let end = next.index?;
+        let count = end.checked_sub(start)? / ew;
+        if start + count * ew != end {
+            return None;
+        }
+        Some(count)
+    }
+
+    fn parse_row(&self, line: &str) -> Result<ParsedRow, ParseErrorKind> {
+        let (index, data) = if self.options.show_index {
+            let (index, data) = self.split_index(line).ok_or(ParseErrorKind::InvalidIndex)?;
+            (Some(index), data.as_bytes())
+        } else {
+            (None, line.as_bytes())
+        };
+
+        let cell_width = self.options.base.cell_width();
+        let ew = self.options.elt_width();
+        let mut bytes = Vec::with_capacity(ew);
+        let mut pos = 0usize;
+
+        for i in 0..ew {
+            // lines may have had their trailing whitespace trimmed,
+            // so missing characters are treated as padding
+            let mut cell = [b' '; 8];
+            for (j, c) in cell[..cell_width].iter_mut().enumerate() {
+                if let Some(b) = data.get(pos + j) {
+                    *c = *b;
+                }
+            }
+            let cell = &cell[..cell_width];
+
+            if !cell.iter().all(|c| *c == b' ') {
+                let b = self
+                    .parse_cell(cell)
+                    .ok_or(ParseErrorKind::InvalidByte(i))?;
+                bytes.push(b);
+            }
+
+            pos += cell_width + self.options.grouping.spacing_for_index(i).as_spaces().len();
+        }
+
+        Ok(ParsedRow { index, bytes })
+    }
+
+    /// Split the index column off `line` and return the byte offset it shows.
+    fn split_index<'a>(&self, line: &'a str) -> Option<(usize, &'a str)> {
+        let format = &self.options.index_format;
+        let (index, data) = match &*format.separator {
+            // without a separator, only a fixed width tells where the index ends
+            "" => {
+                let width = format.max_width.filter(|max| *max == format.min_width)?;
+                let width = format.prefix.len() + width;
+                (line.get(..width)?, line.get(width..)?)
+            }
+            separator => line.split_once(separator)?,
+        };
+        let digits = index.strip_prefix(&*format.prefix)?;
+        let index = usize::from_str_radix(digits, format.base.radix()).ok()?;
+        index
+            .checked_mul(self.options.index_unit())
+            .map(|i| (i, data))
+    }
+
+    fn parse_cell(&self, cell: &[u8]) -> Option<u8> {
+        let (radix, lzc) = match self.options.base {
+            Base::Hex => (16, None),
+            Base::Binary => (2, None),
+            Base::Octal(lzc) => (8, Some(lzc)),
+            Base::Decimal(lzc) => (10, Some(lzc)),
+        };
+        let lead_char: Option<u8> = lzc
+            .filter(|lzc| *lzc != LeadingZeroChar::Zero)
+            .map(|lzc| lzc.into());
+
+        let mut value = 0u32;
+        for c in cell {
+            let c = if Some(*c) == lead_char { b'0' } else { *c };
+            let digit = (c as char).to_digit(radix)?;
+            value = value * radix + digit;
+        }
+        u8::try_from(value).ok()
+    }
+}
+
+/// Match `line` against the parts of an elision marker. Each field of
+/// the marker matches a run of letters and digits.
+fn match_marker(line: &str, parts: &[MarkerPart]) -> Option<Option<usize>> {
+    let mut rest = line;
+    let mut rows = None;
+    for (i, part) in parts.iter().enumerate() {
+        match part {
+            MarkerPart::Text(text) => {
+                // lines may have had their trailing whitespace trimmed
+                let text = if i == parts.len() - 1 {
+                    text.trim_end()
+                } else {
+                    text
+                };
+                rest = rest.strip_prefix(text)?;
+            }
+            field => {
+                let len = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric())
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    return None;
+                }
+                if *field == MarkerPart::Rows {
+                    rows = Some(rest[..len].parse().ok()?);
+                }
+                rest = &rest[len..];
+            }
+        }
+    }
+    rest.is_empty().then_some(rows)
+}
+
+/// [`HexdParser`] implements [`HexdOptionsBuilder`] so that it can be configured
+/// the same way as the [`Hexd`](crate::Hexd) that produced the dump.
+impl HexdOptionsBuilder for HexdParser {
+    fn map_options<F: FnOnce(HexdOptions) -> HexdOptions>(self, f: F) -> Self {
+        Self {
+            options: f(self.options),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::{
+    reader::ReadBytes, writer::WriteHexdump, HexdumpLineWriter, ReadWriteError, RowBuffer,
+};
+
+impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
+    /// Write the bytes of a row as a continuous run of digits, breaking
+    /// the line after every `bytes_per_line` bytes.
+    pub(crate) fn write_plain_row(
+        &mut self,
+        bytes_per_line: Option<usize>,
+        row: &RowBuffer,
+    ) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        for (p, b) in row.buffer.as_slice().iter().enumerate() {
+            if row.is_unknown(p) {
+                let style = self.options.color.map(|p| p.elision).unwrap_or_default();
+                self.formatter.write_unknown_byte(style);
+            } else {
+                let style = self.formatter.byte_style(*b);
+                self.formatter.write_byte(Some(*b), style);
+            }
+            self.line_len += 1;
+            if Some(self.line_len) == bytes_per_line {
+                self.flush_line()?;
+                self.line_len = 0;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use core::{cmp::min, str::FromStr};
+use std::{
+    fs::{self, File},
+    io::{self, ErrorKind},
+    os::unix::fs::FileExt,
+};
+
+use crate::reader::ReadBytes;
+
+/// The smallest page size on Linux. Pages which cannot be read are skipped
+/// in steps of this size.
+const PAGE_SIZE: usize = 4096;
+
+/// A mapped region of the address space of a process, as listed in
+/// `/proc/<pid>/maps`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryRegion {
+    /// The address of the first byte of the region.
+    pub start: usize,
+
+    /// The address after the last byte of the region.
+    pub end: usize,
+
+    pub readable: bool,
+    pub writable: bool,
+    pub executable: bool,
+
+    /// Whether the region is shared with other processes rather than
+    /// copied on write.
+    pub shared: bool,
+
+    /// The offset of the region in the mapped file.
+    pub offset: u64,
+
+    /// The mapped file, or a pseudo-path such as `[heap]` or `[stack]`.
+    /// Anonymous regions have no path.
+    pub path: Option<String>,
+}
+
+impl MemoryRegion {
+    /// The number of bytes in the region.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Whether `address` is within the region.
+    pub fn contains(&self, address: usize) -> bool {
+        (self.start..self.end).contains(&address)
+    }
+}
+
+/// The error returned when a line of `/proc/<pid>/maps` cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRegionError;
+
+impl core::fmt::Display for ParseRegionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid memory map line")
+    }
+}
+
+impl std::error::Error for ParseRegionError {}
+
+/// Parse a line of `/proc/<pid>/maps`, e.g.
+/// `7f2c4a200000-7f2c4a222000 r--p 00000000 08:01 1234 /usr/lib/libc.so.6`.
+impl FromStr for MemoryRegion {
+    type Err = ParseRegionError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut fields = line.split_ascii_whitespace();
+        let mut next = || fields.next().ok_or(ParseRegionError);
+        let (start, end) = next()?.split_once('-').ok_or(ParseRegionError)?;
+        let perms = next()?.as_bytes();
+        let offset = next()?;
+        let (_device, _inode) = (next()?, next()?);
+        if perms.len() != 4 {
+            return Err(ParseRegionError);
+        }
+        // the path is the rest of the line, and may contain spaces
+        let path = line
+            .splitn(6, |c: char| c.is_ascii_whitespace())
+            .nth(5)
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+
+        let hex = |s: &str| usize::from_str_radix(s, 16).map_err(|_| ParseRegionError);
+        let region = Self {
+            start: hex(start)?,
+            end: hex(end)?,
+            readable: perms[0] == b'r',
+            writable: perms[1] == b'w',
+            executable: perms[2] == b'x',
+            shared: perms[3] == b's',
+            offset: u64::from_str_radix(offset, 16).map_err(|_| ParseRegionError)?,
+            path: path.map(String::from),
+        };
+        if region.end < region.start {
+            return Err(ParseRegionError);
+        }
+        Ok(region)
+    }
+}
+
+/// Read the regions mapped by the process `pid`, in order of their address.
+pub fn memory_regions(pid: u32) -> io::Result<Vec<MemoryRegion>> {
+    parse_regions(&fs::read_to_string(format!("/proc/{pid}/maps"))?)
+}
+
+fn parse_regions(maps: &str) -> io::Result<Vec<MemoryRegion>> {
+    maps.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.parse()
+                .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
+
+/// A [reader](ReadBytes) over the memory of a live process, through
+/// `/proc/<pid>/mem`.
+///
+/// The reader starts at address zero, so a [range](crate::options::HexdRange)
+/// selects addresses, and the index of each row is the address of its first
+/// byte. Use [`IndexOffset::Absolute`](crate::options::IndexOffset::Absolute)
+/// with the start of the range to show addresses in a dump of a range that
+/// is read from a reader positioned elsewhere.
+///
+/// Bytes outside the mapped regions, and pages which cannot be read (such
+/// as guard pages), are shown as [placeholders](crate::options::Placeholder).
+/// The gaps between regions are skipped at once, so with
+/// [autoskip](crate::options::HexdOptions::autoskip) a dump without a range
+/// elides each of them in a single step. The dump ends after the last
+/// readable region.
+///
+/// Reading the memory of another process needs the same permissions as
+/// attaching a debugger to it.
+///
+/// ```
+/// use hxd::{options::HexdOptionsBuilder, process::ProcessMemory, FallibleHexd};
+///
+/// let memory = ProcessMemory::open_self()?;
+/// let stack = memory
+///     .regions()
+///     .iter()
+///     .find(|r| r.path.as_deref() == Some("[stack]"))
+///     .unwrap()
+///     .clone();
+/// let dump = FallibleHexd::new(memory)
+///     .range(stack.end - 0x40..stack.end)
+///     .dump_to::<String>()?;
+/// assert!(dump.contains(&format!("{:X}: ", stack.end - 0x10)));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct ProcessMemory {
+    mem: File,
+    regions: Vec<MemoryRegion>,
+    address: usize,
+}
+
+impl ProcessMemory {
+    /// Open the memory of the process `pid`. The regions are read once,
+    /// so regions which are mapped later are not read.
+    pub fn open(pid: u32) -> io::Result<Self> {
+        Self::open_paths(&format!("/proc/{pid}/maps"), &format!("/proc/{pid}/mem"))
+    }
+
+    /// Open the memory of the current process.
+    pub fn open_self() -> io::Result<Self> {
+        Self::open_paths("/proc/self/maps", "/proc/self/mem")
+    }
+
+    fn open_paths(maps: &str, mem: &str) -> io::Result<Self> {
+        // open the memory first, so that the regions are
+        // those of the same process if its pid is reused
+        let mem = File::open(mem)?;
+        let regions = parse_regions(&fs::read_to_string(maps)?)?;
+        Ok(Self {
+            mem,
+            regions,
+            address: 0,
+        })
+    }
+
+    /// The regions mapped by the process when it was opened.
+    pub fn regions(&self) -> &[MemoryRegion] {
+        &self.regions
+    }
+
+    /// Return the readable region which contains `address`, or else the
+    /// first readable region after it.
+    fn region_from(&self, address: usize) -> Option<&MemoryRegion> {
+        let first = self.regions.partition_point(|r| r.end <= address);
+        self.regions[first..]
+            .iter()
+            .find(|r| r.readable && !r.is_empty())
+    }
+}
+
+impl ReadBytes for ProcessMemory {
+    type Error = io::Error;
+
+    fn next_n<'buf>(&mut self, buf: &'buf mut [u8]) -> Result<&'buf [u8], Self::Error> {
+        let Some(region) = self.region_from(self.address) else {
+            return Ok(&[]);
+        };
+        if !region.contains(self.address) {
+            return Ok(&[]);
+        }
+        let len = min(buf.len(), region.end - self.address);
+        match self.mem.read_at(&mut buf[..len], self.address as u64) {
+            Ok(n) => {
+                self.address += n;
+                Ok(&buf[..n])
+            }
+            // the page is mapped but cannot be read (e.g. a guard page)
+            Err(e) if matches!(e.raw_os_error(), Some(5 /* EIO */ | 14 /* EFAULT */)) => Ok(&[]),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn skip_n(&mut self, n: usize) -> Result<usize, Self::Error> {
+        self.address = self.address.saturating_add(n);
+        Ok(n)
+    }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|r| r.readable)
+            .map(|r| r.end)
+    }
+
+    fn skip_unknown(&mut self, max: usize) -> Result<usize, Self::Error> {
+        let end = match self.region_from(self.address) {
+            None => return Ok(0),
+            Some(r) if r.start > self.address => r.start,
+            // an unreadable page in a readable region
+            Some(r) => min((self.address / PAGE_SIZE + 1) * PAGE_SIZE, r.end),
+        };
+        let n = min(max, end - self.address);
+        self.address += n;
+        Ok(n)
+    }
+}
@@ -1,4 +1,23 @@
-use std::{cmp::min, convert::Infallible, fmt::Debug, io::Read};
+use core::{
+    cmp::min,
+    convert::Infallible,
+    fmt::Debug,
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(all(feature = "std", target_os = "linux"))]
+use std::{
+    ffi::c_int,
+    io,
+    ops::Range,
+    os::{fd::AsRawFd, unix::fs::FileExt},
+};
+#[cfg(feature = "std")]
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
 
 use crate::Endianness;
 
@@ -10,6 +29,7 @@ pub trait GroupedReader<const N: usize> {
     }
 }
 
+#[derive(Clone)]
 pub struct ByteSliceReader<'a> {
     slice: &'a [u8],
     index: usize,
@@ -139,11 +159,107 @@ impl EndianBytes<16> for i128 {
         }
     }
 }
+
+/// The inverse of [`EndianBytes`]: this trait provides a method to
+/// read integer types back from sized byte arrays.
+/// Under the hood, implementations for primitive integer
+/// types call `from_be_bytes()` or `from_le_bytes()`
+/// depending on the [endianness](crate::options::Endianness).
+pub trait FromEndianBytes<const N: usize>: Sized {
+    fn from_bytes(bytes: [u8; N], end: Endianness) -> Self;
+}
+
+impl FromEndianBytes<1> for u8 {
+    fn from_bytes(bytes: [u8; 1], _: Endianness) -> Self {
+        bytes[0]
+    }
+}
+
+impl FromEndianBytes<1> for i8 {
+    fn from_bytes(bytes: [u8; 1], _: Endianness) -> Self {
+        bytes[0] as i8
+    }
+}
+
+impl FromEndianBytes<2> for u16 {
+    fn from_bytes(bytes: [u8; 2], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => Self::from_be_bytes(bytes),
+            Endianness::LittleEndian => Self::from_le_bytes(bytes),
+        }
+    }
+}
+
+impl FromEndianBytes<2> for i16 {
+    fn from_bytes(bytes: [u8; 2], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => Self::from_be_bytes(bytes),
+            Endianness::LittleEndian => Self::from_le_bytes(bytes),
+        }
+    }
+}
+
+impl FromEndianBytes<4> for u32 {
+    fn from_bytes(bytes: [u8; 4], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => Self::from_be_bytes(bytes),
+            Endianness::LittleEndian => Self::from_le_bytes(bytes),
+        }
+    }
+}
+
+impl FromEndianBytes<4> for i32 {
+    fn from_bytes(bytes: [u8; 4], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => Self::from_be_bytes(bytes),
+            Endianness::LittleEndian => Self::from_le_bytes(bytes),
+        }
+    }
+}
+
+impl FromEndianBytes<8> for u64 {
+    fn from_bytes(bytes: [u8; 8], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => Self::from_be_bytes(bytes),
+            Endianness::LittleEndian => Self::from_le_bytes(bytes),
+        }
+    }
+}
+
+impl FromEndianBytes<8> for i64 {
+    fn from_bytes(bytes: [u8; 8], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => Self::from_be_bytes(bytes),
+            Endianness::LittleEndian => Self::from_le_bytes(bytes),
+        }
+    }
+}
+
+impl FromEndianBytes<16> for u128 {
+    fn from_bytes(bytes: [u8; 16], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => Self::from_be_bytes(bytes),
+            Endianness::LittleEndian => Self::from_le_bytes(bytes),
+        }
+    }
+}
+
+impl FromEndianBytes<16> for i128 {
+    fn from_bytes(bytes: [u8; 16], endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => Self::from_be_bytes(bytes),
+            Endianness::LittleEndian => Self::from_le_bytes(bytes),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct GroupedSliceReader<'a, U: EndianBytes<N>, const N: usize> {
     slice: &'a [U],
     index: usize,
 }
 
+#[derive(Clone)]
 pub struct GroupedSliceByteReader<'a, U: EndianBytes<N>, const N: usize> {
     slice: &'a [U],
     elt_index: usize,
@@ -171,7 +287,7 @@ impl<'a, U: EndianBytes<N>, const N: usize> ReadBytes for GroupedSliceByteReader
 
 impl<'a, U: EndianBytes<N>, const N: usize> GroupedSliceByteReader<'a, U, N> {
     pub fn new(slice: &'a [U], endianness: Endianness) -> Self {
-        let current_elt = if slice.len() > 0 {
+        let current_elt = if !slice.is_empty() {
             Some(slice[0].to_bytes(endianness))
         } else {
             None
@@ -274,13 +390,25 @@ impl<'a, const N: usize, U: EndianBytes<N>> GroupedReader<N> for GroupedSliceRea
     }
 }
 
+/// Return the length of `iterator` if its size hint gives it exactly.
+fn exact_len<I: Iterator>(iterator: &I) -> Option<usize> {
+    match iterator.size_hint() {
+        (lower, Some(upper)) if lower == upper => Some(lower),
+        _ => None,
+    }
+}
+
+#[derive(Clone)]
 pub struct IteratorByteReader<I: Iterator<Item = u8>> {
     iterator: I,
+    /// The length of the iterator when it was wrapped, if it was known.
+    total: Option<usize>,
 }
 
 impl<I: Iterator<Item = u8>> IteratorByteReader<I> {
     pub fn new(iterator: I) -> Self {
-        Self { iterator }
+        let total = exact_len(&iterator);
+        Self { iterator, total }
     }
 }
 
@@ -305,29 +433,38 @@ impl<I: Iterator<Item = u8>> ReadBytes for IteratorByteReader<I> {
 
     fn skip_n(&mut self, n: usize) -> Result<usize, Self::Error> {
         for i in 0..n {
-            if let None = self.iterator.next() {
+            if self.iterator.next().is_none() {
                 return Ok(i);
             }
         }
         Ok(n)
     }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        self.total
+    }
 }
 
+#[derive(Clone)]
 pub struct GroupedIteratorReader<U: EndianBytes<N>, I: Iterator<Item = U>, const N: usize> {
     iterator: I,
     current: Option<[u8; N]>,
     index: usize,
     endianness: Endianness,
+    /// The length in bytes of the iterator when it was wrapped, if it was known.
+    total: Option<usize>,
 }
 
 impl<U: EndianBytes<N>, I: Iterator<Item = U>, const N: usize> GroupedIteratorReader<U, I, N> {
     pub fn new(mut iterator: I, endianness: Endianness) -> Self {
+        let total = exact_len(&iterator).and_then(|len| len.checked_mul(N));
         let current = iterator.next().map(|u| u.to_bytes(endianness));
         Self {
             iterator,
             current,
             index: 0,
             endianness,
+            total,
         }
     }
 
@@ -370,7 +507,7 @@ impl<U: EndianBytes<N>, I: Iterator<Item = U>, const N: usize> ReadBytes
     }
 
     fn total_byte_hint(&self) -> Option<usize> {
-        None
+        self.total
     }
 }
 
@@ -385,7 +522,7 @@ pub trait ReadBytes {
         while i < n {
             let skipbuf = &mut skipbuf[..min(n - i, SKIP_LEN)];
             let b = self.next_n(skipbuf)?;
-            if b.len() == 0 {
+            if b.is_empty() {
                 return Ok(i);
             }
             i += b.len();
@@ -395,6 +532,21 @@ pub trait ReadBytes {
     fn total_byte_hint(&self) -> Option<usize> {
         None
     }
+
+    /// Skip up to `max` bytes at the current position which the source has
+    /// no value for (e.g. unmapped pages), returning how many were skipped.
+    ///
+    /// This is called when [`next_n`](Self::next_n) returns no bytes. If it
+    /// returns zero as well, the input has ended; otherwise the skipped bytes
+    /// are shown as [placeholders](crate::options::Placeholder) and reading
+    /// continues after them.
+    ///
+    /// `max` may span many rows, and sources should skip as much of it as
+    /// they know to be unknown, so that long runs can be elided at once.
+    fn skip_unknown(&mut self, max: usize) -> Result<usize, Self::Error> {
+        let _ = max;
+        Ok(0)
+    }
 }
 
 impl<'b, T: Iterator<Item = &'b u8>> ReadBytes for T {
@@ -436,14 +588,17 @@ impl<'b, T: Iterator<Item = &'b u8>> ReadBytes for T {
     }
 }
 
+#[cfg(feature = "std")]
 pub struct IOReader<R: Read>(R);
 
+#[cfg(feature = "std")]
 impl<R: Read> IOReader<R> {
     pub fn new(reader: R) -> IOReader<R> {
         Self(reader)
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: Read> ReadBytes for IOReader<R> {
     type Error = std::io::Error;
 
@@ -454,10 +609,11 @@ impl<R: Read> ReadBytes for IOReader<R> {
 
     fn skip_n(&mut self, n: usize) -> Result<usize, Self::Error> {
         let mut skipped = 0;
+        let mut buf = [0u8; 1024];
         while skipped < n {
-            let mut buf = [0u8; 1024];
-            let bytes = self.next_n(buf.as_mut_slice())?;
-            if bytes.len() == 0 {
+            let len = min(n - skipped, buf.len());
+            let bytes = self.next_n(&mut buf[..len])?;
+            if bytes.is_empty() {
                 break;
             }
             skipped += bytes.len();
@@ -465,3 +621,281 @@ impl<R: Read> ReadBytes for IOReader<R> {
         Ok(skipped)
     }
 }
+
+/// A reader over a seekable stream, such as a [`File`].
+///
+/// Unlike [`IOReader`], skipping the start of a [range](crate::options::HexdRange)
+/// seeks past it instead of reading it, and the length of the stream is
+/// reported as the [total byte hint](ReadBytes::total_byte_hint).
+/// Skipping past the end of the stream leaves the reader at the end, so
+/// ranges which start beyond it produce an empty dump.
+///
+/// ```no_run
+/// use hxd::{options::HexdOptionsBuilder, reader::SeekReader, FallibleHexd};
+///
+/// let reader = SeekReader::open("disk.img")?;
+/// let dump = FallibleHexd::new(reader)
+///     .range(0x1_0000_0000..0x1_0000_0100)
+///     .dump_to::<String>()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "std")]
+pub struct SeekReader<R: Read + Seek> {
+    reader: R,
+    /// The offset of the reader, relative to where it started.
+    position: u64,
+    /// The length of the stream, relative to where the reader started.
+    len: u64,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read + Seek> SeekReader<R> {
+    /// Wrap `reader`, reading from its current position. The length of the
+    /// stream is found by seeking to its end and back.
+    pub fn new(mut reader: R) -> std::io::Result<Self> {
+        let start = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(Self {
+            reader,
+            position: 0,
+            len: end.saturating_sub(start),
+        })
+    }
+
+    /// Unwrap the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(feature = "std")]
+impl SeekReader<BufReader<File>> {
+    /// Wrap `file` in a buffered reader, reading from its current position.
+    pub fn from_file(file: File) -> std::io::Result<Self> {
+        Self::new(BufReader::new(file))
+    }
+
+    /// Open the file at `path` for reading.
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::from_file(File::open(path)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read + Seek> ReadBytes for SeekReader<R> {
+    type Error = std::io::Error;
+
+    fn next_n<'buf>(&mut self, buf: &'buf mut [u8]) -> Result<&'buf [u8], Self::Error> {
+        let n = self.reader.read(buf)?;
+        self.position += n as u64;
+        Ok(&buf[..n])
+    }
+
+    fn skip_n(&mut self, n: usize) -> Result<usize, Self::Error> {
+        let n = min(n as u64, self.len.saturating_sub(self.position));
+        // `n` is bounded by the requested `usize` and by the stream length,
+        // which fits in an `i64` offset, so neither cast truncates
+        self.reader.seek_relative(n as i64)?;
+        self.position += n;
+        Ok(n as usize)
+    }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        usize::try_from(self.len).ok()
+    }
+}
+
+// `off_t` is only 64 bits wide on 64-bit targets, so 32-bit
+// targets use the large file variant to reach past 2 GiB
+#[cfg(all(feature = "std", target_os = "linux"))]
+extern "C" {
+    #[cfg_attr(not(target_pointer_width = "64"), link_name = "lseek64")]
+    fn lseek(fd: c_int, offset: i64, whence: c_int) -> i64;
+}
+
+// The values of `SEEK_DATA` and `SEEK_HOLE` are those of the Linux headers
+// (`include/uapi/linux/fs.h`), and `ENXIO` is that of
+// `include/uapi/asm-generic/errno-base.h`. They are the same on every
+// architecture.
+#[cfg(all(feature = "std", target_os = "linux"))]
+const SEEK_DATA: c_int = 3;
+#[cfg(all(feature = "std", target_os = "linux"))]
+const SEEK_HOLE: c_int = 4;
+#[cfg(all(feature = "std", target_os = "linux"))]
+const ENXIO: i32 = 6;
+
+/// A reader over a sparse file on Linux, which reports the holes of the
+/// file as [unknown bytes](ReadBytes::skip_unknown) instead of reading them
+/// as zeroes.
+///
+/// Holes are found with `SEEK_DATA` and `SEEK_HOLE`. File systems which do
+/// not track holes report the whole file as data. Like [`SeekReader`],
+/// skipping the start of a [range](crate::options::HexdRange) does not read it.
+///
+/// ```no_run
+/// use hxd::{reader::SparseFileReader, FallibleHexd};
+///
+/// let reader = SparseFileReader::open("disk.img")?;
+/// let dump = FallibleHexd::new(reader).dump_to::<String>()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub struct SparseFileReader {
+    file: File,
+    position: u64,
+    len: u64,
+    /// The data or hole which contains `position`, as last found.
+    extent: Range<u64>,
+    is_data: bool,
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl SparseFileReader {
+    /// Read `file` from its start.
+    pub fn new(file: File) -> io::Result<Self> {
+        let len = file.metadata()?.len();
+        Ok(Self {
+            file,
+            position: 0,
+            len,
+            extent: 0..0,
+            is_data: false,
+        })
+    }
+
+    /// Open the file at `path` for reading.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(File::open(path)?)
+    }
+
+    /// Return the offset of the next data or hole at or after `offset`,
+    /// or `None` if there is none.
+    fn seek(&self, offset: u64, whence: c_int) -> io::Result<Option<u64>> {
+        let offset = i64::try_from(offset).map_err(|_| io::ErrorKind::InvalidInput)?;
+        // SAFETY: `lseek` only reads its arguments. The position of the
+        // file is changed, but all reads are positioned.
+        let found = unsafe { lseek(self.file.as_raw_fd(), offset, whence) };
+        if found >= 0 {
+            return Ok(Some(found as u64));
+        }
+        match io::Error::last_os_error() {
+            e if e.raw_os_error() == Some(ENXIO) => Ok(None),
+            e => Err(e),
+        }
+    }
+
+    /// Find the data or hole which contains the current position.
+    fn locate(&mut self) -> io::Result<()> {
+        let position = self.position;
+        if self.extent.contains(&position) || position >= self.len {
+            return Ok(());
+        }
+        (self.extent, self.is_data) = match self.seek(position, SEEK_DATA)? {
+            Some(data) if data == position => {
+                let hole = self.seek(position, SEEK_HOLE)?.unwrap_or(self.len);
+                (position..hole.min(self.len), true)
+            }
+            Some(data) => (position..data.min(self.len), false),
+            None => (position..self.len, false),
+        };
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl ReadBytes for SparseFileReader {
+    type Error = io::Error;
+
+    fn next_n<'buf>(&mut self, buf: &'buf mut [u8]) -> Result<&'buf [u8], Self::Error> {
+        self.locate()?;
+        if self.position >= self.len || !self.is_data {
+            return Ok(&[]);
+        }
+        let len = min(buf.len() as u64, self.extent.end - self.position) as usize;
+        let n = self.file.read_at(&mut buf[..len], self.position)?;
+        self.position += n as u64;
+        Ok(&buf[..n])
+    }
+
+    fn skip_n(&mut self, n: usize) -> Result<usize, Self::Error> {
+        let n = min(n as u64, self.len.saturating_sub(self.position));
+        self.position += n;
+        Ok(n as usize)
+    }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        usize::try_from(self.len).ok()
+    }
+
+    fn skip_unknown(&mut self, max: usize) -> Result<usize, Self::Error> {
+        self.locate()?;
+        if self.position >= self.len || self.is_data {
+            return Ok(0);
+        }
+        let n = min(max as u64, self.extent.end - self.position);
+        self.position += n;
+        Ok(n as usize)
+    }
+}
+
+/// The asynchronous counterpart of [`ReadBytes`], used by
+/// [`AsyncHexd`](crate::asynchronous::AsyncHexd).
+///
+/// This mirrors the `poll_read` method of the `AsyncRead` traits of async
+/// runtimes, so a stream of such a runtime can be adapted by forwarding to it.
+pub trait AsyncReadBytes {
+    type Error: Debug;
+
+    /// Attempt to read bytes into `buf`, returning the number of bytes read.
+    /// Zero bytes are returned only at the end of input (or if `buf` is empty).
+    ///
+    /// If no bytes are available yet, this returns [`Poll::Pending`] and
+    /// arranges for the waker of `cx` to be woken once they are.
+    fn poll_next_n(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>>;
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<T: AsyncReadBytes + Unpin + ?Sized> AsyncReadBytes for &mut T {
+    type Error = T::Error;
+
+    fn poll_next_n(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        Pin::new(&mut **self).poll_next_n(cx, buf)
+    }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        (**self).total_byte_hint()
+    }
+}
+
+/// Slices are always ready.
+impl AsyncReadBytes for &[u8] {
+    type Error = Infallible;
+
+    fn poll_next_n(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        let n = min(buf.len(), self.len());
+        let (bytes, rest) = self.split_at(n);
+        buf[..n].copy_from_slice(bytes);
+        *self = rest;
+        Poll::Ready(Ok(n))
+    }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
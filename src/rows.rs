@@ -0,0 +1,267 @@
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+
+use crate::{
+    annotation::{Annotation, Annotations},
+    options::HexdOptions,
+    reader::ReadBytes,
+    HexdumpLineIterator, LineFormatter, LineIteratorResult, RowBuffer,
+};
+
+/// An iterator over the rows of a dump, for callers which lay out the
+/// bytes themselves instead of writing the dump as text.
+///
+/// The rows are the same as those of the text dump: the first row is
+/// aligned as configured, and runs of identical rows are elided if
+/// [autoskip](HexdOptions::autoskip) is enabled. Use [`render`](Self::render)
+/// and [`render_elision`](Self::render_elision) to get the text of a single line.
+///
+/// Typically this is constructed with [`Hexd::rows`](crate::Hexd::rows).
+///
+/// ```
+/// use hxd::{AsHexd, options::{HexdOptionsBuilder, Spacing}, rows::Line};
+///
+/// let v = [vec![1u8; 3], vec![0u8; 32]].concat();
+/// let mut rows = v.hexd().ungrouped(4, Spacing::Normal).rows();
+///
+/// let mut lines = Vec::new();
+/// while let Some(line) = rows.next() {
+///     match line.unwrap() {
+///         Line::Row(row) => lines.push(format!("{:02x?} {}", row.bytes(), rows.render(&row))),
+///         Line::Elided(run) => lines.push(format!("{} rows from {} to {}", run.rows, run.start, run.end)),
+///     }
+/// }
+/// assert_eq!(lines, [
+///     "[01, 01, 01, 00] 00000000: 01 01 01 00 |....|",
+///     "[00, 00, 00, 00] 00000004: 00 00 00 00 |....|",
+///     "5 rows from 8 to 28",
+///     "[00, 00, 00, 00] 0000001C: 00 00 00 00 |....|",
+///     "[00, 00, 00] 00000020: 00 00 00    |... |",
+/// ]);
+/// ```
+pub struct Rows<R: ReadBytes> {
+    pub(crate) line_iterator: HexdumpLineIterator<R>,
+    formatter: LineFormatter<String>,
+    run: Option<Run>,
+    pending: VecDeque<Line>,
+}
+
+/// A run of identical rows which are being elided.
+struct Run {
+    start: usize,
+    /// The first rows of the run, which are shown if the run
+    /// is too short to be elided.
+    held: Vec<RowBuffer>,
+    last: RowBuffer,
+    rows: usize,
+}
+
+/// An item of [`Rows`].
+// rows are kept on the stack rather than allocated one by one
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum Line {
+    /// A row of bytes.
+    Row(Row),
+
+    /// A run of rows which were elided because they are identical to the
+    /// row before them. Each run is followed by the row after its end,
+    /// which is identical as well and is shown to close the run.
+    Elided(ElidedRun),
+}
+
+/// A row of bytes in a dump.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub(crate) row: RowBuffer,
+    width: usize,
+}
+
+impl Row {
+    /// The offset in the input of the first cell of the row. Like
+    /// [`print_range`](HexdOptions::print_range), this is not affected
+    /// by the [index offset](HexdOptions::index_offset).
+    pub fn index(&self) -> usize {
+        self.row.row_index
+    }
+
+    /// The bytes of the row.
+    pub fn bytes(&self) -> &[u8] {
+        self.row.buffer.as_slice()
+    }
+
+    /// Whether the reader has [no value](ReadBytes::skip_unknown) for the
+    /// `i`th byte of [`bytes`](Self::bytes). Such bytes are zero in `bytes`.
+    pub fn is_unknown(&self, i: usize) -> bool {
+        self.row.is_unknown(i)
+    }
+
+    /// The number of empty cells before the first byte, if the row is
+    /// the first one of an aligned dump which starts in the middle of a row.
+    pub fn leading_padding(&self) -> usize {
+        self.row.elt_index - self.row.row_index
+    }
+
+    /// The number of empty cells after the last byte, if the row is the
+    /// last one and the input ends in the middle of it.
+    pub fn trailing_padding(&self) -> usize {
+        self.width - self.leading_padding() - self.row.length
+    }
+}
+
+/// A run of elided rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElidedRun {
+    /// The offset in the input of the first elided byte.
+    pub start: usize,
+
+    /// The offset in the input after the last elided byte.
+    pub end: usize,
+
+    /// The number of elided rows.
+    pub rows: usize,
+}
+
+impl<R: ReadBytes> Rows<R> {
+    /// Read rows from `line_iterator`, and render them with `options`.
+    pub(crate) fn new(line_iterator: HexdumpLineIterator<R>, options: HexdOptions) -> Self {
+        Self {
+            line_iterator,
+            formatter: LineFormatter::new(String::new(), options),
+            run: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Highlight the given annotations when rendering rows. Rows with
+    /// annotated bytes are never elided.
+    pub(crate) fn annotated(mut self, annotations: Vec<Annotation>) -> Self {
+        // rows are checked against the annotations one by one
+        self.line_iterator.collapse_unknown = annotations.is_empty();
+        self.formatter.annotations = Annotations::new(annotations);
+        self
+    }
+
+    /// Render `row` as it appears in the text dump, without a line break
+    /// and without the lines which underline its annotations.
+    pub fn render(&mut self, row: &Row) -> String {
+        let byte_hint = self.line_iterator.reader.total_byte_hint();
+        self.formatter.write_row_index(row.row.row_index, byte_hint);
+        self.formatter.write_row_bytes(&row.row, None);
+        self.formatter.write_row_ascii(&row.row, None);
+        self.take_line()
+    }
+
+    /// Render the [marker](crate::options::ElisionMarker) written in place
+    /// of `run` in the text dump, without a line break.
+    pub fn render_elision(&mut self, run: &ElidedRun) -> String {
+        let byte_hint = self.line_iterator.reader.total_byte_hint();
+        self.formatter.write_elision(run, byte_hint);
+        self.take_line()
+    }
+
+    fn take_line(&mut self) -> String {
+        // writing to a string never fails
+        let _ = self.formatter.flush_line();
+
+        let mut line = core::mem::take(&mut self.formatter.writer);
+        if line.ends_with('\n') {
+            line.pop();
+        }
+        line
+    }
+
+    fn row(&self, row: RowBuffer) -> Line {
+        Line::Row(Row {
+            row,
+            width: self.line_iterator.options.elt_width(),
+        })
+    }
+
+    /// Return the next line, reading at most one row from the line
+    /// iterator. `Ok(None)` is returned if a row was read which does not
+    /// complete a line yet, and `None` once there are no more lines.
+    pub(crate) fn step(&mut self) -> Option<Result<Option<Line>, R::Error>> {
+        if let Some(line) = self.pending.pop_front() {
+            return Some(Ok(Some(line)));
+        }
+
+        let Some(next) = self.line_iterator.next() else {
+            self.end_run();
+            return self.pending.pop_front().map(|line| Ok(Some(line)));
+        };
+        match next {
+            Err(e) => return Some(Err(e)),
+            Ok(LineIteratorResult::Elided(r, rows))
+                if !self.formatter.annotations.intersects(&r) =>
+            {
+                let min_rows = self.min_elided_rows();
+                match &mut self.run {
+                    Some(run) => {
+                        let prev = core::mem::replace(&mut run.last, r);
+                        if run.held.len() < min_rows {
+                            run.held.push(prev);
+                        }
+                        run.rows += rows;
+                    }
+                    None => {
+                        let width = self.line_iterator.options.elt_width();
+                        self.run = Some(Run {
+                            start: r.row_index - (rows - 1) * width,
+                            held: Vec::new(),
+                            last: r,
+                            rows,
+                        })
+                    }
+                }
+            }
+            // annotated rows are never elided, so that their legend is shown
+            Ok(LineIteratorResult::Row(r) | LineIteratorResult::Elided(r, _)) => {
+                self.end_run();
+                let row = self.row(r);
+                self.pending.push_back(row);
+            }
+        }
+        Some(Ok(self.pending.pop_front()))
+    }
+
+    /// The number of rows a run must hide before it is elided.
+    fn min_elided_rows(&self) -> usize {
+        self.line_iterator.options.elision.min_rows.max(1)
+    }
+
+    /// Queue the end of the current run of elided rows. Runs which hide
+    /// enough rows are elided up to their last row, while shorter runs
+    /// are shown as is.
+    fn end_run(&mut self) {
+        if let Some(run) = self.run.take() {
+            if run.rows > self.min_elided_rows() {
+                self.pending.push_back(Line::Elided(ElidedRun {
+                    start: run.start,
+                    end: run.last.row_index,
+                    rows: run.rows - 1,
+                }));
+            } else {
+                for r in run.held {
+                    let row = self.row(r);
+                    self.pending.push_back(row);
+                }
+            }
+            let last = self.row(run.last);
+            self.pending.push_back(last);
+        }
+    }
+}
+
+impl<R: ReadBytes> Iterator for Rows<R> {
+    type Item = Result<Line, R::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.step()? {
+                Ok(Some(line)) => return Some(Ok(line)),
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Display, ops::Range, str::FromStr};
+
+use crate::{
+    annotation::{Annotation, Highlight},
+    options::Endianness,
+    reader::FromEndianBytes,
+};
+
+/// A description of a binary layout, used to decode bytes into named fields
+/// and to [annotate](crate::Hexd::annotations) a dump with them.
+///
+/// A schema is a comma-separated list of `name: type` fields. The types are:
+///
+///  - integers: `u8` and `i8`, and `u16`, `i16`, `u32`, `i32`, `u64`, `i64`,
+///    `u128` and `i128` followed by `le` or `be` for their endianness
+///    (e.g. `u32be`)
+///  - floats: `f32le`, `f32be`, `f64le` and `f64be`
+///  - arrays: `[type; count]`, where the count is a number, the name of an
+///    unsigned field decoded earlier in the same or an enclosing struct,
+///    or `*` to repeat the element until the end of the data
+///  - structs: `{ name: type, ... }`
+///
+/// Arrays of `u8` are decoded as a single byte string, while the elements of
+/// other arrays are decoded as separate fields.
+///
+/// ```
+/// use hxd::{AsHexd, options::{HexdOptionsBuilder, Spacing}, schema::Schema};
+///
+/// let schema = Schema::parse("magic: u32be, version: u16le, len: u8, payload: [u8; len]").unwrap();
+/// let bytes = b"\x7fBIN\x02\x00\x03abc";
+///
+/// let dump = bytes.hexd()
+///     .ungrouped(10, Spacing::Normal)
+///     .annotations(schema.annotations(bytes).unwrap())
+///     .dump_to::<String>();
+/// assert_eq!(dump, concat!(
+///     "00000000: 7F 42 49 4E 02 00 03 61 62 63 |.BIN...abc|  ",
+///     "a: magic = 2135050574 (0x7F42494E), b: version = 2, c: len = 3, d: payload = b\"abc\"\n",
+///     "          aaaaaaaaaaa bbbbb cc dddddddd  aaaabbcddd\n",
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    fields: Vec<FieldDef>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FieldDef {
+    name: String,
+    ty: Type,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Type {
+    Int {
+        size: usize,
+        signed: bool,
+        endianness: Endianness,
+    },
+    Float {
+        size: usize,
+        endianness: Endianness,
+    },
+    Array(Box<Type>, Count),
+    Struct(Vec<FieldDef>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Count {
+    Fixed(usize),
+    Field(String),
+    Remaining,
+}
+
+/// A field decoded by a [`Schema`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// The path of the field, such as `header.sizes[2]`.
+    pub path: String,
+
+    /// The offsets of the bytes of the field.
+    pub range: Range<usize>,
+
+    /// The decoded value.
+    pub value: Value,
+}
+
+/// The value of a decoded [`Field`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Unsigned(u128),
+    Signed(i128),
+    F32(f32),
+    F64(f64),
+    Bytes(Vec<u8>),
+}
+
+/// An error encountered while parsing a schema or decoding bytes with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// The schema is malformed at the given offset.
+    Syntax(usize),
+
+    /// The type at the given offset of the schema is not known.
+    UnknownType(usize),
+
+    /// The count of the array with the given path names a field which has
+    /// not been decoded, or which is not an unsigned integer.
+    InvalidCount(String),
+
+    /// The bytes ended before the end of the field with the given path.
+    Truncated(String),
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SchemaError::Syntax(pos) => write!(f, "syntax error at offset {pos}"),
+            SchemaError::UnknownType(pos) => write!(f, "unknown type at offset {pos}"),
+            SchemaError::InvalidCount(path) => write!(f, "{path}: invalid array count"),
+            SchemaError::Truncated(path) => write!(f, "{path}: not enough bytes"),
+        }
+    }
+}
+
+impl core::error::Error for SchemaError {}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Value::Unsigned(n) if *n < 10 => write!(f, "{n}"),
+            Value::Unsigned(n) => write!(f, "{n} (0x{n:X})"),
+            Value::Signed(n) => write!(f, "{n}"),
+            Value::F32(x) => write!(f, "{x}"),
+            Value::F64(x) => write!(f, "{x}"),
+            Value::Bytes(b) => write!(f, "b\"{}\"", b.escape_ascii()),
+        }
+    }
+}
+
+/// Fields are displayed as `path = value`, which is the label of their annotation.
+impl Display for Field {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} = {}", self.path, self.value)
+    }
+}
+
+impl Schema {
+    /// Parse a schema from its text.
+    pub fn parse(schema: &str) -> Result<Self, SchemaError> {
+        let mut parser = SchemaParser {
+            text: schema.as_bytes(),
+            pos: 0,
+        };
+        let fields = parser.fields(None)?;
+        Ok(Self { fields })
+    }
+
+    /// Decode `bytes`, starting at its first byte, into a list of fields in
+    /// the order in which they appear. Structs and arrays other than byte
+    /// strings are not fields themselves; only their elements are.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<Field>, SchemaError> {
+        let mut decoder = Decoder {
+            bytes,
+            offset: 0,
+            fields: Vec::new(),
+            scopes: Vec::new(),
+        };
+        decoder.decode_fields(&self.fields, "")?;
+        Ok(decoder.fields)
+    }
+
+    /// Decode `bytes` and return an underlined [annotation](Annotation)
+    /// for each field, labeled with its path and value.
+    pub fn annotations(&self, bytes: &[u8]) -> Result<Vec<Annotation>, SchemaError> {
+        Ok(self
+            .decode(bytes)?
+            .into_iter()
+            .map(|field| {
+                Annotation::new(field.range.clone(), field.to_string(), Highlight::Underline)
+            })
+            .collect())
+    }
+}
+
+impl FromStr for Schema {
+    type Err = SchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+struct SchemaParser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl SchemaParser<'_> {
+    /// Parse a list of fields, up to the end of the text or to `close`.
+    fn fields(&mut self, close: Option<u8>) -> Result<Vec<FieldDef>, SchemaError> {
+        let mut fields = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == close {
+                break;
+            }
+            let name = self.ident()?;
+            self.expect(b':')?;
+            let ty = self.ty()?;
+            fields.push(FieldDef { name, ty });
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                c if c == close => break,
+                _ => return Err(SchemaError::Syntax(self.pos)),
+            }
+        }
+        // schemas and structs need at least one field
+        if fields.is_empty() {
+            return Err(SchemaError::Syntax(self.pos));
+        }
+        self.pos += close.map_or(0, |_| 1);
+        Ok(fields)
+    }
+
+    fn ty(&mut self) -> Result<Type, SchemaError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'[') => {
+                self.pos += 1;
+                let element = self.ty()?;
+                self.expect(b';')?;
+                self.skip_whitespace();
+                let count = match self.peek() {
+                    Some(b'*') => {
+                        self.pos += 1;
+                        Count::Remaining
+                    }
+                    Some(c) if c.is_ascii_digit() => Count::Fixed(self.number()?),
+                    _ => Count::Field(self.ident()?),
+                };
+                self.expect(b']')?;
+                Ok(Type::Array(Box::new(element), count))
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                Ok(Type::Struct(self.fields(Some(b'}'))?))
+            }
+            _ => {
+                let start = self.pos;
+                let name = self.ident()?;
+                primitive(&name).ok_or(SchemaError::UnknownType(start))
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, SchemaError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == b'_' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        match &self.text[start..self.pos] {
+            [first, ..] if !first.is_ascii_digit() => {
+                Ok(String::from_utf8_lossy(&self.text[start..self.pos]).into_owned())
+            }
+            _ => Err(SchemaError::Syntax(start)),
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, SchemaError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        core::str::from_utf8(&self.text[start..self.pos])
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .ok_or(SchemaError::Syntax(start))
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), SchemaError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(SchemaError::Syntax(self.pos))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+}
+
+fn primitive(name: &str) -> Option<Type> {
+    let (base, endianness) = if let Some(base) = name.strip_suffix("le") {
+        (base, Some(Endianness::LittleEndian))
+    } else if let Some(base) = name.strip_suffix("be") {
+        (base, Some(Endianness::BigEndian))
+    } else {
+        (name, None)
+    };
+
+    let (kind, size) = base.split_at_checked(1)?;
+    let size = match size {
+        "8" => 1,
+        "16" => 2,
+        "32" => 4,
+        "64" => 8,
+        "128" => 16,
+        _ => return None,
+    };
+    // single bytes have no endianness, but every other type needs one
+    let endianness = match (size, endianness) {
+        (1, None) => Endianness::BigEndian,
+        (1, Some(_)) | (_, None) => return None,
+        (_, Some(e)) => e,
+    };
+
+    match kind {
+        "u" | "i" => Some(Type::Int {
+            size,
+            signed: kind == "i",
+            endianness,
+        }),
+        "f" if size == 4 || size == 8 => Some(Type::Float { size, endianness }),
+        _ => None,
+    }
+}
+
+struct Decoder<'s, 'b> {
+    bytes: &'b [u8],
+    offset: usize,
+    fields: Vec<Field>,
+    /// The unsigned fields decoded so far in each enclosing struct,
+    /// which may be used as array counts.
+    scopes: Vec<Vec<(&'s str, u128)>>,
+}
+
+impl<'s> Decoder<'s, '_> {
+    fn decode_fields(&mut self, defs: &'s [FieldDef], prefix: &str) -> Result<(), SchemaError> {
+        self.scopes.push(Vec::new());
+        for def in defs {
+            let path = if prefix.is_empty() {
+                def.name.clone()
+            } else {
+                format!("{prefix}.{}", def.name)
+            };
+            if let Some(value) = self.decode_type(&def.ty, &path)? {
+                self.scopes.last_mut().unwrap().push((&def.name, value));
+            }
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+
+    /// Decode a value of type `ty`, returning its value if it is an unsigned integer.
+    fn decode_type(&mut self, ty: &'s Type, path: &str) -> Result<Option<u128>, SchemaError> {
+        let start = self.offset;
+        let value = match ty {
+            Type::Int {
+                size,
+                signed,
+                endianness,
+            } => int_value(self.take(*size, path)?, *signed, *endianness),
+            Type::Float { size, endianness } => {
+                let bytes = self.take(*size, path)?;
+                match size {
+                    4 => Value::F32(f32::from_bits(u32::from_bytes(array(bytes), *endianness))),
+                    _ => Value::F64(f64::from_bits(u64::from_bytes(array(bytes), *endianness))),
+                }
+            }
+            Type::Array(element, count) => {
+                let count = match count {
+                    Count::Fixed(n) => Some(*n),
+                    Count::Field(name) => Some(self.lookup(name, path)?),
+                    Count::Remaining => None,
+                };
+                if let Type::Int {
+                    size: 1,
+                    signed: false,
+                    ..
+                } = **element
+                {
+                    let remaining = self.bytes.len() - self.offset;
+                    Value::Bytes(self.take(count.unwrap_or(remaining), path)?.to_vec())
+                } else {
+                    let mut i = 0;
+                    while count.map_or(self.offset < self.bytes.len(), |count| i < count) {
+                        let element_start = self.offset;
+                        self.decode_type(element, &format!("{path}[{i}]"))?;
+                        // elements without bytes would repeat forever
+                        if self.offset == element_start {
+                            break;
+                        }
+                        i += 1;
+                    }
+                    return Ok(None);
+                }
+            }
+            Type::Struct(defs) => {
+                self.decode_fields(defs, path)?;
+                return Ok(None);
+            }
+        };
+
+        let unsigned = match value {
+            Value::Unsigned(n) => Some(n),
+            _ => None,
+        };
+        self.fields.push(Field {
+            path: path.to_string(),
+            range: start..self.offset,
+            value,
+        });
+        Ok(unsigned)
+    }
+
+    fn take(&mut self, n: usize, path: &str) -> Result<&[u8], SchemaError> {
+        let end = self
+            .offset
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| SchemaError::Truncated(path.to_string()))?;
+        let bytes = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn lookup(&self, name: &str, path: &str) -> Result<usize, SchemaError> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(n, _)| *n == name)
+            .and_then(|(_, value)| usize::try_from(*value).ok())
+            .ok_or_else(|| SchemaError::InvalidCount(path.to_string()))
+    }
+}
+
+fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    bytes.try_into().unwrap()
+}
+
+fn int_value(bytes: &[u8], signed: bool, e: Endianness) -> Value {
+    match (bytes.len(), signed) {
+        (1, false) => Value::Unsigned(u8::from_bytes(array(bytes), e).into()),
+        (1, true) => Value::Signed(i8::from_bytes(array(bytes), e).into()),
+        (2, false) => Value::Unsigned(u16::from_bytes(array(bytes), e).into()),
+        (2, true) => Value::Signed(i16::from_bytes(array(bytes), e).into()),
+        (4, false) => Value::Unsigned(u32::from_bytes(array(bytes), e).into()),
+        (4, true) => Value::Signed(i32::from_bytes(array(bytes), e).into()),
+        (8, false) => Value::Unsigned(u64::from_bytes(array(bytes), e).into()),
+        (8, true) => Value::Signed(i64::from_bytes(array(bytes), e).into()),
+        (_, false) => Value::Unsigned(u128::from_bytes(array(bytes), e)),
+        (_, true) => Value::Signed(i128::from_bytes(array(bytes), e)),
+    }
+}
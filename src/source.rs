@@ -0,0 +1,155 @@
+use alloc::string::ToString;
+
+use crate::{
+    options::{SourceFormat, SourceLanguage},
+    reader::ReadBytes,
+    writer::WriteHexdump,
+    HexdumpLineWriter, ReadWriteError, RowBuffer,
+};
+
+impl<R: ReadBytes, W: WriteHexdump> HexdumpLineWriter<R, W> {
+    /// Write the declaration which opens the source code. Each row
+    /// of the line iterator is then written on its own line.
+    pub(crate) fn write_source_head(
+        &mut self,
+        format: SourceFormat,
+    ) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        let name = format.name.as_bytes();
+
+        match format.language {
+            SourceLanguage::C => {
+                self.formatter.push_slice(b"unsigned char ");
+                self.formatter.push_slice(name);
+                self.formatter.push_slice(b"[] = {");
+            }
+            SourceLanguage::RustArray => {
+                self.formatter.push_slice(b"const ");
+                self.formatter.push_slice(name);
+                self.formatter.push_slice(b": [u8; ");
+                self.formatter.push_slice(name);
+                self.formatter.push_slice(b"_LEN] = [");
+            }
+            SourceLanguage::RustBytes => {
+                self.formatter.push_slice(b"const ");
+                self.formatter.push_slice(name);
+                self.formatter.push_slice(b": &[u8; ");
+                self.formatter.push_slice(name);
+                self.formatter.push_slice(b"_LEN] = b\"\\");
+            }
+            SourceLanguage::PythonBytes => {
+                self.formatter.push_slice(name);
+                self.formatter.push_slice(b" = (");
+            }
+        }
+        self.flush_line()
+    }
+
+    /// Write the row read before `row`. A row is only written once the next
+    /// one has been read, so that the last row can be terminated differently.
+    pub(crate) fn write_source_next(
+        &mut self,
+        language: SourceLanguage,
+        row: RowBuffer,
+    ) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        match self.held_row.replace(row) {
+            Some(previous) => self.write_source_row(language, &previous, false),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the last row and the declarations which close the source code.
+    pub(crate) fn write_source_tail(
+        &mut self,
+        format: SourceFormat,
+    ) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        if let Some(last) = self.held_row.take() {
+            self.write_source_row(format.language, &last, true)?;
+        }
+
+        let name = format.name.as_bytes();
+        let count = self.bytes_read;
+        let len = count.to_string();
+        match format.language {
+            SourceLanguage::C => {
+                self.formatter.push_slice(b"};");
+                self.flush_line()?;
+                self.formatter.push_slice(b"unsigned int ");
+                self.formatter.push_slice(name);
+                self.formatter.push_slice(b"_len = ");
+                self.formatter.push_slice(len.as_bytes());
+                self.formatter.push_slice(b";");
+            }
+            SourceLanguage::RustArray | SourceLanguage::RustBytes => {
+                if format.language == SourceLanguage::RustArray {
+                    self.formatter.push_slice(b"];");
+                    self.flush_line()?;
+                } else if count == 0 {
+                    self.formatter.push_slice(b"\";");
+                    self.flush_line()?;
+                }
+                self.formatter.push_slice(b"const ");
+                self.formatter.push_slice(name);
+                self.formatter.push_slice(b"_LEN: usize = ");
+                self.formatter.push_slice(len.as_bytes());
+                self.formatter.push_slice(b";");
+            }
+            SourceLanguage::PythonBytes => {
+                if count == 0 {
+                    self.formatter.push_slice(b"    b\"\"");
+                    self.flush_line()?;
+                }
+                self.formatter.push_slice(b")");
+            }
+        }
+        self.flush_line()
+    }
+
+    fn write_source_row(
+        &mut self,
+        language: SourceLanguage,
+        row: &RowBuffer,
+        last: bool,
+    ) -> Result<(), ReadWriteError<R::Error, W::Error>> {
+        let bytes = row.buffer.as_slice();
+
+        match language {
+            SourceLanguage::C | SourceLanguage::RustArray => {
+                self.formatter.push_slice(match language {
+                    SourceLanguage::C => b"  ",
+                    _ => b"    ",
+                });
+                for (i, b) in bytes.iter().enumerate() {
+                    if i > 0 {
+                        self.formatter.push_slice(b", ");
+                    }
+                    self.formatter.push_slice(b"0x");
+                    self.formatter.push_slice(&self.formatter.u8_to_hex(*b));
+                }
+                // C initializers written by xxd have no trailing comma
+                if !last || language == SourceLanguage::RustArray {
+                    self.formatter.push_slice(b",");
+                }
+            }
+            SourceLanguage::RustBytes => {
+                self.formatter.push_slice(b"    ");
+                self.push_escaped_bytes(bytes);
+                self.formatter.push_slice(if last { b"\";" } else { b"\\" });
+            }
+            SourceLanguage::PythonBytes => {
+                self.formatter.push_slice(b"    b\"");
+                self.push_escaped_bytes(bytes);
+                self.formatter.push_slice(b"\"");
+            }
+        }
+        self.flush_line()
+    }
+
+    /// Write each byte as a `\xHH` escape. Printable bytes are escaped too,
+    /// since a Rust string continuation skips leading whitespace.
+    fn push_escaped_bytes(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.formatter.push_slice(b"\\x");
+            self.formatter.push_slice(&self.formatter.u8_to_hex(*b));
+        }
+    }
+}
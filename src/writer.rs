@@ -1,4 +1,7 @@
-use std::{convert::Infallible, fmt::Debug, io::BufWriter};
+use alloc::{string::String, vec::Vec};
+use core::{convert::Infallible, fmt, fmt::Debug};
+#[cfg(feature = "std")]
+use std::io::BufWriter;
 
 pub trait WriteHexdump: Sized {
     type Error: Debug;
@@ -50,9 +53,11 @@ pub trait WriteHexdump: Sized {
     fn consume(r: Result<Self, Self::Error>) -> Self::Output;
 }
 
+#[cfg(feature = "std")]
 #[doc(hidden)]
 pub struct IOWriter<W: std::io::Write>(pub W);
 
+#[cfg(feature = "std")]
 impl<W: std::io::Write> IOWriter<W> {
     pub fn new(w: W) -> IOWriter<BufWriter<W>> {
         IOWriter(BufWriter::new(w))
@@ -62,6 +67,7 @@ impl<W: std::io::Write> IOWriter<W> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<W: std::io::Write> WriteHexdump for IOWriter<W> {
     type Error = std::io::Error;
     type Output = Result<(), std::io::Error>;
@@ -73,6 +79,35 @@ impl<W: std::io::Write> WriteHexdump for IOWriter<W> {
     }
 }
 
+/// Writes a hexdump to any [`core::fmt::Write`] implementation, such as a
+/// serial port driver on a `no_std` target. The output is the inner writer,
+/// or the first error it returned.
+///
+/// ```
+/// use hxd::{AsHexd, options::{HexdOptionsBuilder, Spacing}, writer::FmtWriter};
+///
+/// let out = b"uart".hexd()
+///     .ungrouped(4, Spacing::Normal)
+///     .dump_into(FmtWriter(String::new()))
+///     .unwrap();
+/// assert_eq!(out, "00000000: 75 61 72 74 |uart|\n");
+/// ```
+#[derive(Debug, Default)]
+pub struct FmtWriter<W: fmt::Write>(pub W);
+
+impl<W: fmt::Write> WriteHexdump for FmtWriter<W> {
+    type Error = fmt::Error;
+    type Output = Result<W, fmt::Error>;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.0.write_str(s)
+    }
+
+    fn consume(r: Result<Self, Self::Error>) -> Self::Output {
+        r.map(|w| w.0)
+    }
+}
+
 impl WriteHexdump for String {
     type Error = Infallible;
     type Output = String;
@@ -91,7 +126,7 @@ impl WriteHexdump for Vec<String> {
     type Output = Vec<String>;
 
     fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
-        let last = if self.len() > 0 {
+        let last = if !self.is_empty() {
             self.last_mut().unwrap()
         } else {
             self.push(String::new());
@@ -132,7 +167,7 @@ impl WriteHexdump for Vec<Vec<u8>> {
     type Output = Vec<Vec<u8>>;
 
     fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
-        let last = if self.len() > 0 {
+        let last = if !self.is_empty() {
             self.last_mut().unwrap()
         } else {
             self.push(Vec::new());
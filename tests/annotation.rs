@@ -0,0 +1,199 @@
+use hxd::{
+    annotation::{Annotation, Highlight},
+    options::{Color, HexdOptionsBuilder, Spacing, Style},
+    AsHexd,
+};
+use indoc::indoc;
+
+#[test]
+fn underline_and_legend() {
+    // Given
+    let v = b"\x00\x04ping";
+
+    // When
+    let dump = v
+        .hexd()
+        .ungrouped(8, Spacing::Normal)
+        .annotate(0..2, "length", Highlight::Underline)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 00 04 70 69 6E 67       |..ping  |  a: length
+                      aaaaa                    aa
+        "},
+        dump
+    );
+}
+
+#[test]
+fn bracket_across_rows() {
+    // Given
+    let v = (0..24).collect::<Vec<u8>>();
+
+    // When
+    let dump = v
+        .hexd()
+        .ungrouped(8, Spacing::Normal)
+        .annotate(6..=9, "flags", Highlight::Bracket)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 00 01 02 03 04 05 06 07 |........|  a: flags
+                                        [aaaa        [a
+            00000008: 08 09 0A 0B 0C 0D 0E 0F |........|
+                      aaaa]                    a]
+            00000010: 10 11 12 13 14 15 16 17 |........|
+        "},
+        dump
+    );
+}
+
+#[test]
+fn overlapping_ranges_use_separate_lines() {
+    // Given
+    let v = b"GET /index.html";
+
+    // When
+    let dump = v
+        .hexd()
+        .annotate(0..15, "request line", Highlight::Bracket)
+        .annotate(4..15, "path", Highlight::Underline)
+        .annotate(0..3, "method", Highlight::Underline)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 4745 5420 2F69 6E64 6578 2E68 746D 6C   |GET /index.html |  a: request line, b: path, c: method
+                      [aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa]    [aaaaaaaaaaaaa]
+                      ccccccc   bbbbbbbbbbbbbbbbbbbbbbbbbbb    ccc bbbbbbbbbbb
+        "},
+        dump
+    );
+}
+
+#[test]
+fn colored_ranges() {
+    // Given
+    let style = Style::plain().fg(Color::Green);
+
+    // When
+    let dump = b"key=val"
+        .hexd()
+        .ungrouped(8, Spacing::Normal)
+        .annotate(4.., "value", Highlight::Color(style))
+        .dump_to::<String>();
+
+    // Then
+    assert_eq!(
+        dump,
+        concat!(
+            "00000000: 6B 65 79 3D \x1b[32m76\x1b[0m \x1b[32m61\x1b[0m \x1b[32m6C\x1b[0m    ",
+            "|key=\x1b[32mv\x1b[0m\x1b[32ma\x1b[0m\x1b[32ml\x1b[0m |  \x1b[32mvalue\x1b[0m\n",
+        )
+    );
+}
+
+#[test]
+fn annotated_rows_are_not_elided() {
+    // Given
+    let v = vec![0u8; 96];
+
+    // When
+    let dump = v
+        .hexd()
+        .annotate(0x30..0x32, "marker", Highlight::Underline)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+            *
+            00000020: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+            00000030: 0000 0000 0000 0000 0000 0000 0000 0000 |................|  a: marker
+                      aaaa                                     aa
+            *
+            00000050: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+        "},
+        dump
+    );
+}
+
+#[test]
+fn ranges_starting_before_the_print_range() {
+    // Given
+    let v = (0x40..0x60).collect::<Vec<u8>>();
+
+    // When
+    let dump = v
+        .hexd()
+        .range(0x10..)
+        .annotations([
+            Annotation::new(0x08..0x12, "header", Highlight::Bracket),
+            Annotation::new(0x30.., "out of range", Highlight::Underline),
+        ])
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000010: 5051 5253 5455 5657 5859 5A5B 5C5D 5E5F |PQRSTUVWXYZ[\\]^_|  a: header
+                      aaa]                                     a]
+        "},
+        dump
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn fallible_hexd() {
+    use hxd::IntoFallibleHexd;
+
+    let input = std::io::Cursor::new(b"\x7fELF\x02\x01".to_vec());
+
+    let dump = input
+        .hexd()
+        .ungrouped(6, Spacing::Normal)
+        .annotate(..4, "magic", Highlight::Underline)
+        .annotate(4..5, "class", Highlight::Underline)
+        .dump_to::<String>()
+        .unwrap();
+
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 7F 45 4C 46 02 01 |.ELF..|  a: magic, b: class
+                      aaaaaaaaaaa bb     aaaab
+        "},
+        dump
+    );
+}
+
+#[test]
+fn keys_continue_with_two_letters() {
+    // Given
+    let v = b"\x01\x02\x03\x04";
+    let unused = (0..52).map(|i| Annotation::new(100 + i..101 + i, "unused", Highlight::Underline));
+
+    // When
+    let dump = v
+        .hexd()
+        .ungrouped(4, Spacing::Normal)
+        .annotations(unused)
+        .annotate(0..1, "first", Highlight::Bracket)
+        .annotate(1..4, "rest", Highlight::Underline)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 01 02 03 04 |....|  aa: first, ab: rest
+                      [] abababab  aaba
+        "},
+        dump
+    );
+}
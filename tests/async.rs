@@ -0,0 +1,255 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    convert::Infallible,
+    future::Future,
+    pin::{pin, Pin},
+    rc::Rc,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use hxd::{
+    annotation::Highlight,
+    asynchronous::IntoAsyncHexd,
+    options::{HexdOptionsBuilder, SourceFormat, SourceLanguage, Spacing, TextEncoding},
+    reader::AsyncReadBytes,
+    writer::WriteHexdump,
+    AsHexd, IntoHexd,
+};
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+}
+
+/// Poll `future` until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// A stream which is only ready on every other poll, and
+/// then returns at most `chunk` bytes.
+struct Trickle {
+    bytes: Vec<u8>,
+    pos: usize,
+    chunk: usize,
+    ready: bool,
+}
+
+impl Trickle {
+    fn new(bytes: &[u8], chunk: usize) -> Self {
+        Self {
+            bytes: bytes.to_vec(),
+            pos: 0,
+            chunk,
+            ready: false,
+        }
+    }
+}
+
+impl AsyncReadBytes for Trickle {
+    type Error = Infallible;
+
+    fn poll_next_n(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        self.ready = !self.ready;
+        if !self.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let n = buf.len().min(self.chunk).min(self.bytes.len() - self.pos);
+        buf[..n].copy_from_slice(&self.bytes[self.pos..self.pos + n]);
+        self.pos += n;
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[test]
+fn matches_sync_dumps() {
+    let bytes = [
+        b"header\x00\x01".to_vec(),
+        vec![0u8; 64],
+        "caf\u{e9} \u{1F600} ".repeat(5).into_bytes(),
+    ]
+    .concat();
+
+    let sync = bytes.hexd().dump_to::<String>();
+    let dump = block_on(Trickle::new(&bytes, 3).async_hexd().dump_to::<String>()).unwrap();
+    similar_asserts::assert_eq!(sync, dump);
+
+    let sync = bytes
+        .hexd()
+        .range(5..110)
+        .ungrouped(10, Spacing::Normal)
+        .encoding(TextEncoding::Utf8)
+        .annotate(7..9, "flags", Highlight::Bracket)
+        .dump_to::<String>();
+    let dump = Trickle::new(&bytes, 7)
+        .async_hexd()
+        .range(5..110)
+        .ungrouped(10, Spacing::Normal)
+        .encoding(TextEncoding::Utf8)
+        .annotate(7..9, "flags", Highlight::Bracket)
+        .dump_to::<String>();
+    similar_asserts::assert_eq!(sync, block_on(dump).unwrap());
+
+    let source = SourceFormat::new(SourceLanguage::C, "data");
+    let sync = bytes.hexd().format(source.clone()).dump_to::<String>();
+    let dump = Trickle::new(&bytes, 5)
+        .async_hexd()
+        .format(source)
+        .dump_to::<String>();
+    similar_asserts::assert_eq!(sync, block_on(dump).unwrap());
+
+    let sync = bytes.hexd().plain(Some(30)).dump_to::<String>();
+    let dump = Trickle::new(&bytes, 11)
+        .async_hexd()
+        .plain(Some(30))
+        .dump_to::<String>();
+    similar_asserts::assert_eq!(sync, block_on(dump).unwrap());
+}
+
+#[test]
+fn empty_stream() {
+    let stream: &[u8] = &[];
+
+    let dump = block_on(stream.async_hexd().dump_to::<String>()).unwrap();
+
+    assert_eq!(dump, [].iter().copied().into_hexd().dump_to::<String>());
+}
+
+/// A stream fed by the test, which is pending until bytes are
+/// sent or the stream is closed.
+#[derive(Clone, Default)]
+struct Channel(Rc<RefCell<(VecDeque<u8>, bool)>>);
+
+impl Channel {
+    fn send(&self, bytes: &[u8]) {
+        self.0.borrow_mut().0.extend(bytes);
+    }
+
+    fn close(&self) {
+        self.0.borrow_mut().1 = true;
+    }
+}
+
+impl AsyncReadBytes for Channel {
+    type Error = Infallible;
+
+    fn poll_next_n(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        let (queue, closed) = &mut *self.0.borrow_mut();
+        if queue.is_empty() && !*closed {
+            return Poll::Pending;
+        }
+        let n = buf.len().min(queue.len());
+        for (b, q) in buf.iter_mut().zip(queue.drain(..n)) {
+            *b = q;
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+/// A writer which shares the lines written so far with the test.
+#[derive(Clone, Default)]
+struct Lines(Rc<RefCell<Vec<String>>>);
+
+impl WriteHexdump for Lines {
+    type Error = Infallible;
+    type Output = ();
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        let mut lines = self.0.borrow_mut();
+        match lines.last_mut() {
+            Some(line) if !line.ends_with('\n') => line.push_str(s),
+            _ => lines.push(s.to_string()),
+        }
+        Ok(())
+    }
+
+    fn consume(_: Result<Self, Self::Error>) -> Self::Output {}
+}
+
+#[test]
+fn lines_are_written_as_bytes_arrive() {
+    // Given
+    let channel = Channel::default();
+    let lines = Lines::default();
+    let mut dump = pin!(channel
+        .clone()
+        .async_hexd()
+        .ungrouped(4, Spacing::Normal)
+        .dump_into(lines.clone()));
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    // When
+    channel.send(b"abcdef");
+    let first = dump.as_mut().poll(&mut cx);
+    let written = lines.0.borrow().clone();
+
+    channel.send(b"ghijk");
+    let second = dump.as_mut().poll(&mut cx);
+
+    channel.close();
+    let last = dump.as_mut().poll(&mut cx);
+
+    // Then
+    assert!(first.is_pending());
+    assert_eq!(written, vec!["00000000: 61 62 63 64 |abcd|\n"]);
+    assert!(second.is_pending());
+    assert!(matches!(last, Poll::Ready(Ok(()))));
+    assert_eq!(
+        *lines.0.borrow(),
+        vec![
+            "00000000: 61 62 63 64 |abcd|\n",
+            "00000004: 65 66 67 68 |efgh|\n",
+            "00000008: 69 6A 6B    |ijk |\n",
+        ]
+    );
+}
+
+/// A stream which fails after its bytes have been read.
+struct Failing(&'static [u8]);
+
+impl AsyncReadBytes for Failing {
+    type Error = &'static str;
+
+    fn poll_next_n(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        if self.0.is_empty() {
+            return Poll::Ready(Err("connection reset"));
+        }
+        let n = buf.len().min(self.0.len());
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[test]
+fn read_errors() {
+    let dump = block_on(Failing(b"partial").async_hexd().dump_to::<String>());
+
+    assert_eq!(dump, Err("connection reset"));
+}
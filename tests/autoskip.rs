@@ -4,11 +4,12 @@ use indoc::indoc;
 pub mod common;
 
 use hxd::options::{
-    GroupSize, Grouping, HexdOptions, HexdOptionsBuilder, HexdRange, IndexOffset, Spacing,
+    ElisionMarker, ElisionMode, GroupSize, Grouping, HexdOptions, HexdOptionsBuilder, HexdRange,
+    IndexOffset, Spacing,
 };
 
 fn default_test_options() -> HexdOptions {
-    let default_options = HexdOptions {
+    HexdOptions {
         base: hxd::options::Base::Hex,
         autoskip: true,
         uppercase: true,
@@ -21,8 +22,8 @@ fn default_test_options() -> HexdOptions {
             limit: None,
         },
         index_offset: IndexOffset::Relative(0),
-    };
-    default_options
+        ..HexdOptions::default()
+    }
 }
 
 fn autoskip_test_options() -> HexdOptions {
@@ -34,9 +35,7 @@ byte_tests! {
         input: ByteSequence::new(vec![
             (0u8, 4)
         ]),
-        output: concat!(
-            "00000000: 0000 0000 |....|\n",
-        ),
+        output: "00000000: 0000 0000 |....|\n",
         options: autoskip_test_options()
     },
     two_equal_lines_are_not_autoskipped: RenderTestCase {
@@ -143,6 +142,124 @@ byte_tests! {
         "},
         options: autoskip_test_options()
     },
+    uniform_mode_does_not_autoskip_mixed_groups: RenderTestCase {
+        input: ByteSequence::new([(0xdeu8, 1), (0xad, 1), (0xbe, 1), (0xef, 1)].repeat(3)),
+        output: indoc! {"
+            00000000: DEAD BEEF |....|
+            00000004: DEAD BEEF |....|
+            00000008: DEAD BEEF |....|
+        "},
+        options: autoskip_test_options()
+    },
+    repeated_mode_autoskips_mixed_groups: RenderTestCase {
+        input: ByteSequence::new([(0xdeu8, 1), (0xad, 1), (0xbe, 1), (0xef, 1)].repeat(3)),
+        output: indoc! {"
+            00000000: DEAD BEEF |....|
+            *
+            00000008: DEAD BEEF |....|
+        "},
+        options: autoskip_test_options()
+            .elision_mode(ElisionMode::Repeated)
+    },
+    repeated_mode_autoskips_repeated_structs: RenderTestCase {
+        input: ByteSequence::new([(1u8, 2), (0, 2), (0x41, 1), (0x42, 3)].repeat(8)),
+        output: indoc! {"
+            00000000: 0101 0000 4142 4242 0101 0000 4142 4242 |....ABBB....ABBB|
+            *
+            00000030: 0101 0000 4142 4242 0101 0000 4142 4242 |....ABBB....ABBB|
+        "},
+        options: default_test_options()
+            .elision_mode(ElisionMode::Repeated)
+    },
+    repeated_mode_stops_at_a_different_row: RenderTestCase {
+        input: ByteSequence::new(vec![
+            (1u8, 1), (2, 3),
+            (1, 1), (2, 3),
+            (1, 1), (2, 3),
+            (1, 1), (3, 3),
+        ]),
+        output: indoc! {"
+            00000000: 0102 0202 |....|
+            *
+            00000008: 0102 0202 |....|
+            0000000C: 0103 0303 |....|
+        "},
+        options: autoskip_test_options()
+            .elision_mode(ElisionMode::Repeated)
+    },
+    short_runs_are_not_autoskipped: RenderTestCase {
+        input: ByteSequence::new(vec![
+            (0u8, 16),
+            (2u8, 20)
+        ]),
+        output: indoc! {"
+            00000000: 0000 0000 |....|
+            00000004: 0000 0000 |....|
+            00000008: 0000 0000 |....|
+            0000000C: 0000 0000 |....|
+            00000010: 0202 0202 |....|
+            *
+            00000020: 0202 0202 |....|
+        "},
+        options: autoskip_test_options()
+            .min_elided_rows(3)
+    },
+    zero_min_elided_rows_is_one: RenderTestCase {
+        input: ByteSequence::new(vec![
+            (0u8, 12)
+        ]),
+        output: indoc! {"
+            00000000: 0000 0000 |....|
+            *
+            00000008: 0000 0000 |....|
+        "},
+        options: autoskip_test_options()
+            .min_elided_rows(0)
+    },
+    counted_marker: RenderTestCase {
+        input: ByteSequence::new(vec![
+            (0u8, 12),
+            (1u8, 16)
+        ]),
+        output: indoc! {"
+            00000000: 0000 0000 |....|
+            * (1 row, 0x4 bytes identical)
+            00000008: 0000 0000 |....|
+            0000000C: 0101 0101 |....|
+            * (2 rows, 0x8 bytes identical)
+            00000018: 0101 0101 |....|
+        "},
+        options: autoskip_test_options()
+            .elision_marker(ElisionMarker::Counted)
+    },
+    counted_marker_follows_case: RenderTestCase {
+        input: ByteSequence::new(vec![
+            (0u8, 0x1000)
+        ]),
+        output: indoc! {"
+            00000000: 0000 0000 |....|
+            * (1022 rows, 0xff8 bytes identical)
+            00000ffc: 0000 0000 |....|
+        "},
+        options: autoskip_test_options()
+            .uppercase(false)
+            .elision_marker(ElisionMarker::Counted)
+    },
+    template_marker: RenderTestCase {
+        input: ByteSequence::new(vec![
+            (0u8, 20)
+        ]),
+        output: indoc! {"
+            00000100: 0000 0000 |....|
+            {row} 3 rows, 12 bytes from 00000104 to 00000110 {rows
+            00000110: 0000 0000 |....|
+        "},
+        options: autoskip_test_options()
+            .relative_offset(0x100)
+            .elision_marker(ElisionMarker::Template(
+                "{row} {rows} rows, {bytes} bytes from {start} to {end} {rows".into()
+            ))
+    },
 }
 
 byte_tests! {
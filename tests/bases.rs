@@ -4,7 +4,7 @@ use indoc::indoc;
 mod common;
 
 fn default_test_options() -> HexdOptions {
-    let default_options = HexdOptions {
+    HexdOptions {
         base: hxd::options::Base::Hex,
         autoskip: true,
         uppercase: true,
@@ -17,8 +17,8 @@ fn default_test_options() -> HexdOptions {
             limit: None,
         },
         index_offset: IndexOffset::Relative(0),
-    };
-    default_options
+        ..HexdOptions::default()
+    }
 }
 
 byte_tests! {
@@ -0,0 +1,301 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+use hxd::{
+    elf::Elf,
+    options::{Checksum, GroupSize, HexdOptionsBuilder},
+    AsHexd,
+};
+use indoc::indoc;
+
+fn hxd(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hxd"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("could not run hxd");
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(args: &[&str], stdin: &[u8]) -> String {
+    let output = hxd(args, stdin);
+    assert!(
+        output.status.success(),
+        "hxd {:?} failed: {:?}",
+        args,
+        output
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+const TEXT: &[u8] =
+    b"The Nellie, a cruising yawl, swung to her anchor without a flutter of the sails.";
+
+#[test]
+fn matches_library_defaults() {
+    let input = [TEXT, &[0u8; 64]].concat();
+
+    let expected = input.hexd().dump_to::<String>();
+
+    similar_asserts::assert_eq!(expected, stdout(&["-u", "-a"], &input));
+    similar_asserts::assert_eq!(expected, stdout(&["-ua"], &input));
+}
+
+#[test]
+fn xxd_style_defaults() {
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 5468 6520 4e65 6c6c 6965 2c20 6120 6372 |The Nellie, a cr|
+            00000010: 7569 7369 6e67                          |uising          |
+        "},
+        stdout(&[], &TEXT[..22])
+    );
+}
+
+#[test]
+fn skip_length_columns_and_groups() {
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000:          4e656c6c 69652c20 |    Nellie, |
+            0000000c: 61206372 75697369 6e67     |a cruising  |
+        "},
+        stdout(&["-s", "4", "-l0x12", "-c", "12", "-g", "4"], TEXT)
+    );
+}
+
+#[test]
+fn bases() {
+    similar_asserts::assert_eq!(
+        "00000000: 01010100 01101000 01100101 00100000 |The |\n",
+        stdout(&["-b", "-l", "4"], TEXT)
+    );
+    similar_asserts::assert_eq!(
+        "00000000: 124 150 145 040 |The |\n",
+        stdout(&["-o", "-l", "4", "-c", "4"], TEXT)
+    );
+    similar_asserts::assert_eq!(
+        "00000000:  84 104 101  32 |The |\n",
+        stdout(&["--decimal", "--length=4", "--cols=4"], TEXT)
+    );
+}
+
+#[test]
+fn offsets() {
+    let expected = TEXT
+        .hexd()
+        .grouped_by(GroupSize::Short, 8)
+        .uppercase(false)
+        .autoskip(false)
+        .range(4..20)
+        .absolute_offset(0x1000)
+        .dump_to::<String>();
+
+    similar_asserts::assert_eq!(expected, stdout(&["-s4", "-l16", "-A", "0x1000"], TEXT));
+}
+
+#[test]
+fn color() {
+    let expected = TEXT.hexd().color(true).dump_to::<String>();
+
+    similar_asserts::assert_eq!(expected, stdout(&["-ua", "-R", "always"], TEXT));
+    assert!(!stdout(&["--color=auto"], TEXT).contains('\x1b'));
+}
+
+#[test]
+fn source_output() {
+    similar_asserts::assert_eq!(
+        indoc! {"
+            unsigned char data[] = {
+              0x54, 0x68, 0x65, 0x20, 0x4e, 0x65, 0x6c, 0x6c, 0x69, 0x65, 0x2c, 0x20,
+              0x61
+            };
+            unsigned int data_len = 13;
+        "},
+        stdout(&["-i", "-l", "13"], TEXT)
+    );
+    similar_asserts::assert_eq!(
+        indoc! {"
+            const NELLIE: [u8; NELLIE_LEN] = [
+                0x54, 0x68, 0x65, 0x20,
+            ];
+            const NELLIE_LEN: usize = 4;
+        "},
+        stdout(
+            &["--source=rust", "-n", "NELLIE", "-c", "4", "-l", "4"],
+            TEXT
+        )
+    );
+}
+
+#[test]
+fn plain_output() {
+    similar_asserts::assert_eq!(
+        indoc! {"
+            546865204e656c6c69652c2061206372756973696e67207961776c2c2073
+            77756e6720746f2068657220616e63686f7220776974686f757420612066
+            6c7574746572206f6620746865207361696c732e
+        "},
+        stdout(&["-p"], TEXT)
+    );
+    similar_asserts::assert_eq!(
+        "546865204e656c6c69652c\n",
+        stdout(&["-p", "-c", "0", "-l", "11"], TEXT)
+    );
+
+    let output = hxd(&["-r", "-p"], stdout(&["-p", "-c", "7"], TEXT).as_bytes());
+    assert_eq!(TEXT, output.stdout);
+}
+
+#[test]
+fn encodings() {
+    assert_eq!(
+        "00000000: c885 9393 96                            |Hello           |\n",
+        stdout(&["-E"], b"\xc8\x85\x93\x93\x96")
+    );
+    assert_eq!(
+        "00000000: 4772 c3bc c39f 65                       |Grü·ß·e         |\n",
+        stdout(&["--encoding=utf8"], "Grüße".as_bytes())
+    );
+}
+
+#[test]
+fn reverse() {
+    let input = [TEXT, &[0u8; 64], &[0xffu8; 3]].concat();
+
+    let dump = stdout(&["-a", "-g", "4"], &input);
+    let output = hxd(&["-r", "-a", "-g", "4"], dump.as_bytes());
+
+    assert!(output.status.success());
+    assert_eq!(input, output.stdout);
+}
+
+#[test]
+fn elision_options() {
+    let input = [&b"abcd".repeat(8)[..], TEXT].concat();
+
+    let dump = stdout(
+        &[
+            "-a", "-c", "4", "--elide", "repeated", "--marker", "counted",
+        ],
+        &input,
+    );
+    let output = hxd(&["-r", "-c", "4", "--marker", "counted"], dump.as_bytes());
+
+    assert_eq!(
+        dump.lines().take(3).collect::<Vec<_>>(),
+        [
+            "00000000: 6162 6364 |abcd|",
+            "* (6 rows, 0x18 bytes identical)",
+            "0000001c: 6162 6364 |abcd|",
+        ]
+    );
+    assert_eq!(input, output.stdout);
+
+    let dump = stdout(
+        &["-a", "-c", "4", "--elide", "repeated", "--min-elided", "7"],
+        &input,
+    );
+    assert_eq!(dump.lines().count(), input.len().div_ceil(4));
+}
+
+#[test]
+fn index_options() {
+    let dump = stdout(
+        &["-c", "8", "--index-base", "decimal", "--index-width", "4"],
+        &TEXT[..20],
+    );
+    let output = hxd(
+        &["-r", "-c", "8", "--index-base=decimal", "--index-width=4"],
+        dump.as_bytes(),
+    );
+
+    assert!(dump.starts_with("0000: "));
+    assert!(dump.lines().nth(2).unwrap().starts_with("0016: "));
+    assert_eq!(&TEXT[..20], output.stdout);
+}
+
+#[test]
+fn header() {
+    let dump = stdout(&["-c", "4", "--header-every", "2"], &TEXT[..12]);
+    let output = hxd(&["-r", "-c", "4", "--header"], dump.as_bytes());
+
+    let lines = dump.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0], "          0001 0203  0123");
+    assert_eq!(lines[3], lines[0]);
+    assert_eq!(&TEXT[..12], output.stdout);
+}
+
+#[test]
+fn footer() {
+    let dump = stdout(&["--checksum", "crc32"], TEXT);
+    let output = hxd(&["-r", "--footer"], dump.as_bytes());
+
+    let footer = dump.lines().last().unwrap();
+    assert!(footer.starts_with(&format!("total: {} bytes, range: 00000000-", TEXT.len())));
+    assert!(footer.ends_with(&format!(", crc32: {:08x}", Checksum::Crc32.of(TEXT))));
+    assert_eq!(TEXT, output.stdout);
+}
+
+#[test]
+fn files_are_skipped_by_seeking() {
+    let path = std::env::temp_dir().join(format!("hxd-cli-seek-{}", std::process::id()));
+    std::fs::write(&path, TEXT).unwrap();
+    let path = path.to_str().unwrap();
+
+    let dump = stdout(&["-u", "-a", "-s", "76", path], b"");
+    let past_end = stdout(&["-s", "0x100000000", "--footer", path], b"");
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(TEXT.hexd().range(76..).dump_to::<String>(), dump);
+    assert_eq!(
+        "total: 0 bytes, range: 0100000000-0100000000, elided: 0 rows (0 bytes)\n",
+        past_end
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn elf_sections() {
+    let path = env!("CARGO_BIN_EXE_hxd");
+    let data = std::fs::read(path).unwrap();
+    let elf = Elf::parse(&data).unwrap();
+
+    let list = stdout(&["--list-sections", path], b"");
+    let dump = stdout(&["-u", "-a", "--section", ".interp", path], b"");
+    let missing = hxd(&["--section", ".nope", path], b"");
+
+    let interp = elf.section(".interp").unwrap();
+    assert!(list.contains(&format!("{interp}\n")));
+    assert!(list.lines().any(|l| l.starts_with("segment LOAD (R-X): ")));
+    assert_eq!(
+        format!("{interp}\n") + &elf.section_hexd(".interp").unwrap().dump_to::<String>(),
+        dump
+    );
+    assert_eq!(Some(1), missing.status.code());
+}
+
+#[test]
+fn missing_file_fails() {
+    let output = hxd(&["/this/file/does/not/exist"], b"");
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn invalid_arguments_fail() {
+    assert_eq!(Some(2), hxd(&["--frobnicate"], b"").status.code());
+    assert_eq!(Some(2), hxd(&["-c", "12", "-g", "8"], b"").status.code());
+    assert_eq!(Some(2), hxd(&["-s", "lots"], b"").status.code());
+    assert_eq!(Some(2), hxd(&["--color=sometimes"], b"").status.code());
+    assert_eq!(Some(2), hxd(&["--source", "cobol"], b"").status.code());
+    assert_eq!(Some(2), hxd(&["--encoding", "utf16"], b"").status.code());
+    assert_eq!(Some(2), hxd(&["-c", "100000"], b"").status.code());
+    assert_eq!(Some(2), hxd(&["--index-width", "0"], b"").status.code());
+}
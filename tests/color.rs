@@ -0,0 +1,141 @@
+use hxd::{
+    options::{ByteClass, Color, HexdOptionsBuilder, Palette, Spacing, Style},
+    AsHexd,
+};
+
+const RESET: &str = "\x1b[0m";
+
+/// Remove all ANSI SGR escape sequences from a string.
+fn strip_sgr(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[test]
+fn byte_classes() {
+    assert_eq!(ByteClass::of(0x00), ByteClass::Null);
+    assert_eq!(ByteClass::of(b'a'), ByteClass::Printable);
+    assert_eq!(ByteClass::of(b'~'), ByteClass::Printable);
+    assert_eq!(ByteClass::of(b' '), ByteClass::Whitespace);
+    assert_eq!(ByteClass::of(b'\t'), ByteClass::Whitespace);
+    assert_eq!(ByteClass::of(b'\r'), ByteClass::Whitespace);
+    assert_eq!(ByteClass::of(0x07), ByteClass::Control);
+    assert_eq!(ByteClass::of(0x7f), ByteClass::Control);
+    assert_eq!(ByteClass::of(0x80), ByteClass::High);
+    assert_eq!(ByteClass::of(0xff), ByteClass::High);
+}
+
+#[test]
+fn default_palette_styles_each_class() {
+    // Given
+    let input = [0x00u8, b'A', b' ', 0x07, 0xff];
+
+    // When
+    let dump = input
+        .hexd()
+        .ungrouped(5, Spacing::Normal)
+        .color(true)
+        .dump_to::<String>();
+
+    // Then
+    let expected = [
+        format!("\x1b[2m00000000{RESET}: "),
+        format!("\x1b[90m00{RESET} "),
+        format!("\x1b[36m41{RESET} "),
+        format!("\x1b[32m20{RESET} "),
+        format!("\x1b[35m07{RESET} "),
+        format!("\x1b[33mFF{RESET} "),
+        "|".to_string(),
+        format!("\x1b[90m.{RESET}"),
+        format!("\x1b[36mA{RESET}"),
+        format!("\x1b[32m {RESET}"),
+        format!("\x1b[35m.{RESET}"),
+        format!("\x1b[33m.{RESET}"),
+        "|\n".to_string(),
+    ]
+    .concat();
+    similar_asserts::assert_eq!(expected, dump);
+}
+
+#[test]
+fn custom_palette() {
+    // Given
+    let palette = Palette {
+        printable: Style::plain()
+            .fg(Color::Fixed(208))
+            .bg(Color::BrightBlue)
+            .bold(),
+        elision: Style::plain().reverse().underline(),
+        ..Palette::plain()
+    };
+    let input = [[b'z'; 16], [0u8; 16], [0u8; 16], [0u8; 16]].concat();
+
+    // When
+    let dump = input.hexd().palette(palette).dump_to::<Vec<String>>();
+
+    // Then
+    let z = "\x1b[1;38;5;208;104m";
+    assert_eq!(
+        dump[0],
+        format!(
+            "00000000: {}|{}|\n",
+            format!("{z}7A{RESET}{z}7A{RESET} ").repeat(8),
+            format!("{z}z{RESET}").repeat(16)
+        )
+    );
+    assert_eq!(dump[2], format!("\x1b[4;7m*{RESET}\n"));
+}
+
+#[test]
+fn uncolored_dump_has_no_escapes() {
+    let input = (0..=255u8).collect::<Vec<u8>>();
+
+    let dump = input.hexd().dump_to::<String>();
+
+    assert!(!dump.contains('\x1b'));
+}
+
+#[test]
+fn colored_dump_only_differs_by_escapes() {
+    let input = (0..=255u8).collect::<Vec<u8>>();
+
+    let plain = input.hexd().autoskip(false).dump_to::<String>();
+    let colored = input.hexd().autoskip(false).color(true).dump_to::<String>();
+
+    similar_asserts::assert_eq!(plain, strip_sgr(&colored));
+}
+
+#[test]
+fn wide_colored_rows_are_written() {
+    // Given
+    let input = (0..=255u8).collect::<Vec<u8>>();
+
+    // When
+    let dump = input
+        .hexd()
+        .ungrouped(64, Spacing::Normal)
+        .color(true)
+        .dump_to::<Vec<String>>();
+
+    // Then
+    let plain = input
+        .hexd()
+        .ungrouped(64, Spacing::Normal)
+        .dump_to::<Vec<String>>();
+    assert_eq!(plain.len(), dump.len());
+    for (plain, colored) in plain.iter().zip(dump.iter()) {
+        similar_asserts::assert_eq!(plain, &strip_sgr(colored));
+    }
+}
@@ -121,7 +121,7 @@ macro_rules! byte_tests {
     $(
         #[test]
         fn $name() -> anyhow::Result<()> {
-            crate::common::test_byte_case($value)
+            $crate::common::test_byte_case($value)
         }
     )*
     };
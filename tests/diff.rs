@@ -0,0 +1,247 @@
+use hxd::{
+    diff::{DiffLayout, HexdDiff},
+    options::{ElisionMarker, ElisionMode, HexdOptionsBuilder, Palette, Spacing, Style},
+    reader::ByteSliceReader,
+    AsHexd,
+};
+use indoc::indoc;
+
+#[test]
+fn identical_inputs_are_elided() {
+    // Given
+    let left = vec![0x41u8; 64];
+    let right = left.clone();
+
+    // When
+    let diff = left.hexd().diff(right.hexd()).dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+             00000000: 4141 4141 4141 4141 4141 4141 4141 4141 |AAAAAAAAAAAAAAAA|
+            *
+             00000030: 4141 4141 4141 4141 4141 4141 4141 4141 |AAAAAAAAAAAAAAAA|
+        "},
+        diff
+    );
+}
+
+#[test]
+fn elision_options_apply_to_diffs() {
+    // Given
+    let left = b"0123".repeat(6);
+    let right = left.clone();
+
+    // When
+    let diff = left
+        .hexd()
+        .diff(right.hexd())
+        .ungrouped(4, Spacing::None)
+        .elision_mode(ElisionMode::Repeated)
+        .min_elided_rows(3)
+        .dump_to::<String>();
+    let short = left
+        .hexd()
+        .diff(right.hexd())
+        .ungrouped(4, Spacing::None)
+        .elision_mode(ElisionMode::Repeated)
+        .min_elided_rows(5)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+             00000000: 30313233 |0123|
+            *
+             00000014: 30313233 |0123|
+        "},
+        diff
+    );
+    assert_eq!(short.lines().count(), 6);
+}
+
+#[test]
+fn interleaved_marks_differing_bytes() {
+    // Given
+    let left = [vec![0u8; 48], b"Hello, world!".to_vec()].concat();
+    let mut right = left.clone();
+    right[0x31] = b'E';
+    right[0x3c] = b'?';
+
+    // When
+    let diff = left.hexd().diff(right.hexd()).dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+             00000000: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+            *
+             00000020: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+            -00000030: 4865 6C6C 6F2C 2077 6F72 6C64 21        |Hello, world!   |
+            +00000030: 4845 6C6C 6F2C 2077 6F72 6C64 3F        |HEllo, world?   |
+                         ^^                          ^^          ^          ^
+        "},
+        diff
+    );
+}
+
+#[test]
+fn side_by_side_with_uneven_lengths() {
+    // Given
+    let left = b"abcdefgh";
+    let right = b"abcdEfghijkl";
+
+    // When
+    let diff = left
+        .hexd()
+        .ungrouped(4, Spacing::Normal)
+        .diff(right.hexd())
+        .layout(DiffLayout::SideBySide)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 61 62 63 64 |abcd|   61 62 63 64 |abcd|
+            00000004: 65 66 67 68 |efgh| | 45 66 67 68 |Efgh|
+                      ^^           ^       ^^           ^
+            00000008:                    > 69 6A 6B 6C |ijkl|
+        "},
+        diff
+    );
+}
+
+#[test]
+fn context_only_prints_differing_rows() {
+    // Given
+    let left = (0..0x80u8).collect::<Vec<u8>>();
+    let mut right = left.clone();
+    right[0x12] = 0xff;
+    right[0x75] = 0xff;
+
+    // When
+    let diff = left
+        .hexd()
+        .diff(right.hexd())
+        .context(1)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+             00000000: 0001 0203 0405 0607 0809 0A0B 0C0D 0E0F |................|
+            -00000010: 1011 1213 1415 1617 1819 1A1B 1C1D 1E1F |................|
+            +00000010: 1011 FF13 1415 1617 1819 1A1B 1C1D 1E1F |................|
+                            ^^                                    ^
+             00000020: 2021 2223 2425 2627 2829 2A2B 2C2D 2E2F | !\"#$%&'()*+,-./|
+            *
+             00000060: 6061 6263 6465 6667 6869 6A6B 6C6D 6E6F |`abcdefghijklmno|
+            -00000070: 7071 7273 7475 7677 7879 7A7B 7C7D 7E7F |pqrstuvwxyz{|}~.|
+            +00000070: 7071 7273 74FF 7677 7879 7A7B 7C7D 7E7F |pqrst.vwxyz{|}~.|
+                                   ^^                                ^
+        "},
+        diff
+    );
+}
+
+#[test]
+fn context_elision_markers_count_hidden_rows() {
+    // Given
+    let left = vec![0u8; 0x80];
+    let mut right = left.clone();
+    right[0x30] = 1;
+
+    // When
+    let diff = left
+        .hexd()
+        .diff(right.hexd())
+        .ungrouped(16, Spacing::None)
+        .show_ascii(false)
+        .context(0)
+        .elision_marker(ElisionMarker::Template(
+            "{rows} rows from {start} to {end}".into(),
+        ))
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            3 rows from 00000000 to 00000030
+            -00000030: 00000000000000000000000000000000
+            +00000030: 01000000000000000000000000000000
+                       ^^
+            4 rows from 00000040 to 00000080
+        "},
+        diff
+    );
+}
+
+#[test]
+fn context_without_differences_is_empty() {
+    let v = vec![7u8; 100];
+
+    let diff = v.hexd().diff(v.hexd()).context(3).dump_to::<String>();
+
+    assert_eq!("", diff);
+}
+
+#[test]
+fn changed_bytes_are_colored() {
+    // Given
+    let palette = Palette {
+        changed: Style::plain().reverse(),
+        ..Palette::plain()
+    };
+
+    // When
+    let diff = HexdDiff::new(ByteSliceReader::new(b"ab"), ByteSliceReader::new(b"aB"))
+        .ungrouped(2, Spacing::Normal)
+        .palette(palette)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        concat!(
+            "-00000000: 61 \x1b[7m62\x1b[0m |a\x1b[7mb\x1b[0m|\n",
+            "+00000000: 61 \x1b[7m42\x1b[0m |a\x1b[7mB\x1b[0m|\n",
+            "              ^^   ^\n",
+        ),
+        diff
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn read_errors_name_the_failing_side() {
+    use std::io::ErrorKind;
+
+    use hxd::{diff::DiffReadError, IntoFallibleHexd, ReadWriteError, TryDumpError};
+
+    // Given
+    struct Broken;
+    impl std::io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+    }
+    let data: &[u8] = b"abcd";
+
+    // When
+    let left = Broken.hexd().diff(data.hexd()).try_dump_to::<String>();
+    let right = data.hexd().diff(Broken.hexd()).try_dump_to::<String>();
+    let io = Broken.hexd().diff(Broken.hexd()).dump_io(Vec::new());
+
+    // Then
+    assert!(matches!(
+        left,
+        Err(TryDumpError::Dump(DiffReadError::Left(e))) if e.kind() == ErrorKind::BrokenPipe
+    ));
+    assert!(matches!(
+        right,
+        Err(TryDumpError::Dump(DiffReadError::Right(e))) if e.kind() == ErrorKind::BrokenPipe
+    ));
+    assert!(matches!(
+        io,
+        Err(ReadWriteError::Read(DiffReadError::Left(_)))
+    ));
+}
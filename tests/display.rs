@@ -0,0 +1,84 @@
+use hxd::{
+    options::{HexdOptionsBuilder, Spacing},
+    AsHexd, HexdDebug, IntoHexd,
+};
+use indoc::indoc;
+
+#[test]
+fn display_matches_dump() {
+    // Given
+    let v = (0..40).collect::<Vec<u8>>();
+    let hexd = v.hexd().range(4..).aligned(false);
+
+    // When
+    let first = format!("{hexd}");
+    let second = hexd.to_string();
+
+    // Then
+    assert_eq!(
+        first,
+        v.hexd().range(4..).aligned(false).dump_to::<String>()
+    );
+    assert_eq!(first, second);
+}
+
+#[test]
+fn display_grouped_and_iterator_readers() {
+    use hxd::AsHexdGrouped;
+
+    let ints = [0x1234u16, 0xabcd];
+    assert_eq!(
+        format!("{}", ints.hexd_be().ungrouped(4, Spacing::Normal)),
+        "00000000: 12 34 AB CD |.4..|\n"
+    );
+
+    let bytes = b"xyz".iter().copied().into_hexd();
+    assert_eq!(
+        format!("{}", bytes.ungrouped(3, Spacing::Normal)),
+        "00000000: 78 79 7A |xyz|\n"
+    );
+}
+
+#[test]
+fn debug_preview() {
+    assert_eq!(format!("{:?}", HexdDebug(b"ok")), "6F 6B |ok|");
+    assert_eq!(format!("{:?}", HexdDebug([0u8; 0])), "(0 bytes)");
+    assert_eq!(
+        format!("{:?}", HexdDebug(vec![0x41u8; 16])),
+        "41 41 41 41 41 41 41 41 41 41 41 41 41 41 41 41 |AAAAAAAAAAAAAAAA|"
+    );
+    assert_eq!(
+        format!("{:?}", HexdDebug(vec![0x41u8; 17])),
+        "41 41 41 41 41 41 41 41 41 41 41 41 41 41 41 41 |AAAAAAAAAAAAAAAA| ... (17 bytes)"
+    );
+}
+
+#[test]
+fn debug_alternate_form() {
+    // Given
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Frame<'a> {
+        header: HexdDebug<&'a [u8]>,
+        body: HexdDebug<Vec<u8>>,
+    }
+    let frame = Frame {
+        header: HexdDebug(b"\x01\x02"),
+        body: HexdDebug(vec![0u8; 64]),
+    };
+
+    // When
+    let debug = format!("{frame:#?}");
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            Frame {
+                header: 00000000: 0102                                    |..              |,
+                body: 00000000: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+                *
+                00000030: 0000 0000 0000 0000 0000 0000 0000 0000 |................|,
+            }"},
+        debug
+    );
+}
@@ -0,0 +1,251 @@
+use hxd::{
+    elf::{Elf, ElfClass, ElfError, Section},
+    options::{Endianness, HexdOptions, HexdOptionsBuilder, Spacing},
+};
+use indoc::indoc;
+
+static MARKER: [u8; 24] = *b"hxd elf test marker 1234";
+
+/// Writes the fields of an ELF file in either byte order.
+struct Builder {
+    bytes: Vec<u8>,
+    endianness: Endianness,
+}
+
+impl Builder {
+    fn u8(&mut self, n: u8) -> &mut Self {
+        self.bytes.push(n);
+        self
+    }
+
+    fn u16(&mut self, n: u16) -> &mut Self {
+        match self.endianness {
+            Endianness::BigEndian => self.bytes.extend(n.to_be_bytes()),
+            Endianness::LittleEndian => self.bytes.extend(n.to_le_bytes()),
+        }
+        self
+    }
+
+    fn u32(&mut self, n: u32) -> &mut Self {
+        match self.endianness {
+            Endianness::BigEndian => self.bytes.extend(n.to_be_bytes()),
+            Endianness::LittleEndian => self.bytes.extend(n.to_le_bytes()),
+        }
+        self
+    }
+}
+
+/// Build a 32-bit executable with a `.data`, a `.bss` and a section name
+/// table, and a single segment loading `.data`.
+fn elf32(endianness: Endianness) -> Vec<u8> {
+    let names = b"\0.data\0.bss\0.shstrtab\0";
+    let mut b = Builder {
+        bytes: b"\x7fELF".to_vec(),
+        endianness,
+    };
+    let encoding = match endianness {
+        Endianness::LittleEndian => 1,
+        Endianness::BigEndian => 2,
+    };
+    b.u8(1).u8(encoding).u8(1);
+    b.bytes.resize(16, 0);
+    // type, machine, version, entry, phoff, shoff, flags
+    b.u16(2).u16(3).u32(1).u32(0x8000).u32(52).u32(116).u32(0);
+    // ehsize, phentsize, phnum, shentsize, shnum, shstrndx
+    b.u16(52).u16(32).u16(1).u16(40).u16(4).u16(3);
+
+    // LOAD R-W: offset, vaddr, paddr, filesz, memsz, flags, align
+    b.u32(1)
+        .u32(84)
+        .u32(0x8000)
+        .u32(0x8000)
+        .u32(8)
+        .u32(24)
+        .u32(6)
+        .u32(4);
+
+    b.bytes.extend(b"\xde\xad\xbe\xef\x00\x01\x02\x03");
+    b.bytes.extend(names);
+    b.bytes.resize(116, 0);
+
+    // name, type, flags, addr, offset, size, link, info, addralign, entsize
+    b.bytes.extend([0; 40]);
+    b.u32(1).u32(1).u32(3).u32(0x8000).u32(84).u32(8);
+    b.u32(0).u32(0).u32(4).u32(0);
+    b.u32(7).u32(8).u32(3).u32(0x8008).u32(92).u32(16);
+    b.u32(0).u32(0).u32(4).u32(0);
+    b.u32(12)
+        .u32(3)
+        .u32(0)
+        .u32(0)
+        .u32(92)
+        .u32(names.len() as u32);
+    b.u32(0).u32(0).u32(1).u32(0);
+    b.bytes
+}
+
+#[test]
+fn elf32_files_are_parsed_in_both_byte_orders() {
+    for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+        // Given
+        let data = elf32(endianness);
+
+        // When
+        let elf = Elf::parse(&data).unwrap();
+
+        // Then
+        assert_eq!(ElfClass::Elf32, elf.class());
+        assert_eq!(endianness, elf.endianness());
+        assert_eq!((3, 0x8000), (elf.machine(), elf.entry()));
+        let names: Vec<_> = elf.sections().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(vec!["", ".data", ".bss", ".shstrtab"], names);
+        assert_eq!(
+            &Section {
+                name: ".bss".into(),
+                kind: 8,
+                flags: 3,
+                address: 0x8008,
+                offset: 92,
+                size: 16,
+                alignment: 4,
+            },
+            elf.section(".bss").unwrap()
+        );
+        assert_eq!(
+            b"\xde\xad\xbe\xef\x00\x01\x02\x03",
+            elf.section_data(&elf.sections()[1])
+        );
+        assert!(elf.section_data(elf.section(".bss").unwrap()).is_empty());
+        assert_eq!(1, elf.segments().len());
+        assert_eq!(
+            "segment LOAD (RW-): address 0x8000, offset 0x54, 8 bytes (24 in memory)",
+            elf.segments()[0].to_string()
+        );
+        assert_eq!(
+            elf.section_data(&elf.sections()[1]),
+            elf.segment_data(&elf.segments()[0])
+        );
+    }
+}
+
+#[test]
+fn sections_are_dumped_at_their_address() {
+    // Given
+    let data = elf32(Endianness::BigEndian);
+    let elf = Elf::parse(&data).unwrap();
+
+    // When
+    let dump = elf
+        .section_hexd(".data")
+        .unwrap()
+        .ungrouped(8, Spacing::Normal)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!("00008000: DE AD BE EF 00 01 02 03 |........|\n", dump);
+}
+
+#[test]
+fn each_dumped_section_has_a_header() {
+    // Given
+    let data = elf32(Endianness::LittleEndian);
+    let elf = Elf::parse(&data).unwrap();
+    let options = HexdOptions::default().ungrouped(8, Spacing::None);
+
+    // When
+    let dump = elf
+        .dump_sections_to::<String>(&[".data", ".bss", ".shstrtab"], &options)
+        .unwrap();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            section .data (PROGBITS, WA): address 0x8000, offset 0x54, 8 bytes
+            00008000: DEADBEEF00010203 |........|
+            section .bss (NOBITS, WA): address 0x8008, offset 0x5C, 16 bytes
+            section .shstrtab (STRTAB): address 0x0, offset 0x5C, 22 bytes
+            00000000: 002E64617461002E |..data..|
+            00000008: 627373002E736873 |bss..shs|
+            00000010: 747274616200     |trtab.  |
+        "},
+        dump
+    );
+    assert_eq!(
+        Err(ElfError::NoSuchSection(".text".into())),
+        elf.dump_sections_to::<String>(&[".data", ".text"], &options)
+    );
+}
+
+#[test]
+fn invalid_files_are_rejected() {
+    // Given
+    let data = elf32(Endianness::LittleEndian);
+    let mut wide = data.clone();
+    wide[4] = 3;
+
+    // Then
+    assert_eq!(Some(ElfError::NotElf), Elf::parse(b"MZ\x90\x00").err());
+    assert_eq!(Some(ElfError::UnsupportedClass(3)), Elf::parse(&wide).err());
+    assert_eq!(
+        Some(ElfError::Truncated("ELF header")),
+        Elf::parse(&data[..40]).err()
+    );
+    assert_eq!(
+        Some(ElfError::Truncated("section header")),
+        Elf::parse(&data[..200]).err()
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_binaries_are_parsed() {
+    // Given
+    let data = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+
+    // When
+    let elf = Elf::parse(&data).unwrap();
+
+    // Then
+    let expected_class = if cfg!(target_pointer_width = "64") {
+        ElfClass::Elf64
+    } else {
+        ElfClass::Elf32
+    };
+    assert_eq!(expected_class, elf.class());
+    assert!(elf.segments().iter().any(|s| s.kind_name() == Some("LOAD")));
+    let text = elf.section(".text").unwrap();
+    assert!(text
+        .to_string()
+        .starts_with("section .text (PROGBITS, AX): address 0x"));
+    assert!(elf.segments().iter().any(|s| {
+        s.executable()
+            && (s.virtual_address..s.virtual_address + s.memory_size).contains(&text.address)
+    }));
+
+    // the row with the marker is indexed by its address
+    let rodata = elf.section(".rodata").unwrap();
+    let position = elf
+        .section_data(rodata)
+        .windows(MARKER.len())
+        .position(|w| w == MARKER)
+        .unwrap();
+    let dump = elf
+        .section_hexd(".rodata")
+        .unwrap()
+        .ungrouped(16, Spacing::None)
+        .autoskip(false)
+        .dump_to::<String>();
+    let (index, cells) = dump
+        .lines()
+        .nth(position / 16)
+        .unwrap()
+        .split_once(": ")
+        .unwrap();
+    let marker: String = MARKER.iter().map(|b| format!("{b:02X}")).collect();
+    let column = position % 16;
+    assert_eq!(
+        rodata.address as usize + position - column,
+        usize::from_str_radix(index, 16).unwrap()
+    );
+    assert_eq!(&marker[..32 - 2 * column], &cells[2 * column..32]);
+}
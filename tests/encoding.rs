@@ -0,0 +1,144 @@
+use hxd::{
+    options::{HexdOptionsBuilder, Spacing, TextEncoding},
+    AsHexd, IntoHexd,
+};
+use indoc::indoc;
+
+#[test]
+fn ascii_is_the_default() {
+    let v = b"caf\xe9\x00 ~\x7f";
+
+    let dump = v.hexd().ungrouped(8, Spacing::Normal).dump_to::<String>();
+
+    assert_eq!(dump, "00000000: 63 61 66 E9 00 20 7E 7F |caf.. ~.|\n");
+}
+
+#[test]
+fn latin1() {
+    let v = b"caf\xe9 \xa0\xad\xbf\x85";
+
+    let dump = v
+        .hexd()
+        .ungrouped(9, Spacing::Normal)
+        .encoding(TextEncoding::Latin1)
+        .dump_to::<String>();
+
+    // no-break space, soft hyphen and C1 controls are not shown
+    assert_eq!(dump, "00000000: 63 61 66 E9 20 A0 AD BF 85 |café ..¿.|\n");
+}
+
+#[test]
+fn cp437() {
+    let v = b"\xc9\xcd\xbb\x01\x7f\xe1\x9c\xfe";
+
+    let dump = v
+        .hexd()
+        .ungrouped(8, Spacing::Normal)
+        .encoding(TextEncoding::Cp437)
+        .dump_to::<String>();
+
+    assert_eq!(dump, "00000000: C9 CD BB 01 7F E1 9C FE |╔═╗.⌂ß£■|\n");
+}
+
+#[test]
+fn ebcdic() {
+    // "RECORD 01" followed by a newline and a few control characters
+    let v = b"\xd9\xc5\xc3\xd6\xd9\xc4\x40\xf0\xf1\x15\x00\x25\x4a\x5b\x7c\x9f";
+
+    let dump = v.hexd().encoding(TextEncoding::Ebcdic).dump_to::<String>();
+
+    assert_eq!(
+        dump,
+        "00000000: D9C5 C3D6 D9C4 40F0 F115 0025 4A5B 7C9F |RECORD 01...¢$@¤|\n"
+    );
+}
+
+#[test]
+fn utf8_across_group_and_row_boundaries() {
+    // Given
+    let text = "ab €ü 😀 Ωx";
+
+    // When
+    let dump = text
+        .hexd()
+        .grouped_by(hxd::options::GroupSize::Short, 2)
+        .encoding(TextEncoding::Utf8)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 6162 20E2 |ab €|
+            00000004: 82AC C3BC |··ü·|
+            00000008: 20F0 9F98 | 😀··|
+            0000000C: 8020 CEA9 |· Ω·|
+            00000010: 78        |x   |
+        "},
+        dump
+    );
+}
+
+#[test]
+fn utf8_invalid_sequences() {
+    // a lone continuation byte, a truncated sequence, an overlong
+    // encoding and a byte which never appears in UTF-8
+    let v = b"\x80a\xe2\x82b\xc0\xafc\xff";
+
+    let dump = v
+        .hexd()
+        .ungrouped(9, Spacing::Normal)
+        .encoding(TextEncoding::Utf8)
+        .dump_to::<String>();
+
+    assert_eq!(dump, "00000000: 80 61 E2 82 62 C0 AF 63 FF |.a..b..c.|\n");
+}
+
+#[test]
+fn utf8_respects_print_range() {
+    // the character after the range must not be decoded into it
+    let text = "aé";
+
+    let dump = text
+        .hexd()
+        .range(..2)
+        .ungrouped(2, Spacing::Normal)
+        .encoding(TextEncoding::Utf8)
+        .dump_to::<String>();
+    assert_eq!(dump, "00000000: 61 C3 |a.|\n");
+
+    let dump = text
+        .hexd()
+        .range(2..)
+        .aligned(false)
+        .ungrouped(2, Spacing::Normal)
+        .encoding(TextEncoding::Utf8)
+        .dump_to::<String>();
+    assert_eq!(dump, "00000002: A9    |. |\n");
+}
+
+#[test]
+fn utf8_with_narrow_rows_and_iterators() {
+    // Given
+    let text = "€uro";
+
+    // When
+    let dump = text
+        .bytes()
+        .into_hexd()
+        .ungrouped(1, Spacing::Normal)
+        .encoding(TextEncoding::Utf8)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: E2 |€|
+            00000001: 82 |·|
+            00000002: AC |·|
+            00000003: 75 |u|
+            00000004: 72 |r|
+            00000005: 6F |o|
+        "},
+        dump
+    );
+}
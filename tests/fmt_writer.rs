@@ -0,0 +1,59 @@
+use std::fmt::{self, Write};
+
+use hxd::{
+    options::{HexdOptionsBuilder, Spacing},
+    writer::FmtWriter,
+    AsHexd,
+};
+
+/// A sink with a fixed capacity, like a buffer for a serial port.
+#[derive(Default)]
+struct Uart {
+    sent: String,
+    capacity: usize,
+}
+
+impl Write for Uart {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.sent.len() + s.len() > self.capacity {
+            return Err(fmt::Error);
+        }
+        self.sent.push_str(s);
+        Ok(())
+    }
+}
+
+#[test]
+fn writes_to_fmt_sinks() {
+    let uart = Uart {
+        capacity: 1024,
+        ..Default::default()
+    };
+
+    let uart = b"boot ok\n"
+        .hexd()
+        .ungrouped(8, Spacing::Normal)
+        .dump_into(FmtWriter(uart))
+        .unwrap();
+
+    assert_eq!(uart.sent, "00000000: 62 6F 6F 74 20 6F 6B 0A |boot ok.|\n");
+}
+
+#[test]
+fn stops_at_the_first_error() {
+    let uart = Uart {
+        capacity: 40,
+        ..Default::default()
+    };
+
+    let result = [0u8; 64].hexd().dump_into(FmtWriter(uart));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn default_sinks() {
+    let dump = b"abc".hexd().dump_to::<FmtWriter<String>>().unwrap();
+
+    assert_eq!(dump, b"abc".hexd().dump_to::<String>());
+}
@@ -0,0 +1,129 @@
+use hxd::{
+    options::{Checksum, ElisionMode, Footer, HexdOptionsBuilder, Spacing},
+    AsHexd,
+};
+use indoc::indoc;
+
+fn footer(dump: &str) -> &str {
+    dump.lines().last().unwrap()
+}
+
+#[test]
+fn footer_reports_totals() {
+    // Given
+    let v = [b"head".to_vec(), vec![0u8; 40], b"tail".to_vec()].concat();
+
+    // When
+    let dump = v
+        .hexd()
+        .ungrouped(8, Spacing::None)
+        .range(2..46)
+        .footer(Footer::Summary(None))
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000:     616400000000 |  ad....|
+            00000008: 0000000000000000 |........|
+            *
+            00000020: 0000000000000000 |........|
+            00000028: 000000007461     |....ta  |
+            total: 44 bytes, range: 00000002-0000002E, elided: 2 rows (16 bytes)
+        "},
+        dump
+    );
+}
+
+#[test]
+fn checksums_match_reference_values() {
+    // Given
+    let v = b"The quick brown fox jumps over the lazy dog";
+    let dump = |checksum| {
+        v.hexd()
+            .footer(Footer::Summary(Some(checksum)))
+            .uppercase(false)
+            .dump_to::<String>()
+    };
+
+    // Then
+    assert!(footer(&dump(Checksum::Crc32)).ends_with(", crc32: 414fa339"));
+    assert!(footer(&dump(Checksum::Adler32)).ends_with(", adler32: 5bdc0fda"));
+    assert!(footer(&dump(Checksum::Sum8)).ends_with(", sum8: d9"));
+    assert!(footer(&dump(Checksum::Xor8)).ends_with(", xor8: 4f"));
+}
+
+#[test]
+fn checksums_include_elided_rows() {
+    // Given
+    let v = [b"0123456789abcdef".repeat(300), vec![0xffu8; 70000]].concat();
+
+    for checksum in [
+        Checksum::Crc32,
+        Checksum::Adler32,
+        Checksum::Sum8,
+        Checksum::Xor8,
+    ] {
+        // When
+        let dump = v
+            .hexd()
+            .elision_mode(ElisionMode::Repeated)
+            .footer(Footer::Summary(Some(checksum)))
+            .dump_to::<String>();
+
+        // Then
+        let expected = format!(
+            "total: 74800 bytes, range: 00000000-00012430, elided: 4671 rows (74736 bytes), {}: {:0w$X}",
+            checksum.name(),
+            checksum.of(&v),
+            w = checksum.width()
+        );
+        assert_eq!(dump.lines().count(), 7);
+        similar_asserts::assert_eq!(expected, footer(&dump));
+    }
+}
+
+#[test]
+fn empty_input_has_a_footer() {
+    let dump = b""
+        .hexd()
+        .range(0x10..)
+        .footer(Footer::Summary(Some(Checksum::Crc32)))
+        .dump_to::<String>();
+
+    assert_eq!(
+        "total: 0 bytes, range: 00000010-00000010, elided: 0 rows (0 bytes), crc32: 00000000\n",
+        dump
+    );
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+#[test]
+fn checksums_skip_unknown_bytes() {
+    use std::io::{Seek, SeekFrom, Write};
+
+    use hxd::{reader::SparseFileReader, FallibleHexd};
+
+    // Given
+    let path = std::env::temp_dir().join(format!("hxd-footer-{}", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    file.write_all(b"head").unwrap();
+    file.seek(SeekFrom::Start(0x20000)).unwrap();
+    file.write_all(b"tail").unwrap();
+    drop(file);
+    let known = [b"head".to_vec(), vec![0u8; 0xffc], b"tail".to_vec()].concat();
+
+    // When
+    let dump = FallibleHexd::new(SparseFileReader::open(&path).unwrap())
+        .footer(Footer::Summary(Some(Checksum::Crc32)))
+        .dump_to::<String>()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // Then
+    let expected = format!(
+        "total: 131076 bytes, range: 00000000-00020004, elided: 8187 rows (130992 bytes), unknown: 126976 bytes, crc32: {:08X}",
+        Checksum::Crc32.of(&known)
+    );
+    similar_asserts::assert_eq!(expected, footer(&dump));
+}
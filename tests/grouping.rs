@@ -1,13 +1,18 @@
 use common::{ByteSequence, RenderTestCase};
-use hxd::options::{
-    GroupSize, Grouping, HexdOptions, HexdOptionsBuilder, HexdRange, IndexOffset, Spacing,
+use hxd::{
+    options::{
+        ElisionMarker, ElisionMode, GroupSize, Grouping, HexdOptions, HexdOptionsBuilder,
+        HexdRange, IndexOffset, Spacing, TextEncoding,
+    },
+    parser::HexdParser,
+    AsHexd,
 };
 use indoc::indoc;
 
 mod common;
 
 fn default_test_options() -> HexdOptions {
-    let default_options = HexdOptions {
+    HexdOptions {
         base: hxd::options::Base::Hex,
         autoskip: true,
         uppercase: true,
@@ -20,8 +25,8 @@ fn default_test_options() -> HexdOptions {
             limit: None,
         },
         index_offset: IndexOffset::Relative(0),
-    };
-    default_options
+        ..HexdOptions::default()
+    }
 }
 
 byte_tests! {
@@ -237,3 +242,95 @@ byte_tests! {
             )
     },
 }
+
+#[test]
+fn rows_wider_than_256_bytes() {
+    // Given
+    let input = (0..600).map(|i| (i % 256) as u8).collect::<Vec<u8>>();
+
+    // When
+    let dump = input
+        .hexd()
+        .autoskip(false)
+        .ungrouped(300, Spacing::Normal)
+        .dump_to::<String>();
+
+    // Then
+    let expected = input
+        .chunks(300)
+        .enumerate()
+        .map(|(i, row)| {
+            let bytes = row.iter().map(|b| format!("{b:02X}")).collect::<Vec<_>>();
+            let text = row
+                .iter()
+                .map(|b| match b {
+                    0x20..=0x7e => *b as char,
+                    _ => '.',
+                })
+                .collect::<String>();
+            format!("{:08X}: {} |{}|\n", i * 300, bytes.join(" "), text)
+        })
+        .collect::<String>();
+    similar_asserts::assert_eq!(expected, dump);
+}
+
+#[test]
+fn wide_rows_round_trip_in_every_base() {
+    let input = (0..1000).map(|i| (i * 7 % 256) as u8).collect::<Vec<u8>>();
+    let layouts = [
+        HexdOptions::default()
+            .binary()
+            .ungrouped(64, Spacing::Normal),
+        HexdOptions::default().octal().ungrouped(300, Spacing::Wide),
+        HexdOptions::default()
+            .decimal()
+            .ungrouped(257, Spacing::None),
+        HexdOptions::default().grouped((GroupSize::ULong, Spacing::Normal), (40, Spacing::Wide)),
+        HexdOptions::default()
+            .encoding(TextEncoding::Utf8)
+            .ungrouped(512, Spacing::Normal),
+    ];
+
+    for options in layouts {
+        let dump = input
+            .hexd()
+            .with_options(options.clone())
+            .dump_to::<String>();
+        let parsed = HexdParser::new_with_options(options.clone()).parse_str(&dump);
+
+        assert_eq!(Ok(input.clone()), parsed, "options: {options:?}");
+    }
+}
+
+#[test]
+fn wide_rows_are_elided() {
+    // Given
+    let input = b"0123456789".repeat(400);
+
+    // When
+    let dump = input
+        .hexd()
+        .ungrouped(1000, Spacing::None)
+        .elision_mode(ElisionMode::Repeated)
+        .elision_marker(ElisionMarker::Counted)
+        .dump_to::<String>();
+
+    // Then
+    let lines = dump.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("00000000: 3031"));
+    assert_eq!(lines[1], "* (2 rows, 0x7D0 bytes identical)");
+    assert!(lines[2].starts_with("00000BB8: 3031"));
+}
+
+#[test]
+fn markers_wider_than_the_line_buffer() {
+    let marker = "*".repeat(2000);
+
+    let dump = vec![0u8; 64]
+        .hexd()
+        .elision_marker(ElisionMarker::Template(marker.clone().into()))
+        .dump_to::<String>();
+
+    assert_eq!(dump.lines().nth(1), Some(marker.as_str()));
+}
@@ -0,0 +1,113 @@
+use hxd::{
+    options::{ElisionMarker, Header, HexdOptionsBuilder, IndexBase, Palette, Spacing, Style},
+    AsHexd,
+};
+use indoc::indoc;
+
+#[test]
+fn header_matches_the_default_layout() {
+    let dump = (0..20u8)
+        .collect::<Vec<_>>()
+        .hexd()
+        .header(Header::Top)
+        .dump_to::<String>();
+
+    similar_asserts::assert_eq!(
+        indoc! {"
+                      0001 0203 0405 0607 0809 0A0B 0C0D 0E0F  0123456789ABCDEF
+            00000000: 0001 0203 0405 0607 0809 0A0B 0C0D 0E0F |................|
+            00000010: 1011 1213                               |....            |
+        "},
+        dump
+    );
+}
+
+#[test]
+fn header_follows_the_base_and_spacing() {
+    // Given
+    let v = b"abcdef";
+
+    // When
+    let decimal = v
+        .hexd()
+        .decimal()
+        .ungrouped(6, Spacing::Wide)
+        .header(Header::Top)
+        .index_base(IndexBase::Decimal)
+        .index_width(4, Some(4))
+        .dump_to::<String>();
+    let binary = v
+        .hexd()
+        .binary()
+        .ungrouped(3, Spacing::Normal)
+        .show_ascii(false)
+        .show_index(false)
+        .header(Header::Top)
+        .uppercase(false)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+                   00   01   02   03   04   05   012345
+            0000:  97   98   99  100  101  102  |abcdef|
+        "},
+        decimal
+    );
+    similar_asserts::assert_eq!(
+        indoc! {"
+                  00       01       02
+            01100001 01100010 01100011
+            01100100 01100101 01100110
+        "},
+        binary
+    );
+}
+
+#[test]
+fn header_is_repeated() {
+    // Given
+    let v = [vec![1u8; 4], vec![0u8; 16], (2..14).collect()].concat();
+
+    // When
+    let dump = v
+        .hexd()
+        .ungrouped(4, Spacing::None)
+        .header(Header::Every(2))
+        .elision_marker(ElisionMarker::Counted)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+                      00010203  0123
+            00000000: 01010101 |....|
+            00000004: 00000000 |....|
+            * (2 rows, 0x8 bytes identical)
+                      00010203  0123
+            00000010: 00000000 |....|
+            00000014: 02030405 |....|
+                      00010203  0123
+            00000018: 06070809 |....|
+            0000001C: 0A0B0C0D |....|
+        "},
+        dump
+    );
+}
+
+#[test]
+fn header_is_styled_like_the_index() {
+    let palette = Palette {
+        index: Style::plain().bold(),
+        ..Palette::plain()
+    };
+
+    let dump = b"ab"
+        .hexd()
+        .ungrouped(2, Spacing::Normal)
+        .header(Header::Top)
+        .palette(palette)
+        .dump_to::<Vec<String>>();
+
+    assert_eq!(dump[0], "\x1b[1m          00 01  01\x1b[0m\n");
+}
@@ -0,0 +1,226 @@
+use std::convert::Infallible;
+
+use hxd::{
+    options::{Base, HexdOptionsBuilder, LeadingZeroChar, Placeholder, Spacing},
+    reader::ReadBytes,
+    rows::Line,
+    Hexd,
+};
+use indoc::indoc;
+
+/// A reader over bytes which may be unknown.
+struct Gappy {
+    bytes: Vec<Option<u8>>,
+    index: usize,
+}
+
+impl Gappy {
+    fn new(bytes: Vec<Option<u8>>) -> Self {
+        Self { bytes, index: 0 }
+    }
+
+    /// Return the length of the run of known or unknown bytes at the
+    /// current index, up to `max`.
+    fn run(&self, known: bool, max: usize) -> usize {
+        self.bytes[self.index..]
+            .iter()
+            .take(max)
+            .take_while(|b| b.is_some() == known)
+            .count()
+    }
+}
+
+impl ReadBytes for Gappy {
+    type Error = Infallible;
+
+    fn next_n<'buf>(&mut self, buf: &'buf mut [u8]) -> Result<&'buf [u8], Self::Error> {
+        let n = self.run(true, buf.len());
+        for (b, v) in buf.iter_mut().zip(&self.bytes[self.index..self.index + n]) {
+            *b = v.unwrap();
+        }
+        self.index += n;
+        Ok(&buf[..n])
+    }
+
+    fn skip_unknown(&mut self, max: usize) -> Result<usize, Self::Error> {
+        let n = self.run(false, max);
+        self.index += n;
+        Ok(n)
+    }
+
+    fn total_byte_hint(&self) -> Option<usize> {
+        Some(self.bytes.len())
+    }
+}
+
+fn gappy(known: &[u8], unknown: usize, after: &[u8]) -> Gappy {
+    let bytes = known
+        .iter()
+        .map(|b| Some(*b))
+        .chain((0..unknown).map(|_| None))
+        .chain(after.iter().map(|b| Some(*b)))
+        .collect();
+    Gappy::new(bytes)
+}
+
+#[test]
+fn unknown_bytes_are_placeholders_in_every_base() {
+    // Given
+    let dump = |base: Base, placeholder: Placeholder| {
+        Hexd::new(gappy(b"ab", 2, b"c"))
+            .ungrouped(5, Spacing::Normal)
+            .base(base)
+            .placeholder(placeholder)
+            .dump_to::<String>()
+    };
+    let octal = Base::Octal(LeadingZeroChar::Zero);
+    let decimal = Base::Decimal(LeadingZeroChar::Space);
+
+    // Then
+    similar_asserts::assert_eq!(
+        "00000000: 61 62 ?? ?? 63 |ab??c|\n",
+        dump(Base::Hex, Placeholder::Question)
+    );
+    similar_asserts::assert_eq!(
+        "00000000: 61 62 __ __ 63 |ab__c|\n",
+        dump(Base::Hex, Placeholder::Underscore)
+    );
+    similar_asserts::assert_eq!(
+        "00000000: 141 142 ??? ??? 143 |ab??c|\n",
+        dump(octal, Placeholder::Question)
+    );
+    similar_asserts::assert_eq!(
+        "00000000:  97  98 ___ ___  99 |ab__c|\n",
+        dump(decimal, Placeholder::Underscore)
+    );
+    similar_asserts::assert_eq!(
+        "00000000: 01100001 01100010 ???????? ???????? 01100011 |ab??c|\n",
+        dump(Base::Binary, Placeholder::Question)
+    );
+}
+
+#[test]
+fn unknown_rows_are_elided() {
+    // Given
+    let dump = |autoskip: bool| {
+        Hexd::new(gappy(&[0; 6], 40, b"end"))
+            .ungrouped(8, Spacing::None)
+            .autoskip(autoskip)
+            .dump_to::<String>()
+    };
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 000000000000???? |......??|
+            00000008: ???????????????? |????????|
+            *
+            00000020: ???????????????? |????????|
+            00000028: ????????????656E |??????en|
+            00000030: 64               |d       |
+        "},
+        dump(true)
+    );
+    assert_eq!(7, dump(false).lines().count());
+}
+
+#[test]
+fn unknown_rows_do_not_match_zero_rows() {
+    // Given
+    let dump = Hexd::new(gappy(&[0; 8], 16, &[0; 8]))
+        .ungrouped(8, Spacing::None)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 0000000000000000 |........|
+            00000008: ???????????????? |????????|
+            00000010: ???????????????? |????????|
+            00000018: 0000000000000000 |........|
+        "},
+        dump
+    );
+}
+
+#[test]
+fn rows_report_unknown_bytes() {
+    // Given
+    let rows = Hexd::new(gappy(b"xy", 3, b"z"))
+        .ungrouped(4, Spacing::None)
+        .rows();
+
+    // When
+    let mut unknown = Vec::new();
+    for line in rows {
+        if let Line::Row(row) = line.unwrap() {
+            unknown.push(
+                (0..row.bytes().len())
+                    .map(|i| row.is_unknown(i))
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    // Then
+    assert_eq!(
+        vec![vec![false, false, true, true], vec![true, false]],
+        unknown
+    );
+}
+
+#[test]
+fn plain_output_has_placeholders() {
+    // Given
+    let dump = Hexd::new(gappy(b"ab", 2, b"c"))
+        .plain(None)
+        .placeholder(Placeholder::Underscore)
+        .dump_to::<String>();
+
+    // Then
+    assert_eq!("6162____63\n", dump);
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+#[test]
+fn sparse_file_holes_are_unknown() {
+    use std::io::{Seek, SeekFrom, Write};
+
+    use hxd::{reader::SparseFileReader, FallibleHexd};
+
+    // Given
+    let path = std::env::temp_dir().join(format!("hxd-sparse-{}", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    file.write_all(b"head").unwrap();
+    file.seek(SeekFrom::Start(0x20000)).unwrap();
+    file.write_all(b"tail").unwrap();
+    file.set_len(0x30000).unwrap();
+    drop(file);
+
+    // When
+    let dump = FallibleHexd::new(SparseFileReader::open(&path).unwrap())
+        .dump_to::<String>()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 6865 6164 0000 0000 0000 0000 0000 0000 |head............|
+            00000010: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+            *
+            00000FF0: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+            00001000: ???? ???? ???? ???? ???? ???? ???? ???? |????????????????|
+            *
+            0001FFF0: ???? ???? ???? ???? ???? ???? ???? ???? |????????????????|
+            00020000: 7461 696C 0000 0000 0000 0000 0000 0000 |tail............|
+            00020010: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+            *
+            00020FF0: 0000 0000 0000 0000 0000 0000 0000 0000 |................|
+            00021000: ???? ???? ???? ???? ???? ???? ???? ???? |????????????????|
+            *
+            0002FFF0: ???? ???? ???? ???? ???? ???? ???? ???? |????????????????|
+        "},
+        dump
+    );
+}
@@ -0,0 +1,158 @@
+use hxd::{
+    options::{GroupSize, HexdOptionsBuilder, IndexBase, IndexUnits, Spacing},
+    AsHexd, AsHexdGrouped, IntoHexd,
+};
+use indoc::indoc;
+
+#[test]
+fn decimal_and_octal_indices() {
+    // Given
+    let v = vec![0x41u8; 24];
+
+    // When
+    let decimal = v
+        .as_hexd()
+        .ungrouped(8, Spacing::None)
+        .autoskip(false)
+        .index_base(IndexBase::Decimal)
+        .dump_to::<String>();
+    let octal = v
+        .as_hexd()
+        .ungrouped(8, Spacing::None)
+        .autoskip(false)
+        .index_base(IndexBase::Octal)
+        .index_prefix("0o")
+        .index_width(3, None)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000: 4141414141414141 |AAAAAAAA|
+            00000008: 4141414141414141 |AAAAAAAA|
+            00000016: 4141414141414141 |AAAAAAAA|
+        "},
+        decimal
+    );
+    similar_asserts::assert_eq!(
+        indoc! {"
+            0o000: 4141414141414141 |AAAAAAAA|
+            0o010: 4141414141414141 |AAAAAAAA|
+            0o020: 4141414141414141 |AAAAAAAA|
+        "},
+        octal
+    );
+}
+
+// indices past 32 bits need a 64-bit `usize`
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn minimum_width_steadies_unknown_lengths() {
+    // Given
+    let bytes = || {
+        let mut bytes = (0..48u8).map(|b| b % 4);
+        std::iter::from_fn(move || bytes.next())
+    };
+    let dump = |raised: bool| {
+        let hexd = bytes()
+            .into_hexd()
+            .ungrouped(16, Spacing::None)
+            .show_ascii(false)
+            .relative_offset(0xffff_ffe0);
+        let hexd = if raised {
+            hexd.index_width(10, None)
+        } else {
+            hexd
+        };
+        hexd.dump_to::<String>()
+    };
+
+    // When
+    let default = dump(false);
+    let raised = dump(true);
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            FFFFFFE0: 00010203000102030001020300010203
+            FFFFFFF0: 00010203000102030001020300010203
+            100000000: 00010203000102030001020300010203
+        "},
+        default
+    );
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00FFFFFFE0: 00010203000102030001020300010203
+            00FFFFFFF0: 00010203000102030001020300010203
+            0100000000: 00010203000102030001020300010203
+        "},
+        raised
+    );
+}
+
+#[test]
+fn maximum_width_never_drops_digits() {
+    let dump = vec![0u8; 16]
+        .as_hexd()
+        .ungrouped(8, Spacing::None)
+        .show_ascii(false)
+        .relative_offset(0x12345)
+        .index_width(4, Some(4))
+        .dump_to::<String>();
+
+    assert_eq!("12345: 0000000000000000\n1234D: 0000000000000000\n", dump);
+}
+
+#[test]
+fn indices_count_elements() {
+    // Given
+    let v = (0..12u32).collect::<Vec<_>>();
+
+    // When
+    let dump = v
+        .hexd_le()
+        .grouped((GroupSize::Int, Spacing::None), (4, Spacing::Normal))
+        .show_ascii(false)
+        .index_units(IndexUnits::Elements)
+        .index_base(IndexBase::Decimal)
+        .index_width(2, None)
+        .index_separator(" | ")
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00 | 00000000 01000000 02000000 03000000
+            04 | 04000000 05000000 06000000 07000000
+            08 | 08000000 09000000 0A000000 0B000000
+        "},
+        dump
+    );
+}
+
+#[test]
+fn diff_padding_matches_the_index() {
+    // Given
+    let left = b"abcd";
+    let right = b"abcD";
+
+    // When
+    let diff = left
+        .as_hexd()
+        .diff(right.as_hexd())
+        .ungrouped(4, Spacing::Normal)
+        .index_prefix("0x")
+        .index_width(4, Some(4))
+        .index_separator(" | ")
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            -0x0000 | 61 62 63 64 |abcd|
+            +0x0000 | 61 62 63 44 |abcD|
+                               ^^     ^
+        "},
+        diff
+    );
+}
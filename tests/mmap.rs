@@ -0,0 +1,108 @@
+#![cfg(all(feature = "mmap", target_os = "linux"))]
+
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+use hxd::{
+    mmap::Mmap,
+    options::HexdOptionsBuilder,
+    reader::{ByteSliceReader, ReadBytes},
+    AsHexd, FallibleHexd, Hexd,
+};
+
+fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("hxd-mmap-{name}-{}", std::process::id()));
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn maps_dump_like_slices() {
+    // Given
+    let v: Vec<u8> = (0..=255u8).cycle().take(10000).collect();
+    let path = temp_file("slice", &v);
+
+    // When
+    let map = Mmap::open(&path).unwrap();
+    // SAFETY: the file is not modified while the slice is alive
+    let from_slice = unsafe { map.as_slice() }
+        .hexd()
+        .range(0x1ff0..0x2010)
+        .dump_to::<String>();
+    let from_reader = FallibleHexd::new(map.reader())
+        .range(0x1ff0..0x2010)
+        .dump_to::<String>()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    // Then
+    let expected = v.hexd().range(0x1ff0..0x2010).dump_to::<String>();
+    similar_asserts::assert_eq!(expected, from_slice);
+    similar_asserts::assert_eq!(expected, from_reader);
+}
+
+#[test]
+fn readers_skip_without_reading() {
+    // Given
+    let path = temp_file("skip", &[7u8; 100]);
+    let map = Mmap::open(&path).unwrap();
+    let mut reader = map.reader();
+    let mut buf = [0u8; 16];
+
+    // When
+    let skipped = (reader.skip_n(90).unwrap(), reader.skip_n(90).unwrap());
+    let rest = reader.next_n(&mut buf).unwrap().len();
+    fs::remove_file(&path).unwrap();
+
+    // Then
+    assert_eq!((90, 10), skipped);
+    assert_eq!(0, rest);
+    assert_eq!(Some(100), reader.total_byte_hint());
+}
+
+#[test]
+fn empty_files_can_be_mapped() {
+    // Given
+    let path = temp_file("empty", &[]);
+
+    // When
+    let map = Mmap::open(&path).unwrap();
+    let dump = FallibleHexd::new(map.reader()).dump_to::<String>().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    // Then
+    assert!(map.is_empty());
+    assert_eq!(
+        Hexd::new(ByteSliceReader::new(&[])).dump_to::<String>(),
+        dump
+    );
+}
+
+#[test]
+fn truncated_files_end_the_dump() {
+    // Given
+    let path = temp_file("truncated", &[0x55u8; 3 * 4096]);
+    let map = Mmap::open(&path).unwrap();
+    let mut reader = map.reader();
+    let mut buf = [0u8; 1024];
+    reader.next_n(&mut buf).unwrap();
+
+    // When
+    fs::OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_len(5000)
+        .unwrap();
+    let mut read = 1024;
+    let error = loop {
+        match reader.next_n(&mut buf) {
+            Ok(bytes) => read += bytes.len(),
+            Err(e) => break e,
+        }
+    };
+    fs::remove_file(&path).unwrap();
+
+    // Then
+    assert_eq!(ErrorKind::UnexpectedEof, error.kind());
+    assert!((4096..=5000).contains(&read));
+}
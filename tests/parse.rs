@@ -0,0 +1,234 @@
+use common::ByteSequence;
+use hxd::{
+    options::{
+        Base, Checksum, ElisionMarker, ElisionMode, Footer, GroupSize, Grouping, Header,
+        HexdOptions, HexdOptionsBuilder, HexdRange, IndexBase, IndexOffset, IndexUnits,
+        LeadingZeroChar, Spacing,
+    },
+    parser::{HexdParser, ParseError, ParseErrorKind},
+    AsHexd, AsHexdGrouped, IntoHexd,
+};
+use indoc::indoc;
+
+mod common;
+
+fn default_test_options() -> HexdOptions {
+    HexdOptions {
+        base: hxd::options::Base::Hex,
+        autoskip: true,
+        uppercase: true,
+        show_index: true,
+        show_ascii: true,
+        align: true,
+        grouping: Grouping::default(),
+        print_range: HexdRange {
+            skip: 0,
+            limit: None,
+        },
+        index_offset: IndexOffset::Relative(0),
+        ..HexdOptions::default()
+    }
+}
+
+fn test_inputs() -> Vec<Vec<u8>> {
+    vec![
+        vec![],
+        vec![0x7a],
+        (0..=255u8).collect(),
+        ByteSequence::new(vec![(0u8, 4), (0x0f, 4), (0x7a, 4), (0x20, 4)]).collect(),
+        ByteSequence::new(vec![(0u8, 17), (2, 15)]).collect(),
+        ByteSequence::new(vec![(0u8, 100), (0xff, 3), (0x41, 200), (0x7c, 33)]).collect(),
+    ]
+}
+
+fn test_options() -> Vec<HexdOptions> {
+    let o = default_test_options;
+    vec![
+        o(),
+        o().autoskip(false),
+        o().hexadecimal(),
+        o().decimal(),
+        o().octal(),
+        o().binary(),
+        o().decimal()
+            .base(Base::Decimal(LeadingZeroChar::Underscore)),
+        o().decimal().base(Base::Decimal(LeadingZeroChar::Zero)),
+        o().octal().base(Base::Octal(LeadingZeroChar::Space)),
+        o().uppercase(false),
+        o().ungrouped(4, Spacing::None),
+        o().ungrouped(4, Spacing::Normal),
+        o().ungrouped(4, Spacing::Wide),
+        o().ungrouped(4, Spacing::UltraWide),
+        o().grouped((GroupSize::Short, Spacing::None), (2, Spacing::Normal)),
+        o().grouped((GroupSize::Short, Spacing::Normal), (4, Spacing::Wide)),
+        o().grouped((GroupSize::Short, Spacing::Normal), (4, Spacing::None)),
+        o().grouped((GroupSize::Int, Spacing::Normal), (2, Spacing::Wide)),
+        o().grouped((GroupSize::Long, Spacing::None), (2, Spacing::Normal)),
+        o().grouped((GroupSize::ULong, Spacing::None), (2, Spacing::Normal)),
+        o().range(3..),
+        o().range(..29),
+        o().range(3..13),
+        o().range(19..48),
+        o().range(3..).aligned(false),
+        o().range(19..).relative_offset(0x1B0),
+        o().range(3..).absolute_offset(0),
+        o().absolute_offset(0x201B),
+        o().relative_offset(0xfff0000),
+        o().show_ascii(false),
+        o().show_index(false).autoskip(false),
+        o().show_index(false).show_ascii(false).autoskip(false),
+        o().show_ascii(false).ungrouped(4, Spacing::None),
+        o().elision_mode(ElisionMode::Repeated).min_elided_rows(2),
+        o().elision_marker(ElisionMarker::Counted),
+        o().elision_marker(ElisionMarker::Counted).show_index(false),
+        o().elision_marker(ElisionMarker::Template(
+            "skipped {rows} rows ({start}-{end})".into(),
+        )),
+        o().elision_marker(ElisionMarker::Template("  ...  ".into())),
+        o().index_base(IndexBase::Decimal).relative_offset(0x1B0),
+        o().index_base(IndexBase::Octal).index_prefix("0o"),
+        o().index_width(4, Some(4)).index_separator(" | "),
+        o().index_width(6, Some(6)).index_separator(""),
+        o().grouped_by(GroupSize::Int, 4)
+            .index_units(IndexUnits::Elements)
+            .index_prefix("#"),
+        o().index_prefix("0x")
+            .elision_marker(ElisionMarker::Template("{start}..{end}".into())),
+        o().header(Header::Top),
+        o().header(Header::Every(2))
+            .octal()
+            .show_index(false)
+            .autoskip(false),
+        o().header(Header::Every(1))
+            .binary()
+            .index_base(IndexBase::Decimal),
+        o().footer(Footer::Summary(Some(Checksum::Adler32))),
+        o().footer(Footer::Summary(None))
+            .show_index(false)
+            .autoskip(false),
+        o().plain(Some(30)),
+        o().plain(None).range(3..13),
+        o().decimal().plain(Some(7)),
+        o().octal().plain(Some(300)),
+        o().binary().plain(Some(3)),
+    ]
+}
+
+#[test]
+fn round_trip_all_options() {
+    for options in test_options() {
+        for input in test_inputs() {
+            // Given
+            let range = options.print_range;
+            let start = range.skip.min(input.len());
+            let end = range.limit.unwrap_or(input.len()).min(input.len());
+            let expected = &input[start..end.max(start)];
+
+            // When
+            let dump = input
+                .as_hexd()
+                .with_options(options.clone())
+                .dump_to::<String>();
+            let parsed = HexdParser::new_with_options(options.clone()).parse_str(&dump);
+
+            // Then
+            similar_asserts::assert_eq!(
+                Ok(expected.to_vec()),
+                parsed,
+                "round trip failed for options {:?} and dump:\n{}",
+                options,
+                dump
+            );
+        }
+    }
+}
+
+#[test]
+fn round_trip_line_vector() {
+    let input: Vec<u8> = ByteSequence::new(vec![(0u8, 40), (0x41, 9)]).collect();
+
+    let dump = input.iter().copied().hexd().dump_to::<Vec<String>>();
+    let parsed = HexdParser::new().parse_lines(&dump);
+
+    assert_eq!(Ok(input), parsed);
+}
+
+#[test]
+fn round_trip_grouped_ints() {
+    let input = vec![0x72f072f0u32; 32];
+
+    let dump = input.as_hexd_be().dump_to::<String>();
+    let parsed = HexdParser::new()
+        .grouped_by(GroupSize::Int, 4)
+        .parse_str(&dump)
+        .unwrap();
+
+    let expected: Vec<u8> = input.iter().flat_map(|i| i.to_be_bytes()).collect();
+    assert_eq!(expected, parsed);
+}
+
+#[test]
+fn parse_trimmed_lines() {
+    let dump = indoc! {"
+        00000000: 4865 6C6C 6F2C 2077 6F72 6C64 2120 486F |Hello, world! Ho|
+        00000010: 7065 6675 6C6C 79
+    "};
+
+    let parsed = HexdParser::new().parse_str(dump).unwrap();
+
+    assert_eq!(b"Hello, world! Hopefully".to_vec(), parsed);
+}
+
+#[test]
+fn elision_without_row_is_an_error() {
+    let dump = indoc! {"
+        *
+        00000030: 0505 0505 0505 0505 0505 0505 0505 0505 |................|
+    "};
+
+    let parsed = HexdParser::new().parse_str(dump);
+
+    assert_eq!(
+        Err(ParseError {
+            line: 1,
+            kind: ParseErrorKind::UnexpectedElision
+        }),
+        parsed
+    );
+}
+
+#[test]
+fn elision_without_index_is_an_error() {
+    let dump = indoc! {"
+        0505 0505 0505 0505 0505 0505 0505 0505 |................|
+        *
+        0505 0505 0505 0505 0505 0505 0505 0505 |................|
+    "};
+
+    let parsed = HexdParser::new().show_index(false).parse_str(dump);
+
+    assert_eq!(
+        Err(ParseError {
+            line: 3,
+            kind: ParseErrorKind::UnsizedElision
+        }),
+        parsed
+    );
+}
+
+#[test]
+fn invalid_byte_is_an_error() {
+    let dump = "00000000: 0505 05G5 |....|\n";
+
+    let parsed = HexdParser::new()
+        .grouped_by(GroupSize::Short, 2)
+        .parse_str(dump);
+
+    assert_eq!(
+        Err(ParseError {
+            line: 1,
+            kind: ParseErrorKind::InvalidByte(3)
+        }),
+        parsed
+    );
+}
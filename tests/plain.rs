@@ -0,0 +1,98 @@
+use hxd::{
+    options::{HexdOptionsBuilder, Palette, Style},
+    AsHexd, IntoHexd,
+};
+use indoc::indoc;
+
+#[test]
+fn xxd_style_lines() {
+    // Given
+    let input = (0..64u8).collect::<Vec<u8>>();
+
+    // When
+    let dump = input
+        .hexd()
+        .plain(Some(30))
+        .uppercase(false)
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d
+            1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b
+            3c3d3e3f
+        "},
+        dump
+    );
+}
+
+#[test]
+fn last_line_is_not_padded() {
+    let dump = b"abcde".hexd().plain(Some(4)).dump_to::<Vec<String>>();
+
+    assert_eq!(vec!["61626364\n", "65\n", ""], dump);
+}
+
+#[test]
+fn lines_wider_than_a_row() {
+    // Given
+    let input = (0..=255u8).cycle().take(1000);
+
+    // When
+    let dump = input.hexd().plain(Some(600)).dump_to::<Vec<String>>();
+
+    // Then
+    assert_eq!(dump.len(), 3);
+    assert_eq!(dump[0].len(), 1201);
+    assert_eq!(dump[1].len(), 801);
+    assert!(dump[0].starts_with("000102"));
+    assert!(dump[1].starts_with("58595A"));
+}
+
+#[test]
+fn single_line() {
+    // Given
+    let input = vec![0xabu8; 5000];
+
+    // When
+    let dump = input.hexd().plain(None).range(1..).dump_to::<String>();
+
+    // Then
+    assert_eq!(format!("{}\n", "AB".repeat(4999)), dump);
+}
+
+#[test]
+fn empty_input() {
+    let empty: &[u8] = &[];
+
+    assert_eq!("", empty.hexd().plain(Some(16)).dump_to::<String>());
+}
+
+#[test]
+fn other_bases() {
+    assert_eq!(
+        " 65 66\n 67\n",
+        b"ABC".hexd().decimal().plain(Some(2)).dump_to::<String>()
+    );
+    assert_eq!(
+        "0100000101000010\n",
+        b"AB".hexd().binary().plain(None).dump_to::<String>()
+    );
+}
+
+#[test]
+fn colored() {
+    let palette = Palette {
+        printable: Style::plain().bold(),
+        ..Palette::plain()
+    };
+
+    let dump = b"A\0"
+        .hexd()
+        .plain(None)
+        .palette(palette)
+        .dump_to::<String>();
+
+    assert_eq!("\x1b[1m41\x1b[0m00\n", dump);
+}
@@ -0,0 +1,142 @@
+#![cfg(all(feature = "std", target_os = "linux"))]
+
+use hxd::{
+    options::{HexdOptionsBuilder, Spacing},
+    process::{memory_regions, MemoryRegion, ProcessMemory},
+    AsHexd, FallibleHexd,
+};
+
+#[repr(align(16))]
+struct Aligned([u8; 48]);
+
+#[test]
+fn regions_are_parsed() {
+    // Given
+    let file: MemoryRegion = "7f2c4a200000-7f2c4a222000 r-xp 00001000 08:01 1234   /opt/my lib.so"
+        .parse()
+        .unwrap();
+    let anonymous: MemoryRegion = "7f2c4a222000-7f2c4a223000 rw-s 00000000 00:00 0"
+        .parse()
+        .unwrap();
+
+    // Then
+    assert_eq!(
+        MemoryRegion {
+            start: 0x7f2c4a200000,
+            end: 0x7f2c4a222000,
+            readable: true,
+            writable: false,
+            executable: true,
+            shared: false,
+            offset: 0x1000,
+            path: Some("/opt/my lib.so".into()),
+        },
+        file
+    );
+    assert_eq!(0x1000, anonymous.len());
+    assert!(anonymous.writable && anonymous.shared && anonymous.path.is_none());
+    assert!("7f2c4a200000 r-xp 0 08:01 1234"
+        .parse::<MemoryRegion>()
+        .is_err());
+}
+
+#[test]
+fn own_memory_is_dumped_at_its_address() {
+    // Given
+    let data = Aligned(core::array::from_fn(|i| i as u8 * 3));
+    let address = data.0.as_ptr() as usize;
+    let memory = ProcessMemory::open(std::process::id()).unwrap();
+
+    // When
+    let dump = FallibleHexd::new(memory)
+        .range(address..address + data.0.len())
+        .dump_to::<String>()
+        .unwrap();
+
+    // Then
+    let expected = data
+        .0
+        .hexd()
+        .relative_offset(address)
+        .index_width(12, None)
+        .dump_to::<String>();
+    similar_asserts::assert_eq!(expected, dump);
+    assert!(dump.starts_with(&format!("{address:012X}: 0003 0609")));
+}
+
+#[test]
+fn unreadable_bytes_are_placeholders() {
+    // Given
+    let regions = memory_regions(std::process::id()).unwrap();
+    let readable = |address: usize| regions.iter().any(|r| r.readable && r.contains(address));
+    let last = regions.iter().rfind(|r| r.readable).unwrap().start;
+    let end = regions
+        .iter()
+        .find(|r| r.readable && !readable(r.end) && r.end < last)
+        .expect("no readable region is followed by an unreadable one")
+        .end;
+
+    // When
+    let dump = FallibleHexd::new(ProcessMemory::open_self().unwrap())
+        .ungrouped(8, Spacing::None)
+        .aligned(false)
+        .range(end - 4..end + 4)
+        .dump_to::<String>()
+        .unwrap();
+
+    // Then
+    let (index, cells) = dump.trim_end().split_once(": ").unwrap();
+    assert_eq!(format!("{:012X}", end - 4), index);
+    assert_eq!("????????", &cells[8..16]);
+    assert!(cells.ends_with("????|"), "{cells}");
+}
+
+#[test]
+fn absolute_offset_shows_addresses() {
+    // Given
+    let memory = ProcessMemory::open_self().unwrap();
+    let region = memory
+        .regions()
+        .iter()
+        .find(|r| r.readable && r.len() >= 0x40)
+        .unwrap()
+        .clone();
+
+    // When
+    let dump = FallibleHexd::new(memory)
+        .range(region.start..region.start + 0x40)
+        .absolute_offset(region.start)
+        .dump_to::<String>()
+        .unwrap();
+
+    // Then
+    let indexes: Vec<usize> = dump
+        .lines()
+        .filter(|line| !line.starts_with('*'))
+        .map(|line| usize::from_str_radix(line.split_once(": ").unwrap().0, 16).unwrap())
+        .collect();
+    assert_eq!(Some(&region.start), indexes.first());
+    assert_eq!(Some(&(region.start + 0x30)), indexes.last());
+}
+
+#[test]
+fn gaps_between_regions_are_elided_at_once() {
+    // Given
+    let memory = ProcessMemory::open_self().unwrap();
+    let first = memory.regions().iter().find(|r| r.readable).unwrap().start;
+
+    // When
+    let dump = FallibleHexd::new(memory)
+        .range(..first + 0x10)
+        .dump_to::<String>()
+        .unwrap();
+    let dump: Vec<&str> = dump.lines().collect();
+
+    // Then
+    let unknown = "???? ???? ???? ???? ???? ???? ???? ???? |????????????????|";
+    assert_eq!(4, dump.len(), "{dump:?}");
+    assert_eq!(format!("000000000000: {unknown}"), dump[0]);
+    assert_eq!("*", dump[1]);
+    assert_eq!(format!("{:012X}: {unknown}", first - 0x10), dump[2]);
+    assert!(dump[3].starts_with(&format!("{first:012X}: ")));
+}
@@ -0,0 +1,128 @@
+use hxd::{
+    options::{ElisionMarker, HexdOptionsBuilder, Spacing},
+    rows::{ElidedRun, Line},
+    AsHexd,
+};
+
+/// Render the lines of `rows` the way a text dump would.
+fn render<R: hxd::reader::ReadBytes>(mut rows: hxd::rows::Rows<R>) -> String {
+    let mut out = String::new();
+    while let Some(line) = rows.next() {
+        match line.unwrap() {
+            Line::Row(row) => out += &rows.render(&row),
+            Line::Elided(run) => out += &rows.render_elision(&run),
+        }
+        out += "\n";
+    }
+    out
+}
+
+#[test]
+fn rendered_rows_match_the_dump() {
+    let v = [vec![7u8; 5], vec![0u8; 70], b"tail".to_vec(), vec![0u8; 40]].concat();
+
+    let dump = v.hexd().dump_to::<String>();
+    similar_asserts::assert_eq!(dump, render(v.hexd().rows()));
+
+    let hexd = || {
+        v.hexd()
+            .range(3..)
+            .ungrouped(8, Spacing::Normal)
+            .relative_offset(0x100)
+    };
+    similar_asserts::assert_eq!(hexd().dump_to::<String>(), render(hexd().rows()));
+
+    let hexd = || {
+        v.hexd()
+            .elision_marker(ElisionMarker::Template("{start}..{end}".into()))
+            .relative_offset(0x100)
+    };
+    similar_asserts::assert_eq!(hexd().dump_to::<String>(), render(hexd().rows()));
+}
+
+#[test]
+fn alignment_padding() {
+    // Given
+    let v = (0..32).collect::<Vec<u8>>();
+
+    // When
+    let rows = v
+        .hexd()
+        .range(5..27)
+        .ungrouped(8, Spacing::Normal)
+        .rows()
+        .map(|line| match line.unwrap() {
+            Line::Row(row) => (
+                row.index(),
+                row.bytes().to_vec(),
+                row.leading_padding(),
+                row.trailing_padding(),
+            ),
+            Line::Elided(_) => panic!("no rows are identical"),
+        })
+        .collect::<Vec<_>>();
+
+    // Then
+    assert_eq!(
+        rows,
+        vec![
+            (0, vec![5, 6, 7], 5, 0),
+            (8, (8..16).collect(), 0, 0),
+            (16, (16..24).collect(), 0, 0),
+            (24, vec![24, 25, 26], 0, 5),
+        ]
+    );
+}
+
+#[test]
+fn elided_runs() {
+    // Given
+    let v = [vec![1u8; 4], vec![0u8; 24], vec![2u8; 4], vec![0u8; 12]].concat();
+
+    // When
+    let lines = v
+        .hexd()
+        .ungrouped(4, Spacing::Normal)
+        .rows()
+        .map(|line| match line.unwrap() {
+            Line::Row(row) => format!("row {}", row.index()),
+            Line::Elided(run) => format!("{run:?}"),
+        })
+        .collect::<Vec<_>>();
+
+    // Then
+    let run = |start, end, rows| format!("{:?}", ElidedRun { start, end, rows });
+    assert_eq!(
+        lines,
+        vec![
+            "row 0".to_string(),
+            "row 4".to_string(),
+            run(8, 24, 4),
+            "row 24".to_string(),
+            "row 28".to_string(),
+            "row 32".to_string(),
+            run(36, 40, 1),
+            "row 40".to_string(),
+        ]
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn fallible_rows() {
+    use hxd::IntoFallibleHexd;
+
+    let input = std::io::Cursor::new(b"rows from a reader".to_vec());
+
+    let bytes = input
+        .hexd()
+        .rows()
+        .map(|line| match line.unwrap() {
+            Line::Row(row) => row.bytes().to_vec(),
+            Line::Elided(_) => vec![],
+        })
+        .collect::<Vec<_>>()
+        .concat();
+
+    assert_eq!(bytes, b"rows from a reader");
+}
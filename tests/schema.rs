@@ -0,0 +1,188 @@
+use hxd::{
+    schema::{Field, Schema, SchemaError, Value},
+    AsHexd,
+};
+use indoc::indoc;
+
+fn values(schema: &str, bytes: &[u8]) -> Vec<(String, Value)> {
+    Schema::parse(schema)
+        .unwrap()
+        .decode(bytes)
+        .unwrap()
+        .into_iter()
+        .map(|f| (f.path, f.value))
+        .collect()
+}
+
+#[test]
+fn primitives() {
+    // Given
+    let schema = "a: u8, b: i8, c: u16be, d: i16le, e: u32le, f: i64be, g: f32le, h: f64be";
+    let bytes = [
+        vec![0xff, 0xff, 0x12, 0x34, 0xfe, 0xff, 0x78, 0x56, 0x34, 0x12],
+        (-2i64).to_be_bytes().to_vec(),
+        1.5f32.to_le_bytes().to_vec(),
+        (-0.25f64).to_be_bytes().to_vec(),
+    ]
+    .concat();
+
+    // When
+    let fields = Schema::parse(schema).unwrap().decode(&bytes).unwrap();
+
+    // Then
+    let decoded = fields
+        .iter()
+        .map(|f| (f.path.as_str(), f.range.clone(), f.value.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        decoded,
+        vec![
+            ("a", 0..1, Value::Unsigned(255)),
+            ("b", 1..2, Value::Signed(-1)),
+            ("c", 2..4, Value::Unsigned(0x1234)),
+            ("d", 4..6, Value::Signed(-2)),
+            ("e", 6..10, Value::Unsigned(0x12345678)),
+            ("f", 10..18, Value::Signed(-2)),
+            ("g", 18..22, Value::F32(1.5)),
+            ("h", 22..30, Value::F64(-0.25)),
+        ]
+    );
+}
+
+#[test]
+fn arrays() {
+    // Given
+    let schema = "
+        tag: [u8; 2],
+        count: u8,
+        sizes: [u16le; count],
+        rest: [u8; *],
+    ";
+
+    // When
+    let fields = values(schema, b"ok\x02\x01\x00\x02\x00xyz");
+
+    // Then
+    assert_eq!(
+        fields,
+        vec![
+            ("tag".to_string(), Value::Bytes(b"ok".to_vec())),
+            ("count".to_string(), Value::Unsigned(2)),
+            ("sizes[0]".to_string(), Value::Unsigned(1)),
+            ("sizes[1]".to_string(), Value::Unsigned(2)),
+            ("rest".to_string(), Value::Bytes(b"xyz".to_vec())),
+        ]
+    );
+}
+
+#[test]
+fn nested_and_repeated_structs() {
+    // Given
+    let schema = "
+        header: { version: u8, flags: u8 },
+        records: [{ len: u8, data: [u8; len] }; *],
+    ";
+
+    // When
+    let fields = values(schema, b"\x01\x80\x02hi\x00\x03abc");
+
+    // Then
+    assert_eq!(
+        fields,
+        vec![
+            ("header.version".to_string(), Value::Unsigned(1)),
+            ("header.flags".to_string(), Value::Unsigned(0x80)),
+            ("records[0].len".to_string(), Value::Unsigned(2)),
+            ("records[0].data".to_string(), Value::Bytes(b"hi".to_vec())),
+            ("records[1].len".to_string(), Value::Unsigned(0)),
+            ("records[1].data".to_string(), Value::Bytes(vec![])),
+            ("records[2].len".to_string(), Value::Unsigned(3)),
+            ("records[2].data".to_string(), Value::Bytes(b"abc".to_vec())),
+        ]
+    );
+}
+
+#[test]
+fn counts_from_enclosing_structs() {
+    let schema = "n: u8, items: [{ id: u8, name: [u8; n] }; 2]";
+
+    let fields = values(schema, b"\x01\x0aA\x0bB");
+
+    assert_eq!(
+        fields[4],
+        ("items[1].name".to_string(), Value::Bytes(b"B".to_vec()))
+    );
+}
+
+#[test]
+fn field_display() {
+    let field = Field {
+        path: "magic".to_string(),
+        range: 0..4,
+        value: Value::Unsigned(0xcafebabe),
+    };
+    assert_eq!(field.to_string(), "magic = 3405691582 (0xCAFEBABE)");
+
+    assert_eq!(Value::Unsigned(7).to_string(), "7");
+    assert_eq!(Value::Signed(-40).to_string(), "-40");
+    assert_eq!(
+        Value::Bytes(b"a\"\n\x00".to_vec()).to_string(),
+        r#"b"a\"\n\x00""#
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(Schema::parse("a u8"), Err(SchemaError::Syntax(2)));
+    assert_eq!(Schema::parse(""), Err(SchemaError::Syntax(0)));
+    assert_eq!(Schema::parse("a: {}"), Err(SchemaError::Syntax(4)));
+    assert_eq!(Schema::parse("a: [u8; 2"), Err(SchemaError::Syntax(9)));
+    assert_eq!(
+        Schema::parse("a: u8, b: u32"),
+        Err(SchemaError::UnknownType(10))
+    );
+    assert_eq!(Schema::parse("a: u8le"), Err(SchemaError::UnknownType(3)));
+
+    let schema = Schema::parse("a: i8, b: [u8; a], c: [u8; z]").unwrap();
+    assert_eq!(
+        schema.decode(b"\xff"),
+        Err(SchemaError::InvalidCount("b".to_string()))
+    );
+
+    let schema = Schema::parse("a: u8, b: { c: u32be }").unwrap();
+    assert_eq!(
+        schema.decode(b"\x00\x01\x02"),
+        Err(SchemaError::Truncated("b.c".to_string()))
+    );
+    assert_eq!(
+        SchemaError::Truncated("b.c".to_string()).to_string(),
+        "b.c: not enough bytes"
+    );
+}
+
+#[test]
+fn annotated_dump() {
+    // Given
+    let schema: Schema =
+        "magic: [u8; 4], class: u8, data: u8, version: u8, pad: [u8; 9], kind: u16le"
+            .parse()
+            .unwrap();
+    let header = b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x03\x00";
+
+    // When
+    let dump = header
+        .hexd()
+        .annotations(schema.annotations(header).unwrap())
+        .dump_to::<String>();
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {r#"
+            00000000: 7F45 4C46 0201 0100 0000 0000 0000 0000 |.ELF............|  a: magic = b"\x7fELF", b: class = 2, c: data = 1, d: version = 1, e: pad = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00"
+                      aaaaaaaaa bbcc ddeeeeeeeeeeeeeeeeeeeeee  aaaabcdeeeeeeeee
+            00000010: 0300                                    |..              |  f: kind = 3
+                      ffff                                     ff
+        "#},
+        dump
+    );
+}
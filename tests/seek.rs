@@ -0,0 +1,118 @@
+#![cfg(feature = "std")]
+
+use std::{
+    fs,
+    io::{self, Cursor, Read, Seek, SeekFrom},
+};
+
+use hxd::{
+    options::{Footer, HexdOptionsBuilder},
+    reader::{ReadBytes, SeekReader},
+    AsHexd, FallibleHexd,
+};
+use indoc::indoc;
+
+/// A seekable stream which counts the bytes read from it.
+struct CountingCursor {
+    inner: Cursor<Vec<u8>>,
+    read: usize,
+}
+
+impl Read for CountingCursor {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n;
+        Ok(n)
+    }
+}
+
+impl Seek for CountingCursor {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn skip_seeks_instead_of_reading() {
+    // Given
+    let v: Vec<u8> = (0..=255u8).cycle().take(0x10000).collect();
+    let mut cursor = CountingCursor {
+        inner: Cursor::new(v.clone()),
+        read: 0,
+    };
+
+    // When
+    let dump = FallibleHexd::new(SeekReader::new(&mut cursor).unwrap())
+        .range(0xfff0..)
+        .dump_to::<String>()
+        .unwrap();
+
+    // Then
+    assert_eq!(v.hexd().range(0xfff0..).dump_to::<String>(), dump);
+    assert_eq!(16, cursor.read);
+}
+
+#[test]
+fn hint_is_the_stream_length() {
+    // Given
+    let mut cursor = Cursor::new(vec![0u8; 0x1234]);
+    cursor.seek(SeekFrom::Start(0x34)).unwrap();
+
+    // When
+    let reader = SeekReader::new(cursor).unwrap();
+
+    // Then
+    assert_eq!(Some(0x1200), reader.total_byte_hint());
+}
+
+#[test]
+fn ranges_past_the_end_are_empty() {
+    // Given
+    let v = b"0123456789".to_vec();
+    let dump = |range: std::ops::Range<usize>| {
+        let reader = SeekReader::new(Cursor::new(v.clone())).unwrap();
+        FallibleHexd::new(reader)
+            .range(range)
+            .footer(Footer::Summary(None))
+            .dump_to::<String>()
+            .unwrap()
+    };
+
+    // Then
+    similar_asserts::assert_eq!(
+        indoc! {"
+            00000000:                     3839                |        89      |
+            total: 2 bytes, range: 00000008-0000000A, elided: 0 rows (0 bytes)
+        "},
+        dump(8..20)
+    );
+    similar_asserts::assert_eq!(
+        "total: 0 bytes, range: 00000064-00000064, elided: 0 rows (0 bytes)\n",
+        dump(100..200)
+    );
+}
+
+#[test]
+fn files_are_read_from_their_current_position() {
+    // Given
+    let path = std::env::temp_dir().join(format!("hxd-seek-{}", std::process::id()));
+    fs::write(&path, b"skip this, dump that").unwrap();
+    let mut file = fs::File::open(&path).unwrap();
+    file.seek(SeekFrom::Start(11)).unwrap();
+
+    // When
+    let reader = SeekReader::from_file(file).unwrap();
+    let dump = FallibleHexd::new(reader)
+        .range(5..)
+        .dump_to::<String>()
+        .unwrap();
+    let opened = SeekReader::open(&path).unwrap().total_byte_hint();
+    fs::remove_file(&path).unwrap();
+
+    // Then
+    assert_eq!(
+        "00000000:             74 6861 74                  |     that       |\n",
+        dump
+    );
+    assert_eq!(Some(20), opened);
+}
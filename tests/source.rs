@@ -0,0 +1,120 @@
+use hxd::{
+    options::{HexdOptionsBuilder, SourceFormat, SourceLanguage},
+    AsHexd, IntoHexd,
+};
+use indoc::indoc;
+
+const TEXT: &[u8] = b"The Nellie, a cruising yawl";
+
+#[test]
+fn c_array() {
+    let dump = TEXT.hexd().c_array("nellie").dump_to::<String>();
+
+    similar_asserts::assert_eq!(
+        indoc! {"
+            unsigned char nellie[] = {
+              0x54, 0x68, 0x65, 0x20, 0x4E, 0x65, 0x6C, 0x6C, 0x69, 0x65, 0x2C, 0x20,
+              0x61, 0x20, 0x63, 0x72, 0x75, 0x69, 0x73, 0x69, 0x6E, 0x67, 0x20, 0x79,
+              0x61, 0x77, 0x6C
+            };
+            unsigned int nellie_len = 27;
+        "},
+        dump
+    );
+}
+
+#[test]
+fn rust_array() {
+    let dump = TEXT
+        .hexd()
+        .rust_array("NELLIE")
+        .uppercase(false)
+        .range(..20)
+        .dump_to::<String>();
+
+    similar_asserts::assert_eq!(
+        indoc! {"
+            const NELLIE: [u8; NELLIE_LEN] = [
+                0x54, 0x68, 0x65, 0x20, 0x4e, 0x65, 0x6c, 0x6c, 0x69, 0x65, 0x2c, 0x20,
+                0x61, 0x20, 0x63, 0x72, 0x75, 0x69, 0x73, 0x69,
+            ];
+            const NELLIE_LEN: usize = 20;
+        "},
+        dump
+    );
+}
+
+#[test]
+fn rust_bytes() {
+    let format = SourceFormat::new(SourceLanguage::RustBytes, "YAWL").bytes_per_line(4);
+
+    let dump = TEXT.hexd().format(format).range(23..).dump_to::<String>();
+
+    similar_asserts::assert_eq!(
+        indoc! {r#"
+            const YAWL: &[u8; YAWL_LEN] = b"\
+                \x79\x61\x77\x6C";
+            const YAWL_LEN: usize = 4;
+        "#},
+        dump
+    );
+}
+
+#[test]
+fn python_bytes() {
+    let format = SourceFormat::new(SourceLanguage::PythonBytes, "nellie").bytes_per_line(8);
+
+    let dump = TEXT.hexd().format(format).range(4..20).dump_to::<String>();
+
+    similar_asserts::assert_eq!(
+        indoc! {r#"
+            nellie = (
+                b"\x4E\x65\x6C\x6C\x69\x65\x2C\x20"
+                b"\x61\x20\x63\x72\x75\x69\x73\x69"
+            )
+        "#},
+        dump
+    );
+}
+
+#[test]
+fn empty_input() {
+    let empty: &[u8] = &[];
+
+    assert_eq!(
+        "unsigned char e[] = {\n};\nunsigned int e_len = 0;\n",
+        empty.hexd().c_array("e").dump_to::<String>()
+    );
+    assert_eq!(
+        "const E: &[u8; E_LEN] = b\"\\\n\";\nconst E_LEN: usize = 0;\n",
+        empty.hexd().rust_bytes("E").dump_to::<String>()
+    );
+    assert_eq!(
+        "e = (\n    b\"\"\n)\n",
+        empty.hexd().python_bytes("e").dump_to::<String>()
+    );
+}
+
+#[test]
+fn streams_long_inputs() {
+    // Given
+    let format = SourceFormat::new(SourceLanguage::C, "ramp").bytes_per_line(256);
+
+    // When
+    let dump = (0..=255u8)
+        .cycle()
+        .take(1000)
+        .hexd()
+        .format(format)
+        .dump_to::<Vec<String>>();
+
+    // Then
+    assert_eq!(dump[0], "unsigned char ramp[] = {\n");
+    assert_eq!(dump[1].len(), 2 + 256 * 6);
+    let last_line = (0..232u8)
+        .map(|b| format!("0x{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    assert_eq!(dump[4], format!("  {last_line}\n"));
+    assert_eq!(dump[6], "unsigned int ramp_len = 1000;\n");
+}
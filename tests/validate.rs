@@ -0,0 +1,157 @@
+use hxd::{
+    options::{GroupSize, HexdOptions, HexdOptionsBuilder, HexdRange, OptionsError, Spacing},
+    AsHexd,
+};
+
+#[test]
+fn default_options_are_valid() {
+    assert_eq!(Ok(()), HexdOptions::default().validate());
+    assert_eq!(Ok(()), HexdOptions::default().plain(None).validate());
+}
+
+#[test]
+fn empty_rows_are_invalid() {
+    let dump = [0u8; 16]
+        .hexd()
+        .ungrouped(0, Spacing::Normal)
+        .try_dump_to::<String>();
+    let grouped = [0u8; 16]
+        .hexd()
+        .grouped((GroupSize::Int, Spacing::None), (0, Spacing::Normal))
+        .try_dump_to::<String>();
+
+    assert_eq!(Err(OptionsError::EmptyRow), dump);
+    assert_eq!(Err(OptionsError::EmptyRow), grouped);
+}
+
+#[test]
+fn plain_lines_must_not_be_empty() {
+    let dump = [0u8; 16].hexd().plain(Some(0)).try_dump_to::<String>();
+
+    assert_eq!(Err(OptionsError::EmptyRow), dump);
+}
+
+#[test]
+fn plain_lines_ignore_the_grouping() {
+    let options = HexdOptions::default().ungrouped(0, Spacing::Normal);
+    let dump = [0xABu8; 4]
+        .hexd()
+        .ungrouped(0, Spacing::Normal)
+        .plain(Some(3))
+        .try_dump_to::<String>();
+
+    assert_eq!(Ok(()), options.clone().plain(None).validate());
+    assert_eq!(Ok(()), options.plain(Some(40)).validate());
+    assert_eq!(Ok("ABABAB\nAB\n".to_string()), dump);
+}
+
+#[test]
+fn rows_wider_than_the_maximum_are_invalid() {
+    let width = HexdOptions::MAX_ROW_WIDTH;
+    let input = vec![0u8; 4];
+
+    let widest = input
+        .hexd()
+        .ungrouped(width, Spacing::None)
+        .try_dump_to::<String>();
+    let too_wide = input
+        .hexd()
+        .grouped((GroupSize::ULong, Spacing::None), (width, Spacing::Normal))
+        .try_dump_to::<String>();
+
+    assert!(widest.unwrap().starts_with("00000000: 00000000 "));
+    assert_eq!(
+        Err(OptionsError::RowTooWide {
+            width: width * 16,
+            max: width
+        }),
+        too_wide
+    );
+}
+
+#[test]
+fn reversed_ranges_are_invalid() {
+    // Given
+    let (start, end) = (0x30, 0x10);
+    let range = HexdRange::new(start..end);
+
+    // When
+    let dump = (0..0x40u8)
+        .collect::<Vec<_>>()
+        .hexd()
+        .range(start..end)
+        .try_dump_to::<String>();
+
+    // Then
+    assert_eq!(Some(0), range.length());
+    assert_eq!(
+        Err(OptionsError::ReversedRange {
+            start: 0x30,
+            end: 0x10
+        }),
+        dump
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn fallible_dumps_separate_option_and_read_errors() {
+    use hxd::{IntoFallibleHexd, TryDumpError};
+
+    // Given
+    struct Broken;
+    impl std::io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    // When
+    let invalid = Broken
+        .hexd()
+        .ungrouped(0, Spacing::None)
+        .try_dump_to::<String>();
+    let broken = Broken.hexd().try_dump_to::<String>();
+    let (start, end) = (2, 1);
+    let mut out = Vec::new();
+    let io = Broken.hexd().range(start..end).try_dump_io(&mut out);
+
+    // Then
+    assert!(matches!(
+        invalid,
+        Err(TryDumpError::Options(OptionsError::EmptyRow))
+    ));
+    assert!(matches!(broken, Err(TryDumpError::Dump(_))));
+    assert!(matches!(
+        io,
+        Err(TryDumpError::Options(OptionsError::ReversedRange { .. }))
+    ));
+    assert!(out.is_empty());
+}
+
+#[test]
+fn index_widths_must_not_be_reversed() {
+    let options = HexdOptions::default();
+
+    assert_eq!(Ok(()), options.clone().index_width(4, Some(4)).validate());
+    assert_eq!(
+        Err(OptionsError::IndexWidth { min: 8, max: 6 }),
+        options.clone().index_width(8, Some(6)).validate()
+    );
+    assert_eq!(
+        Err(OptionsError::IndexWidth { min: 0, max: 0 }),
+        options.index_width(0, Some(0)).validate()
+    );
+}
+
+#[test]
+fn errors_describe_the_problem() {
+    assert_eq!(
+        "the range ends (0x10) before it starts (0x30)",
+        OptionsError::ReversedRange {
+            start: 0x30,
+            end: 0x10
+        }
+        .to_string()
+    );
+}